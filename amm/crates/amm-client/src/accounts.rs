@@ -0,0 +1,11 @@
+use amm::state::{Amm, Pool};
+use anchor_lang::{AccountDeserialize, Result};
+
+// both check the account discriminator, so a pool can't be read as an amm
+pub fn deserialize_amm(mut data: &[u8]) -> Result<Amm> {
+    Amm::try_deserialize(&mut data)
+}
+
+pub fn deserialize_pool(mut data: &[u8]) -> Result<Pool> {
+    Pool::try_deserialize(&mut data)
+}
@@ -0,0 +1,240 @@
+use amm::state::CurveType;
+use anchor_lang::{
+    prelude::Pubkey, solana_program::instruction::Instruction, system_program, InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::associated_token;
+
+use crate::pda::{amm_address, PoolKeys};
+
+pub fn create_amm(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    id: Pubkey,
+    fee: u16,
+    protocol_fee: u16,
+) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::CreateAmm {
+            amm: amm_address(&id),
+            admin: *admin,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: amm::instruction::CreateAmm {
+            id,
+            fee,
+            protocol_fee,
+        }
+        .data(),
+    }
+}
+
+pub fn create_pool(
+    keys: &PoolKeys,
+    payer: &Pubkey,
+    curve_type: CurveType,
+    amp: u64,
+) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::CreatePool {
+            amm: keys.amm,
+            pool: keys.pool,
+            pool_authority: keys.pool_authority,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            mint_liqudity: keys.mint_liquidity,
+            locked_liquidity: keys.locked_liquidity,
+            pool_account_a: keys.pool_account_a,
+            pool_account_b: keys.pool_account_b,
+            payer: *payer,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        }
+        .to_account_metas(None),
+        data: amm::instruction::CreatePool { curve_type, amp }.data(),
+    }
+}
+
+// with a deadline these build the _with_deadline instructions, without one
+// the data stays what clients sent before deadlines existed
+pub fn deposit_liquidity(
+    keys: &PoolKeys,
+    depositor: &Pubkey,
+    payer: &Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    min_liquidity_out: u64,
+    deadline: Option<i64>,
+) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::DepositLiquidity {
+            amm: keys.amm,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            pool: keys.pool,
+            pool_authority: keys.pool_authority,
+            mint_liquidity: keys.mint_liquidity,
+            locked_liquidity: keys.locked_liquidity,
+            pool_account_a: keys.pool_account_a,
+            pool_account_b: keys.pool_account_b,
+            depositer_mint_lp_ata: keys.user_account(depositor, &keys.mint_liquidity),
+            depositer_mint_a_ata: keys.user_account(depositor, &keys.mint_a),
+            depositer_mint_b_ata: keys.user_account(depositor, &keys.mint_b),
+            depositor: *depositor,
+            payer: *payer,
+            system_program: system_program::ID,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        }
+        .to_account_metas(None),
+        data: match deadline {
+            None => amm::instruction::DepositLiquidity {
+                amount_a,
+                amount_b,
+                min_liquidity_out,
+            }
+            .data(),
+            Some(deadline) => amm::instruction::DepositLiquidityWithDeadline {
+                amount_a,
+                amount_b,
+                min_liquidity_out,
+                deadline,
+            }
+            .data(),
+        },
+    }
+}
+
+// migrates a pool created before the minimum liquidity was locked
+pub fn lock_minimum_liquidity(keys: &PoolKeys, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::LockMinimumLiquidity {
+            amm: keys.amm,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            pool: keys.pool,
+            pool_authority: keys.pool_authority,
+            mint_liquidity: keys.mint_liquidity,
+            locked_liquidity: keys.locked_liquidity,
+            pool_account_a: keys.pool_account_a,
+            pool_account_b: keys.pool_account_b,
+            payer: *payer,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        }
+        .to_account_metas(None),
+        data: amm::instruction::LockMinimumLiquidity {}.data(),
+    }
+}
+
+pub fn swap_tokens(
+    keys: &PoolKeys,
+    trader: &Pubkey,
+    payer: &Pubkey,
+    swap_a: bool,
+    input_amount: u64,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::SwapExactTokensForTokens {
+            amm: keys.amm,
+            pool: keys.pool,
+            pool_authority: keys.pool_authority,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            pool_account_a: keys.pool_account_a,
+            pool_account_b: keys.pool_account_b,
+            trader_account_a: keys.user_account(trader, &keys.mint_a),
+            trader_account_b: keys.user_account(trader, &keys.mint_b),
+            trader: *trader,
+            payer: *payer,
+            system_program: system_program::ID,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            associated_token_program: associated_token::ID,
+        }
+        .to_account_metas(None),
+        data: match deadline {
+            None => amm::instruction::SwapTokens {
+                swap_a,
+                input_amount,
+                min_output_amount,
+            }
+            .data(),
+            Some(deadline) => amm::instruction::SwapTokensWithDeadline {
+                swap_a,
+                input_amount,
+                min_output_amount,
+                deadline,
+            }
+            .data(),
+        },
+    }
+}
+
+pub fn withdraw_liquidity(
+    keys: &PoolKeys,
+    depositor: &Pubkey,
+    payer: &Pubkey,
+    amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: Option<i64>,
+) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: amm::accounts::WithdrawLiquidity {
+            amm: keys.amm,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            mint_liquidity: keys.mint_liquidity,
+            pool: keys.pool,
+            locked_liquidity: keys.locked_liquidity,
+            pool_authority: keys.pool_authority,
+            pool_token_account_a: keys.pool_account_a,
+            pool_token_account_b: keys.pool_account_b,
+            depositer_account_token_lp: keys.user_account(depositor, &keys.mint_liquidity),
+            depositer_account_token_a: keys.user_account(depositor, &keys.mint_a),
+            depositer_account_token_b: keys.user_account(depositor, &keys.mint_b),
+            depositer: *depositor,
+            payer: *payer,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: match deadline {
+            None => amm::instruction::WithdrawLiquidity {
+                amount,
+                min_amount_a,
+                min_amount_b,
+            }
+            .data(),
+            Some(deadline) => amm::instruction::WithdrawLiquidityWithDeadline {
+                amount,
+                min_amount_a,
+                min_amount_b,
+                deadline,
+            }
+            .data(),
+        },
+    }
+}
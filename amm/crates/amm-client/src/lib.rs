@@ -0,0 +1,13 @@
+// off-chain helpers for the amm program: addresses, instructions, account
+// decoding and quotes that follow the on-chain math
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use amm::{
+    state::{Amm, CurveType, Pool},
+    ID as PROGRAM_ID,
+};
+pub use amm_math::{DepositQuote, SwapQuote, WithdrawQuote};
+pub use pda::PoolKeys;
@@ -0,0 +1,160 @@
+use amm::constants::{AUTHORITY_SEED, LIQUIDITY_SEED, LOCKED_LIQUIDITY_SEED};
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+pub fn amm_address(id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[id.as_ref()], &amm::ID).0
+}
+
+pub fn pool_address(amm: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[amm.as_ref(), mint_a.as_ref(), mint_b.as_ref()], &amm::ID).0
+}
+
+pub fn pool_authority_address(amm: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            amm.as_ref(),
+            mint_a.as_ref(),
+            mint_b.as_ref(),
+            AUTHORITY_SEED,
+        ],
+        &amm::ID,
+    )
+    .0
+}
+
+pub fn liquidity_mint_address(amm: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            amm.as_ref(),
+            mint_a.as_ref(),
+            mint_b.as_ref(),
+            LIQUIDITY_SEED,
+        ],
+        &amm::ID,
+    )
+    .0
+}
+
+pub fn locked_liquidity_address(amm: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            amm.as_ref(),
+            mint_a.as_ref(),
+            mint_b.as_ref(),
+            LOCKED_LIQUIDITY_SEED,
+        ],
+        &amm::ID,
+    )
+    .0
+}
+
+// pools only exist with their mints sorted, mint_a being the lower one
+pub fn sort_mints(mint_x: Pubkey, mint_y: Pubkey) -> (Pubkey, Pubkey) {
+    if mint_x < mint_y {
+        (mint_x, mint_y)
+    } else {
+        (mint_y, mint_x)
+    }
+}
+
+// every address a pool instruction needs, derived once
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub amm: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub token_program_a: Pubkey,
+    pub token_program_b: Pubkey,
+    // token program of the lp mint
+    pub token_program: Pubkey,
+    pub pool: Pubkey,
+    pub pool_authority: Pubkey,
+    pub mint_liquidity: Pubkey,
+    // pool owned lp account holding the minimum liquidity
+    pub locked_liquidity: Pubkey,
+    pub pool_account_a: Pubkey,
+    pub pool_account_b: Pubkey,
+}
+
+impl PoolKeys {
+    // keys of a pool whose mints and lp mint all belong to `token_program`
+    pub fn new(amm: Pubkey, mint_a: Pubkey, mint_b: Pubkey, token_program: Pubkey) -> Self {
+        Self::with_token_programs(
+            amm,
+            mint_a,
+            mint_b,
+            token_program,
+            token_program,
+            token_program,
+        )
+    }
+
+    // keys of a pool mixing token programs, `token_program` is the lp mint's
+    pub fn with_token_programs(
+        amm: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        token_program_a: Pubkey,
+        token_program_b: Pubkey,
+        token_program: Pubkey,
+    ) -> Self {
+        let pool_authority = pool_authority_address(&amm, &mint_a, &mint_b);
+        Self {
+            amm,
+            mint_a,
+            mint_b,
+            token_program_a,
+            token_program_b,
+            token_program,
+            pool: pool_address(&amm, &mint_a, &mint_b),
+            pool_authority,
+            mint_liquidity: liquidity_mint_address(&amm, &mint_a, &mint_b),
+            locked_liquidity: locked_liquidity_address(&amm, &mint_a, &mint_b),
+            pool_account_a: get_associated_token_address_with_program_id(
+                &pool_authority,
+                &mint_a,
+                &token_program_a,
+            ),
+            pool_account_b: get_associated_token_address_with_program_id(
+                &pool_authority,
+                &mint_b,
+                &token_program_b,
+            ),
+        }
+    }
+
+    // keys of the pool for a pair given in either order
+    pub fn for_pair(amm: Pubkey, mint_x: Pubkey, mint_y: Pubkey, token_program: Pubkey) -> Self {
+        let (mint_a, mint_b) = sort_mints(mint_x, mint_y);
+        Self::new(amm, mint_a, mint_b, token_program)
+    }
+
+    // the `swap_a` argument of the swap instructions for an input mint, None
+    // when the pool doesn't trade it
+    pub fn swap_a(&self, input_mint: &Pubkey) -> Option<bool> {
+        if *input_mint == self.mint_a {
+            Some(true)
+        } else if *input_mint == self.mint_b {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    // associated token account of `owner` for one of the pool's mints or its lp mint
+    pub fn user_account(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &self.mint_token_program(mint))
+    }
+
+    // token program of one of the pool's mints, the lp mint's for any other
+    pub fn mint_token_program(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.mint_a {
+            self.token_program_a
+        } else if *mint == self.mint_b {
+            self.token_program_b
+        } else {
+            self.token_program
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use amm::state::{Amm, Pool};
+use amm_math::{DepositQuote, SwapQuote, WithdrawQuote};
+
+// quotes from raw vault balances, the same numbers the program reads. the
+// math itself is amm-math, which the program runs too, and like it these
+// assume mints without transfer fees. `now` only matters for stableswap
+// pools with an amp ramp. like the amm-math quotes they return None wherever
+// the instruction would fail, vaults holding less than the accrued protocol
+// fees included
+
+// mirrors `swap_tokens`
+pub fn quote_swap(
+    amm: &Amm,
+    pool: &Pool,
+    vault_a: u64,
+    vault_b: u64,
+    swap_a: bool,
+    input: u64,
+    now: i64,
+) -> Option<SwapQuote> {
+    let (reserve_in, reserve_out) = reserves_in_out(pool, vault_a, vault_b, swap_a)?;
+    amm_math::quote_exact_input(
+        pool.curve(now),
+        amm.fee,
+        amm.protocol_fee,
+        reserve_in,
+        reserve_out,
+        input,
+    )
+}
+
+// mirrors `swap_tokens_for_exact_tokens`
+pub fn quote_swap_exact_output(
+    amm: &Amm,
+    pool: &Pool,
+    vault_a: u64,
+    vault_b: u64,
+    swap_a: bool,
+    output: u64,
+    now: i64,
+) -> Option<SwapQuote> {
+    let (reserve_in, reserve_out) = reserves_in_out(pool, vault_a, vault_b, swap_a)?;
+    amm_math::quote_exact_output(
+        pool.curve(now),
+        amm.fee,
+        amm.protocol_fee,
+        reserve_in,
+        reserve_out,
+        output,
+    )
+}
+
+// mirrors `deposit_liquidity`
+pub fn quote_deposit(
+    pool: &Pool,
+    vault_a: u64,
+    vault_b: u64,
+    lp_supply: u64,
+    amount_a: u64,
+    amount_b: u64,
+    now: i64,
+) -> Option<DepositQuote> {
+    let (reserve_a, reserve_b) = reserves(pool, vault_a, vault_b)?;
+    amm_math::quote_deposit(
+        pool.curve(now),
+        reserve_a,
+        reserve_b,
+        lp_supply,
+        amount_a,
+        amount_b,
+    )
+}
+
+// mirrors `withdraw_liquidity`
+pub fn quote_withdraw(
+    pool: &Pool,
+    vault_a: u64,
+    vault_b: u64,
+    lp_supply: u64,
+    amount: u64,
+) -> Option<WithdrawQuote> {
+    let (reserve_a, reserve_b) = reserves(pool, vault_a, vault_b)?;
+    amm_math::quote_withdraw(reserve_a, reserve_b, lp_supply, amount)
+}
+
+// accrued protocol fees sit in the vaults but aren't part of the reserves. a
+// stale snapshot can hold less than the fees, there are no reserves to quote
+pub fn reserves(pool: &Pool, vault_a: u64, vault_b: u64) -> Option<(u64, u64)> {
+    Some((
+        vault_a.checked_sub(pool.protocol_fees_a)?,
+        vault_b.checked_sub(pool.protocol_fees_b)?,
+    ))
+}
+
+fn reserves_in_out(pool: &Pool, vault_a: u64, vault_b: u64, swap_a: bool) -> Option<(u64, u64)> {
+    let (reserve_a, reserve_b) = reserves(pool, vault_a, vault_b)?;
+    if swap_a {
+        Some((reserve_a, reserve_b))
+    } else {
+        Some((reserve_b, reserve_a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amm::state::CurveType;
+
+    use super::*;
+
+    const FEE: u16 = 500;
+    const PROTOCOL_FEE: u16 = 1667;
+    const RESERVE_A: u64 = 4_000_000;
+    const RESERVE_B: u64 = 1_000_000;
+    // sqrt(RESERVE_A * RESERVE_B), the locked minimum liquidity included
+    const LP_SUPPLY: u64 = 2_000_000;
+    // fees accrued on the a side, sitting in the vault next to the reserves
+    const FEES_A: u64 = 1_000;
+    const VAULT_A: u64 = RESERVE_A + FEES_A;
+    const VAULT_B: u64 = RESERVE_B;
+
+    fn amm() -> Amm {
+        Amm {
+            fee: FEE,
+            protocol_fee: PROTOCOL_FEE,
+            ..Amm::default()
+        }
+    }
+
+    fn pool() -> Pool {
+        Pool {
+            protocol_fees_a: FEES_A,
+            ..Pool::default()
+        }
+    }
+
+    #[test]
+    fn protocol_fees_are_left_out_of_the_reserves() {
+        assert_eq!(
+            reserves(&pool(), VAULT_A, VAULT_B),
+            Some((RESERVE_A, RESERVE_B))
+        );
+        // a snapshot whose vault holds less than the fees has no reserves
+        assert_eq!(reserves(&pool(), FEES_A - 1, VAULT_B), None);
+        let pool = Pool {
+            protocol_fees_b: VAULT_B + 1,
+            ..pool()
+        };
+        assert_eq!(reserves(&pool, VAULT_A, VAULT_B), None);
+    }
+
+    #[test]
+    fn swap_quotes_follow_swap_tokens() {
+        let quote = quote_swap(&amm(), &pool(), VAULT_A, VAULT_B, true, 100_000, 0).unwrap();
+        // 95_000 * 1_000_000 / 4_095_000, rounded down
+        assert_eq!(
+            (quote.output, quote.fee, quote.protocol_fee),
+            (23_199, 5_000, 833)
+        );
+        assert_eq!(quote.reserve_in, RESERVE_A + 100_000 - 833);
+        assert_eq!(quote.reserve_out, RESERVE_B - 23_199);
+
+        // b in, the reserves swap sides
+        let quote = quote_swap(&amm(), &pool(), VAULT_A, VAULT_B, false, 100_000, 0).unwrap();
+        // 95_000 * 4_000_000 / 1_095_000, rounded down
+        assert_eq!(quote.output, 347_031);
+        assert_eq!(quote.reserve_out, RESERVE_A - 347_031);
+    }
+
+    #[test]
+    fn exact_output_quotes_follow_swap_tokens_for_exact_tokens() {
+        let quote =
+            quote_swap_exact_output(&amm(), &pool(), VAULT_A, VAULT_B, true, 10_000, 0).unwrap();
+        // ceil(10_000 * 4_000_000 / 990_000) = 40_405 after the fee, and
+        // ceil(40_405 * 10_000 / 9_500) before it
+        assert_eq!(quote.input, 42_532);
+        assert_eq!((quote.fee, quote.protocol_fee), (2_126, 354));
+        assert_eq!(quote.output, 10_000);
+
+        // the whole output reserve can't be bought
+        assert_eq!(
+            quote_swap_exact_output(&amm(), &pool(), VAULT_A, VAULT_B, true, RESERVE_B, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn stable_swap_quotes_use_the_ramped_amp() {
+        let pool = Pool {
+            curve_type: CurveType::StableSwap,
+            amp_initial: 10,
+            amp_target: 100,
+            amp_ramp_start_ts: 0,
+            amp_ramp_end_ts: 100,
+            ..pool()
+        };
+        let quote_at = |now, amp| {
+            let quote = quote_swap(&amm(), &pool, VAULT_A, VAULT_B, true, 100_000, now);
+            let expected = amm_math::quote_exact_input(
+                amm_math::Curve::StableSwap { amp },
+                FEE,
+                PROTOCOL_FEE,
+                RESERVE_A,
+                RESERVE_B,
+                100_000,
+            );
+            assert_eq!(quote, expected);
+            quote.unwrap().output
+        };
+        // halfway through the ramp the amp is halfway too
+        let (start, halfway, end) = (quote_at(0, 10), quote_at(50, 55), quote_at(100, 100));
+        assert!(start < halfway && halfway < end);
+    }
+
+    #[test]
+    fn liquidity_quotes_follow_deposit_and_withdraw() {
+        // the excess b is cut down to the 4:1 pool ratio
+        let deposit =
+            quote_deposit(&pool(), VAULT_A, VAULT_B, LP_SUPPLY, 40_000, 50_000, 0).unwrap();
+        assert_eq!((deposit.amount_a, deposit.amount_b), (40_000, 10_000));
+        assert_eq!(deposit.liquidity, 20_000);
+        assert_eq!(deposit.lp_supply, LP_SUPPLY + 20_000);
+
+        let withdraw = quote_withdraw(&pool(), VAULT_A, VAULT_B, LP_SUPPLY, 20_000).unwrap();
+        assert_eq!((withdraw.amount_a, withdraw.amount_b), (40_000, 10_000));
+        // the locked minimum liquidity never comes out
+        assert_eq!(
+            quote_withdraw(&pool(), VAULT_A, VAULT_B, LP_SUPPLY, LP_SUPPLY),
+            None
+        );
+    }
+
+    #[test]
+    fn vaults_below_the_protocol_fees_quote_nothing() {
+        let vault_a = FEES_A - 1;
+        assert_eq!(
+            quote_swap(&amm(), &pool(), vault_a, VAULT_B, true, 1_000, 0),
+            None
+        );
+        assert_eq!(
+            quote_swap_exact_output(&amm(), &pool(), vault_a, VAULT_B, false, 1, 0),
+            None
+        );
+        assert_eq!(
+            quote_deposit(&pool(), vault_a, VAULT_B, LP_SUPPLY, 1_000, 1_000, 0),
+            None
+        );
+        assert_eq!(
+            quote_withdraw(&pool(), vault_a, VAULT_B, LP_SUPPLY, 1_000),
+            None
+        );
+    }
+}
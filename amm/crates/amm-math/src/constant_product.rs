@@ -0,0 +1,99 @@
+use crate::{mul_div_ceil, MathError, MathResult};
+
+// output = taxed_input * reserve_out / (reserve_in + taxed_input)
+pub fn amount_out(taxed_input: u64, reserve_in: u64, reserve_out: u64) -> MathResult<u64> {
+    let denominator = reserve_in as u128 + taxed_input as u128;
+    if denominator == 0 {
+        return Err(MathError::InsufficientReserves);
+    }
+
+    // never more than reserve_out, so it fits
+    Ok((taxed_input as u128 * reserve_out as u128 / denominator) as u64)
+}
+
+// taxed_input = ceil((output * reserve_in) / (reserve_out - output))
+pub fn amount_in(output: u64, reserve_in: u64, reserve_out: u64) -> MathResult<u64> {
+    if output >= reserve_out {
+        return Err(MathError::InsufficientReserves);
+    }
+    mul_div_ceil(output, reserve_in, reserve_out - output)
+}
+
+// lp tokens for the first deposit, MINIMUM_LIQUIDITY included
+pub fn initial_liquidity(amount_a: u64, amount_b: u64) -> u64 {
+    // the square root of a product of two u64s fits in a u64
+    sqrt(amount_a as u128 * amount_b as u128) as u64
+}
+
+// floor of the square root, by newton's method
+fn sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    let mut x = value;
+    let mut y = x / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_out_rounds_down() {
+        assert_eq!(amount_out(1000, 4000, 1000), Ok(200));
+        // 2.5 out
+        assert_eq!(amount_out(1, 3, 10), Ok(2));
+    }
+
+    #[test]
+    fn amount_in_rounds_up() {
+        assert_eq!(amount_in(200, 4000, 1000), Ok(1000));
+        // 0.75 in
+        assert_eq!(amount_in(2, 3, 10), Ok(1));
+
+        // paying the rounded up input gets at least the output back
+        for output in [1, 7, 999, 123_456] {
+            let input = amount_in(output, 4_000_000, 1_000_000).unwrap();
+            assert!(amount_out(input, 4_000_000, 1_000_000).unwrap() >= output);
+        }
+    }
+
+    #[test]
+    fn empty_reserves() {
+        assert_eq!(amount_out(0, 0, 0), Err(MathError::InsufficientReserves));
+        assert_eq!(amount_out(5, 0, 0), Ok(0));
+        assert_eq!(amount_in(1, 10, 0), Err(MathError::InsufficientReserves));
+        assert_eq!(amount_in(10, 10, 10), Err(MathError::InsufficientReserves));
+    }
+
+    #[test]
+    fn extreme_reserves() {
+        assert_eq!(amount_out(u64::MAX, u64::MAX, u64::MAX), Ok(u64::MAX / 2));
+        assert_eq!(amount_out(u64::MAX, 1, u64::MAX), Ok(u64::MAX - 1));
+        assert_eq!(amount_in(1, u64::MAX, u64::MAX), Ok(2));
+    }
+
+    #[test]
+    fn amount_in_overflows() {
+        assert_eq!(
+            amount_in(u64::MAX - 1, u64::MAX, u64::MAX),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn initial_liquidity_is_the_floor_of_the_square_root() {
+        assert_eq!(initial_liquidity(4_000_000, 1_000_000), 2_000_000);
+        assert_eq!(initial_liquidity(2, 3), 2);
+        assert_eq!(initial_liquidity(0, 1000), 0);
+        assert_eq!(initial_liquidity(1, 1), 1);
+        assert_eq!(initial_liquidity(u64::MAX, u64::MAX), u64::MAX);
+        assert_eq!(initial_liquidity(u64::MAX, 1), (1 << 32) - 1);
+    }
+}
@@ -0,0 +1,141 @@
+// swap, deposit and withdraw math of the amm program, with no solana or anchor
+// dependencies. the program runs these exact functions, so quotes built on
+// them round the same way the chain does
+pub mod constant_product;
+pub mod liquidity;
+pub mod quote;
+pub mod stable_swap;
+
+pub use quote::{
+    price_impact_bps, quote_deposit, quote_exact_input, quote_exact_output, quote_withdraw,
+    DepositQuote, SwapQuote, WithdrawQuote,
+};
+pub use uint_types::{U256, U512};
+
+// kept out of the anchor prelude, the macro expects std's Result
+#[allow(clippy::all)]
+mod uint_types {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+
+    uint::construct_uint! {
+        pub struct U512(8);
+    }
+}
+
+// fees are in basis points
+pub const FEE_DENOMINATOR: u64 = 10000;
+
+// lp tokens of the first deposit that are locked in the pool forever, so it can
+// never be fully drained
+pub const MINIMUM_LIQUIDITY: u64 = 100;
+
+// why a computation has no answer, the program turns these into its own errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    // a result doesn't fit in a u64 (or D in a u128)
+    Overflow,
+    // the pool can't pay out that much, or holds nothing to price against
+    InsufficientReserves,
+    // the stableswap iterations didn't settle
+    NoConvergence,
+}
+
+pub type MathResult<T> = Result<T, MathError>;
+
+// the pricing curve of a pool, with the amp already ramped for stableswap pools
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    ConstantProduct,
+    StableSwap { amp: u64 },
+}
+
+impl Curve {
+    // output for an input that has already been taxed
+    pub fn amount_out(
+        self,
+        taxed_input: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> MathResult<u64> {
+        match self {
+            Curve::ConstantProduct => {
+                constant_product::amount_out(taxed_input, reserve_in, reserve_out)
+            }
+            Curve::StableSwap { amp } => {
+                stable_swap::amount_out(amp, taxed_input, reserve_in, reserve_out)
+            }
+        }
+    }
+
+    // taxed input needed to get `output` out
+    pub fn amount_in(self, output: u64, reserve_in: u64, reserve_out: u64) -> MathResult<u64> {
+        match self {
+            Curve::ConstantProduct => constant_product::amount_in(output, reserve_in, reserve_out),
+            Curve::StableSwap { amp } => {
+                stable_swap::amount_in(amp, output, reserve_in, reserve_out)
+            }
+        }
+    }
+
+    // whether the reserves after a swap are worth at least as much as before,
+    // k for constant product pools and D for stableswap pools
+    pub fn invariant_held(self, before: (u64, u64), after: (u64, u64)) -> MathResult<bool> {
+        match self {
+            Curve::ConstantProduct => {
+                Ok(after.0 as u128 * after.1 as u128 >= before.0 as u128 * before.1 as u128)
+            }
+            Curve::StableSwap { amp } => Ok(stable_swap::compute_d(amp, after.0, after.1)?
+                >= stable_swap::compute_d(amp, before.0, before.1)?),
+        }
+    }
+
+    // marginal price of the input token in output tokens, as a fraction
+    pub fn spot_price(self, reserve_in: u64, reserve_out: u64) -> MathResult<(U512, U512)> {
+        match self {
+            Curve::ConstantProduct => Ok((U512::from(reserve_out), U512::from(reserve_in))),
+            Curve::StableSwap { amp } => stable_swap::spot_price(amp, reserve_in, reserve_out),
+        }
+    }
+}
+
+// fee taken from a swap input, truncated in favor of the trader. fees are at
+// most FEE_DENOMINATOR, so it's never more than the input
+pub fn swap_fee(input: u64, fee: u16) -> u64 {
+    (input as u128 * fee as u128 / FEE_DENOMINATOR as u128) as u64
+}
+
+// part of a swap fee set aside for the admin
+pub fn protocol_fee(fee_amount: u64, protocol_fee: u16) -> u64 {
+    (fee_amount as u128 * protocol_fee as u128 / FEE_DENOMINATOR as u128) as u64
+}
+
+// input = ceil(taxed_input * 10000 / (10000 - fee)), rounding up in favor of the pool
+pub fn input_before_fee(taxed_input: u64, fee: u16) -> MathResult<u64> {
+    let kept = FEE_DENOMINATOR
+        .checked_sub(fee as u64)
+        .filter(|kept| *kept > 0)
+        .ok_or(MathError::Overflow)?;
+    to_u64((taxed_input as u128 * FEE_DENOMINATOR as u128).div_ceil(kept as u128))
+}
+
+// a * b / c, truncated. u64 products always fit in a u128
+pub(crate) fn mul_div(a: u64, b: u64, c: u64) -> MathResult<u64> {
+    if c == 0 {
+        return Err(MathError::InsufficientReserves);
+    }
+    to_u64(a as u128 * b as u128 / c as u128)
+}
+
+// a * b / c, rounded up
+pub(crate) fn mul_div_ceil(a: u64, b: u64, c: u64) -> MathResult<u64> {
+    if c == 0 {
+        return Err(MathError::InsufficientReserves);
+    }
+    to_u64((a as u128 * b as u128).div_ceil(c as u128))
+}
+
+pub(crate) fn to_u64(value: u128) -> MathResult<u64> {
+    u64::try_from(value).map_err(|_| MathError::Overflow)
+}
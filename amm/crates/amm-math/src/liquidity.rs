@@ -0,0 +1,175 @@
+use crate::{constant_product, mul_div, stable_swap, Curve, MathResult};
+
+// lp tokens of the first deposit are sqrt(a * b), so they carry the average
+// of the two mints' decimals
+pub fn lp_decimals(decimals_a: u8, decimals_b: u8) -> u8 {
+    ((decimals_a as u16 + decimals_b as u16) / 2) as u8
+}
+
+// the first deposit sets the pool ratio, later ones have to follow it
+pub fn is_empty(reserve_a: u64, reserve_b: u64, lp_supply: u64) -> bool {
+    reserve_a == 0 && reserve_b == 0 && lp_supply == 0
+}
+
+// amounts a deposit actually puts in once matched to the pool ratio, the side
+// given in excess is cut down
+pub fn balanced_amounts(
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> MathResult<(u64, u64)> {
+    // ideal_b = (amount_a * reserve_b) / reserve_a
+    let ideal_b = mul_div(amount_a, reserve_b, reserve_a)?;
+    if ideal_b <= amount_b {
+        Ok((amount_a, ideal_b))
+    } else {
+        // ideal_a = (amount_b * reserve_a) / reserve_b
+        Ok((mul_div(amount_b, reserve_a, reserve_b)?, amount_b))
+    }
+}
+
+// lp tokens for a deposit, on an empty pool MINIMUM_LIQUIDITY of them go to
+// the pool's locked account and the rest to the depositor
+pub fn deposit_liquidity(
+    curve: Curve,
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> MathResult<u64> {
+    match curve {
+        Curve::ConstantProduct if is_empty(reserve_a, reserve_b, lp_supply) => {
+            Ok(constant_product::initial_liquidity(amount_a, amount_b))
+        }
+        Curve::ConstantProduct => {
+            let liquidity_from_a = mul_div(amount_a, lp_supply, reserve_a)?;
+            let liquidity_from_b = mul_div(amount_b, lp_supply, reserve_b)?;
+            Ok(liquidity_from_a.min(liquidity_from_b))
+        }
+        // deposits still follow the pool ratio, so the share of D they add is
+        // the same share of the pool and nobody gets a free imbalanced swap
+        Curve::StableSwap { amp } => {
+            stable_swap::liquidity(amp, amount_a, amount_b, reserve_a, reserve_b, lp_supply)
+        }
+    }
+}
+
+// share of each reserve paid out for burning `amount` lp tokens out of `supply`
+pub fn withdraw_amounts(
+    amount: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    supply: u64,
+) -> MathResult<(u64, u64)> {
+    Ok((
+        mul_div(amount, reserve_a, supply)?,
+        mul_div(amount, reserve_b, supply)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MathError;
+
+    #[test]
+    fn lp_decimals_average_down() {
+        assert_eq!(lp_decimals(6, 9), 7);
+        assert_eq!(lp_decimals(9, 9), 9);
+        assert_eq!(lp_decimals(0, 1), 0);
+        assert_eq!(lp_decimals(u8::MAX, u8::MAX), u8::MAX);
+    }
+
+    #[test]
+    fn balanced_amounts_cut_the_excess_side_down() {
+        assert_eq!(balanced_amounts(4000, 5000, 4000, 1000), Ok((4000, 1000)));
+        assert_eq!(balanced_amounts(8000, 1000, 4000, 1000), Ok((4000, 1000)));
+        // 7/3 of b for one a, rounded down
+        assert_eq!(balanced_amounts(1, 10, 3, 7), Ok((1, 2)));
+        // 3/7 of a for one b, rounded down to nothing
+        assert_eq!(balanced_amounts(10, 1, 3, 7), Ok((0, 1)));
+    }
+
+    #[test]
+    fn balanced_amounts_need_reserves() {
+        assert_eq!(
+            balanced_amounts(1, 1, 0, 5),
+            Err(MathError::InsufficientReserves)
+        );
+        // an empty b side takes none of b
+        assert_eq!(balanced_amounts(1, 1, 5, 0), Ok((1, 0)));
+    }
+
+    #[test]
+    fn balanced_amounts_overflow() {
+        assert_eq!(
+            balanced_amounts(u64::MAX, u64::MAX, 1, u64::MAX),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn constant_product_deposits() {
+        let curve = Curve::ConstantProduct;
+        assert!(is_empty(0, 0, 0));
+        assert!(!is_empty(0, 0, 100));
+        assert_eq!(
+            deposit_liquidity(curve, 4_000_000, 1_000_000, 0, 0, 0),
+            Ok(2_000_000)
+        );
+        // 200.5 from a and 200 from b, the smaller share rounded down
+        assert_eq!(
+            deposit_liquidity(curve, 401, 100, 4000, 1000, 2000),
+            Ok(200)
+        );
+        assert_eq!(
+            deposit_liquidity(curve, 403, 101, 4000, 1000, 2000),
+            Ok(201)
+        );
+        assert_eq!(
+            deposit_liquidity(curve, u64::MAX, u64::MAX, 1, 1, u64::MAX),
+            Err(MathError::Overflow)
+        );
+        // lp tokens without reserves behind them can't be priced
+        assert_eq!(
+            deposit_liquidity(curve, 1, 1, 0, 0, 100),
+            Err(MathError::InsufficientReserves)
+        );
+    }
+
+    #[test]
+    fn stable_swap_deposits() {
+        let curve = Curve::StableSwap { amp: 100 };
+        // D of a balanced pool is the sum of its reserves
+        assert_eq!(deposit_liquidity(curve, 1000, 1000, 0, 0, 0), Ok(2000));
+        assert_eq!(
+            deposit_liquidity(curve, 1000, 1000, 1000, 1000, 2000),
+            Ok(2000)
+        );
+        assert_eq!(
+            deposit_liquidity(curve, u64::MAX, u64::MAX, 0, 0, 0),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn withdraw_amounts_round_down() {
+        assert_eq!(withdraw_amounts(1000, 4000, 1000, 2000), Ok((2000, 500)));
+        assert_eq!(withdraw_amounts(1, 3, 7, 2), Ok((1, 3)));
+        assert_eq!(withdraw_amounts(1, 1, 1, 2), Ok((0, 0)));
+        assert_eq!(
+            withdraw_amounts(1, 1, 1, 0),
+            Err(MathError::InsufficientReserves)
+        );
+        assert_eq!(
+            withdraw_amounts(u64::MAX, u64::MAX, u64::MAX, u64::MAX),
+            Ok((u64::MAX, u64::MAX))
+        );
+        assert_eq!(
+            withdraw_amounts(u64::MAX, u64::MAX, 1, 1),
+            Err(MathError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use crate::{
+    input_before_fee,
+    liquidity::{balanced_amounts, deposit_liquidity, is_empty, withdraw_amounts},
+    protocol_fee, swap_fee, Curve, FEE_DENOMINATOR, MINIMUM_LIQUIDITY, U512,
+};
+
+// quotes take the pool reserves, vault balances minus accrued protocol fees,
+// and assume mints without transfer fees. they return None wherever the
+// program would fail the instruction
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    // what the pool receives and pays out
+    pub input: u64,
+    pub output: u64,
+    // whole fee paid, protocol share included
+    pub fee: u64,
+    pub protocol_fee: u64,
+    // how far below the spot price the trade executes, fee left out
+    pub price_impact_bps: u64,
+    // reserves after the trade
+    pub reserve_in: u64,
+    pub reserve_out: u64,
+}
+
+// same steps as `swap_tokens`
+pub fn quote_exact_input(
+    curve: Curve,
+    fee: u16,
+    protocol_fee_rate: u16,
+    reserve_in: u64,
+    reserve_out: u64,
+    input: u64,
+) -> Option<SwapQuote> {
+    let fee_amount = swap_fee(input, fee);
+    let taxed_input = input - fee_amount;
+    let output = curve
+        .amount_out(taxed_input, reserve_in, reserve_out)
+        .ok()?;
+
+    swap_quote(
+        curve,
+        protocol_fee_rate,
+        reserve_in,
+        reserve_out,
+        input,
+        fee_amount,
+        output,
+    )
+}
+
+// same steps as `swap_tokens_for_exact_tokens`
+pub fn quote_exact_output(
+    curve: Curve,
+    fee: u16,
+    protocol_fee_rate: u16,
+    reserve_in: u64,
+    reserve_out: u64,
+    output: u64,
+) -> Option<SwapQuote> {
+    if output == 0 || output >= reserve_out {
+        return None;
+    }
+
+    let taxed_input = curve.amount_in(output, reserve_in, reserve_out).ok()?;
+    let input = input_before_fee(taxed_input, fee).ok()?;
+    // the fee is charged exactly like an exact input swap of `input` would be
+    let fee_amount = swap_fee(input, fee);
+
+    swap_quote(
+        curve,
+        protocol_fee_rate,
+        reserve_in,
+        reserve_out,
+        input,
+        fee_amount,
+        output,
+    )
+}
+
+fn swap_quote(
+    curve: Curve,
+    protocol_fee_rate: u16,
+    reserve_in: u64,
+    reserve_out: u64,
+    input: u64,
+    fee_amount: u64,
+    output: u64,
+) -> Option<SwapQuote> {
+    let protocol_fee_amount = protocol_fee(fee_amount, protocol_fee_rate);
+    Some(SwapQuote {
+        input,
+        output,
+        fee: fee_amount,
+        protocol_fee: protocol_fee_amount,
+        price_impact_bps: price_impact_bps(
+            curve,
+            input - fee_amount,
+            output,
+            reserve_in,
+            reserve_out,
+        )?,
+        reserve_in: reserve_in.checked_add(input)? - protocol_fee_amount,
+        reserve_out: reserve_out.checked_sub(output)?,
+    })
+}
+
+// 1 - (output / taxed_input) / spot_price, in basis points
+pub fn price_impact_bps(
+    curve: Curve,
+    taxed_input: u64,
+    output: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+) -> Option<u64> {
+    if taxed_input == 0 {
+        return Some(0);
+    }
+
+    let (numerator, denominator) = curve.spot_price(reserve_in, reserve_out).ok()?;
+    if numerator.is_zero() {
+        return None;
+    }
+
+    let executed = U512::from(output) * denominator * U512::from(FEE_DENOMINATOR)
+        / (U512::from(taxed_input) * numerator);
+    let executed = executed.min(U512::from(FEE_DENOMINATOR)).as_u64();
+    Some(FEE_DENOMINATOR - executed)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositQuote {
+    // what the pool takes after matching its ratio
+    pub amount_a: u64,
+    pub amount_b: u64,
+    // lp tokens minted to the depositor
+    pub liquidity: u64,
+    // reserves and lp supply after the deposit, the supply counts the locked
+    // minimum liquidity
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub lp_supply: u64,
+}
+
+// same steps as `deposit_liquidity`
+pub fn quote_deposit(
+    curve: Curve,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+    amount_a: u64,
+    amount_b: u64,
+) -> Option<DepositQuote> {
+    if amount_a == 0 || amount_b == 0 {
+        return None;
+    }
+
+    let pool_creation = is_empty(reserve_a, reserve_b, lp_supply);
+    let (amount_a, amount_b) = if pool_creation {
+        (amount_a, amount_b)
+    } else {
+        balanced_amounts(amount_a, amount_b, reserve_a, reserve_b).ok()?
+    };
+
+    let minted =
+        deposit_liquidity(curve, amount_a, amount_b, reserve_a, reserve_b, lp_supply).ok()?;
+    // the first deposit locks MINIMUM_LIQUIDITY in the pool
+    let liquidity = if pool_creation {
+        minted.checked_sub(MINIMUM_LIQUIDITY)?
+    } else {
+        minted
+    };
+    if liquidity == 0 {
+        return None;
+    }
+
+    Some(DepositQuote {
+        amount_a,
+        amount_b,
+        liquidity,
+        reserve_a: reserve_a.checked_add(amount_a)?,
+        reserve_b: reserve_b.checked_add(amount_b)?,
+        lp_supply: lp_supply.checked_add(minted)?,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawQuote {
+    pub amount_a: u64,
+    pub amount_b: u64,
+    // reserves and lp supply after the withdrawal
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub lp_supply: u64,
+}
+
+// same steps as `withdraw_liquidity`
+pub fn quote_withdraw(
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+    amount: u64,
+) -> Option<WithdrawQuote> {
+    // the locked minimum liquidity can never be burned
+    if amount > lp_supply.checked_sub(MINIMUM_LIQUIDITY)? {
+        return None;
+    }
+
+    let (amount_a, amount_b) = withdraw_amounts(amount, reserve_a, reserve_b, lp_supply).ok()?;
+    if amount_a == 0 || amount_b == 0 {
+        return None;
+    }
+
+    Some(WithdrawQuote {
+        amount_a,
+        amount_b,
+        reserve_a: reserve_a - amount_a,
+        reserve_b: reserve_b - amount_b,
+        lp_supply: lp_supply - amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEE: u16 = 500;
+    const PROTOCOL_FEE: u16 = 1667;
+    const RESERVE_A: u64 = 4_000_000;
+    const RESERVE_B: u64 = 1_000_000;
+
+    #[test]
+    fn exact_input_quote() {
+        let quote = quote_exact_input(
+            Curve::ConstantProduct,
+            FEE,
+            PROTOCOL_FEE,
+            RESERVE_A,
+            RESERVE_B,
+            100_000,
+        )
+        .unwrap();
+        // 5% fee, truncated in favor of the trader
+        assert_eq!(quote.fee, 5_000);
+        assert_eq!(quote.protocol_fee, 833);
+        // 95_000 * 1_000_000 / 4_095_000, rounded down
+        assert_eq!(quote.output, 23_199);
+        assert_eq!(quote.reserve_in, RESERVE_A + 100_000 - 833);
+        assert_eq!(quote.reserve_out, RESERVE_B - 23_199);
+        assert!(quote.price_impact_bps > 0 && quote.price_impact_bps < 300);
+    }
+
+    #[test]
+    fn exact_output_quote_rounds_up() {
+        for curve in [Curve::ConstantProduct, Curve::StableSwap { amp: 100 }] {
+            for output in [1, 999, 50_000] {
+                let quote =
+                    quote_exact_output(curve, FEE, PROTOCOL_FEE, RESERVE_A, RESERVE_B, output)
+                        .unwrap();
+                assert_eq!(quote.output, output);
+                // the quoted input buys at least the output as an exact input swap
+                let check =
+                    quote_exact_input(curve, FEE, PROTOCOL_FEE, RESERVE_A, RESERVE_B, quote.input)
+                        .unwrap();
+                assert!(check.output >= output, "{curve:?}, {output}");
+            }
+        }
+    }
+
+    #[test]
+    fn quotes_on_empty_or_drained_reserves() {
+        let curve = Curve::ConstantProduct;
+        assert_eq!(quote_exact_input(curve, FEE, PROTOCOL_FEE, 0, 0, 0), None);
+        assert_eq!(
+            quote_exact_output(curve, FEE, PROTOCOL_FEE, RESERVE_A, RESERVE_B, 0),
+            None
+        );
+        assert_eq!(
+            quote_exact_output(curve, FEE, PROTOCOL_FEE, RESERVE_A, RESERVE_B, RESERVE_B),
+            None
+        );
+        assert_eq!(price_impact_bps(curve, 0, 0, 0, 0), Some(0));
+    }
+
+    #[test]
+    fn quotes_that_overflow() {
+        let curve = Curve::ConstantProduct;
+        // the new input reserve doesn't fit
+        assert_eq!(
+            quote_exact_input(curve, FEE, PROTOCOL_FEE, u64::MAX, u64::MAX, u64::MAX),
+            None
+        );
+        // neither does the input for nearly all of the output reserve
+        assert_eq!(
+            quote_exact_output(curve, FEE, PROTOCOL_FEE, u64::MAX, u64::MAX, u64::MAX - 1),
+            None
+        );
+        // a 100% fee leaves nothing to divide by
+        assert_eq!(
+            quote_exact_output(curve, 10_000, 0, RESERVE_A, RESERVE_B, 1),
+            None
+        );
+    }
+
+    #[test]
+    fn deposit_quotes() {
+        let curve = Curve::ConstantProduct;
+        let first = quote_deposit(curve, 0, 0, 0, RESERVE_A, RESERVE_B).unwrap();
+        assert_eq!(first.liquidity, 2_000_000 - MINIMUM_LIQUIDITY);
+        assert_eq!(first.lp_supply, 2_000_000);
+
+        // the excess a is left out and the lp share rounds down
+        let quote = quote_deposit(curve, RESERVE_A, RESERVE_B, 2_000_000, 10_000, 1).unwrap();
+        assert_eq!((quote.amount_a, quote.amount_b), (4, 1));
+        assert_eq!(quote.liquidity, 2);
+
+        // a first deposit below the locked minimum mints nothing
+        assert_eq!(quote_deposit(curve, 0, 0, 0, 10, 10), None);
+        assert_eq!(
+            quote_deposit(curve, RESERVE_A, RESERVE_B, 2_000_000, 0, 1),
+            None
+        );
+        assert_eq!(
+            quote_deposit(curve, u64::MAX, u64::MAX, u64::MAX, u64::MAX, u64::MAX),
+            None
+        );
+    }
+
+    #[test]
+    fn withdraw_quotes() {
+        let quote = quote_withdraw(RESERVE_A, RESERVE_B, 2_000_000, 1_000_000).unwrap();
+        assert_eq!((quote.amount_a, quote.amount_b), (2_000_000, 500_000));
+        assert_eq!(quote.lp_supply, 1_000_000);
+
+        // shares round down, to nothing for dust
+        assert_eq!(quote_withdraw(3, 7, 1_000, 1), None);
+        // the locked minimum liquidity can't be burned
+        assert!(quote_withdraw(
+            RESERVE_A,
+            RESERVE_B,
+            2_000_000,
+            2_000_000 - MINIMUM_LIQUIDITY
+        )
+        .is_some());
+        assert_eq!(
+            quote_withdraw(
+                RESERVE_A,
+                RESERVE_B,
+                2_000_000,
+                2_000_000 - MINIMUM_LIQUIDITY + 1
+            ),
+            None
+        );
+        assert_eq!(quote_withdraw(RESERVE_A, RESERVE_B, 0, 0), None);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{MathError, MathResult, U256, U512};
+
+// newton's method converges in a handful of rounds for any sane pool
+const MAX_ITERATIONS: usize = 64;
+
+// two coin pools, A * n^n
+const N_COINS: u64 = 2;
+
+// stableswap invariant D for reserves x and y:
+// A * n^n * (x + y) + D = A * n^n * D + D^(n + 1) / (n^n * x * y)
+pub fn compute_d(amp: u64, reserve_a: u64, reserve_b: u64) -> MathResult<u128> {
+    let sum = U256::from(reserve_a) + U256::from(reserve_b);
+    if sum.is_zero() {
+        return Ok(0);
+    }
+    if reserve_a == 0 || reserve_b == 0 {
+        return Err(MathError::InsufficientReserves);
+    }
+
+    let ann = U256::from(amp) * U256::from(N_COINS * N_COINS);
+    let n = U256::from(N_COINS);
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        // D^3 / (4 * x * y) in a single division, truncating twice can
+        // make very imbalanced pools cycle instead of converging
+        let d_p = d * d * d / (U256::from(reserve_a) * U256::from(reserve_b) * n * n);
+
+        let d_prev = d;
+        let numerator = (ann * sum + d_p * n) * d;
+        let denominator = (ann - 1) * d + (n + 1) * d_p;
+        d = numerator / denominator;
+
+        if abs_diff(d, d_prev) <= U256::one() {
+            return u128::try_from(d).map_err(|_| MathError::Overflow);
+        }
+    }
+
+    Err(MathError::NoConvergence)
+}
+
+// the other reserve once one side of the pool holds `reserve`, keeping D fixed
+pub fn compute_y(amp: u64, reserve: u64, d: u128) -> MathResult<u64> {
+    if reserve == 0 {
+        return Err(MathError::InsufficientReserves);
+    }
+
+    let ann = U256::from(amp) * U256::from(N_COINS * N_COINS);
+    let n = U256::from(N_COINS);
+    let d = U256::from(d);
+    let x = U256::from(reserve);
+
+    let c = d * d / (x * n) * d / (ann * n);
+    let b = x + d / ann;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        let denominator = (y * n + b).checked_sub(d).ok_or(MathError::NoConvergence)?;
+        y = (y * y + c) / denominator;
+
+        if abs_diff(y, y_prev) <= U256::one() {
+            return u64::try_from(y).map_err(|_| MathError::Overflow);
+        }
+    }
+
+    Err(MathError::NoConvergence)
+}
+
+pub fn amount_out(
+    amp: u64,
+    taxed_input: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+) -> MathResult<u64> {
+    let d = compute_d(amp, reserve_in, reserve_out)?;
+    let new_reserve_in = reserve_in
+        .checked_add(taxed_input)
+        .ok_or(MathError::Overflow)?;
+    let new_reserve_out = compute_y(amp, new_reserve_in, d)?;
+
+    // one unit less, rounding in favor of the pool
+    Ok(reserve_out
+        .saturating_sub(new_reserve_out)
+        .saturating_sub(1))
+}
+
+// taxed input needed to get `output` out of the pool
+pub fn amount_in(amp: u64, output: u64, reserve_in: u64, reserve_out: u64) -> MathResult<u64> {
+    let d = compute_d(amp, reserve_in, reserve_out)?;
+    let new_reserve_out = reserve_out
+        .checked_sub(output)
+        .ok_or(MathError::InsufficientReserves)?;
+    let new_reserve_in = compute_y(amp, new_reserve_out, d)?;
+
+    // one unit more, rounding in favor of the pool
+    new_reserve_in
+        .checked_sub(reserve_in)
+        .ok_or(MathError::NoConvergence)?
+        .checked_add(1)
+        .ok_or(MathError::Overflow)
+}
+
+// lp tokens for a deposit, proportional to how much it grows D. on an empty
+// pool it's D itself, MINIMUM_LIQUIDITY included
+pub fn liquidity(
+    amp: u64,
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> MathResult<u64> {
+    let d0 = compute_d(amp, reserve_a, reserve_b)?;
+    let d1 = compute_d(
+        amp,
+        reserve_a.checked_add(amount_a).ok_or(MathError::Overflow)?,
+        reserve_b.checked_add(amount_b).ok_or(MathError::Overflow)?,
+    )?;
+    if d0 == 0 {
+        return u64::try_from(d1).map_err(|_| MathError::Overflow);
+    }
+
+    let growth = d1.checked_sub(d0).ok_or(MathError::NoConvergence)?;
+    let liquidity = U256::from(lp_supply) * U256::from(growth) / U256::from(d0);
+    u64::try_from(liquidity).map_err(|_| MathError::Overflow)
+}
+
+// marginal price of the input token in output tokens, from the slope of the
+// invariant: (4 * A * n^n * x^2 * y^2 + D^3 * y) / (4 * A * n^n * x^2 * y^2 + D^3 * x)
+pub fn spot_price(amp: u64, reserve_in: u64, reserve_out: u64) -> MathResult<(U512, U512)> {
+    let d = U512::from(compute_d(amp, reserve_in, reserve_out)?);
+    let ann = U512::from(amp) * U512::from(N_COINS * N_COINS);
+    let x = U512::from(reserve_in);
+    let y = U512::from(reserve_out);
+
+    let k = U512::from(4) * ann * x * x * y * y;
+    let d3 = d * d * d;
+    Ok((k + d3 * y, k + d3 * x))
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMP: u64 = 100;
+    // MAX_AMP of the program
+    const MAX_AMP: u64 = 1_000_000;
+
+    #[test]
+    fn d_of_a_balanced_pool_is_the_sum() {
+        for amp in [1, AMP, MAX_AMP] {
+            assert_eq!(compute_d(amp, 1_000_000, 1_000_000), Ok(2_000_000));
+            assert_eq!(compute_d(amp, u64::MAX, u64::MAX), Ok(2 * u64::MAX as u128));
+        }
+    }
+
+    #[test]
+    fn d_sits_between_constant_product_and_constant_sum() {
+        for amp in [1, AMP, MAX_AMP] {
+            let d = compute_d(amp, 4_000_000, 1_000_000).unwrap();
+            assert!(d > 4_000_000 && d < 5_000_000, "amp {amp}: {d}");
+        }
+        // a higher amp is closer to the constant sum
+        assert!(
+            compute_d(MAX_AMP, 4_000_000, 1_000_000).unwrap()
+                > compute_d(1, 4_000_000, 1_000_000).unwrap()
+        );
+    }
+
+    #[test]
+    fn d_converges_on_extreme_reserves() {
+        for amp in [1, AMP, MAX_AMP] {
+            for (x, y) in [(1, u64::MAX), (u64::MAX, 1), (1, 1), (1, 1_000_000_000)] {
+                let d = compute_d(amp, x, y).unwrap();
+                assert!(d <= x as u128 + y as u128, "amp {amp}, {x}/{y}: {d}");
+            }
+        }
+    }
+
+    #[test]
+    fn d_of_empty_reserves() {
+        assert_eq!(compute_d(AMP, 0, 0), Ok(0));
+        assert_eq!(compute_d(AMP, 0, 5), Err(MathError::InsufficientReserves));
+        assert_eq!(compute_d(AMP, 5, 0), Err(MathError::InsufficientReserves));
+    }
+
+    #[test]
+    fn y_converges_back_to_the_other_reserve() {
+        for amp in [1, AMP, MAX_AMP] {
+            for (x, y) in [
+                (1_000_000, 1_000_000),
+                (4_000_000, 1_000_000),
+                (1_000_000_000, 1_000),
+                (u64::MAX / 2, u64::MAX / 3),
+            ] {
+                let d = compute_d(amp, x, y).unwrap();
+                let computed = compute_y(amp, x, d).unwrap();
+                assert!(computed.abs_diff(y) <= 1, "amp {amp}, {x}/{y}: {computed}");
+            }
+
+            // solving for the big side of a lopsided pool still settles, but
+            // D is only exact to a unit, which y there magnifies
+            let d = compute_d(amp, 1_000, 1_000_000_000_000).unwrap();
+            assert!(compute_y(amp, 1_000, d).is_ok());
+        }
+        assert_eq!(
+            compute_y(AMP, 0, 1000),
+            Err(MathError::InsufficientReserves)
+        );
+    }
+
+    #[test]
+    fn swaps_round_in_favor_of_the_pool() {
+        for amp in [1, AMP, MAX_AMP] {
+            for input in [1, 1000, 1_000_000] {
+                let output = amount_out(amp, input, 1_000_000, 1_000_000).unwrap();
+                // never better than one for one on a balanced pool
+                assert!(output < input, "amp {amp}, {input}: {output}");
+                let d_before = compute_d(amp, 1_000_000, 1_000_000).unwrap();
+                let d_after = compute_d(amp, 1_000_000 + input, 1_000_000 - output).unwrap();
+                assert!(d_after >= d_before);
+            }
+
+            for output in [1, 1000, 500_000] {
+                let input = amount_in(amp, output, 1_000_000, 1_000_000).unwrap();
+                assert!(input > output, "amp {amp}, {output}: {input}");
+                assert!(amount_out(amp, input, 1_000_000, 1_000_000).unwrap() >= output);
+            }
+        }
+    }
+
+    #[test]
+    fn swaps_against_empty_reserves() {
+        assert_eq!(
+            amount_out(AMP, 10, 0, 1000),
+            Err(MathError::InsufficientReserves)
+        );
+        // draining the whole out side leaves nothing to solve for
+        assert_eq!(
+            amount_in(AMP, 1000, 1000, 1000),
+            Err(MathError::InsufficientReserves)
+        );
+        assert_eq!(
+            amount_in(AMP, 1001, 1000, 1000),
+            Err(MathError::InsufficientReserves)
+        );
+    }
+
+    #[test]
+    fn swaps_overflow() {
+        assert_eq!(
+            amount_out(AMP, 1, u64::MAX, u64::MAX),
+            Err(MathError::Overflow)
+        );
+        assert_eq!(
+            liquidity(AMP, 1, 1, u64::MAX, u64::MAX, 1000),
+            Err(MathError::Overflow)
+        );
+        // D of the first deposit is the lp supply, it has to fit a u64
+        assert_eq!(
+            liquidity(AMP, u64::MAX, u64::MAX, 0, 0, 0),
+            Err(MathError::Overflow)
+        );
+    }
+
+    #[test]
+    fn liquidity_follows_the_growth_of_d() {
+        assert_eq!(liquidity(AMP, 1000, 1000, 0, 0, 0), Ok(2000));
+        assert_eq!(liquidity(AMP, 1000, 1000, 1000, 1000, 2000), Ok(2000));
+        // a third of D added, rounded down
+        assert_eq!(liquidity(AMP, 1, 1, 3, 3, 1000), Ok(333));
+    }
+
+    #[test]
+    fn spot_price_of_a_balanced_pool_is_one() {
+        let (numerator, denominator) = spot_price(AMP, 1_000_000, 1_000_000).unwrap();
+        assert_eq!(numerator, denominator);
+
+        // the scarce side is worth more
+        let (numerator, denominator) = spot_price(AMP, 1_000_000, 4_000_000).unwrap();
+        assert!(numerator > denominator);
+    }
+}
@@ -0,0 +1,253 @@
+use amm_math::{FEE_DENOMINATOR, U256};
+
+use crate::constants::{MAX_SQRT_PRICE, MAX_TICK, MIN_SQRT_PRICE, MIN_TICK};
+
+// sqrt prices are Q64.64 fixed point numbers, the same layout as I64F64 without
+// the sign, and a tick t sits at sqrt(1.0001^t)
+const Q64: u128 = 1 << 64;
+
+// 1 / sqrt(1.0001^(2^i)) in Q64.64 for every bit of |tick|
+const TICK_RATIOS: [u128; 19] = [
+    0xfffcb933bd6fad37,
+    0xfff97272373d4132,
+    0xfff2e50f5f656932,
+    0xffe5caca7e10e4e6,
+    0xffcb9843d60f6159,
+    0xff973b41fa98c081,
+    0xff2ea16466c96a38,
+    0xfe5dee046a99a2a8,
+    0xfcbe86c7900a88ae,
+    0xf987a7253ac41317,
+    0xf3392b0822b70005,
+    0xe7159475a2c29b74,
+    0xd097f3bdfd2022b8,
+    0xa9f746462d870fdf,
+    0x70d869a156d2a1b8,
+    0x31be135f97d08fd9,
+    0x09aa508b5b7a84e1,
+    0x005d6af8dedb8119,
+    0x00002216e584f5fa,
+];
+
+pub fn sqrt_price_at_tick(tick: i32) -> Option<u128> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = Q64;
+    for (i, tick_ratio) in TICK_RATIOS.iter().enumerate() {
+        if abs_tick & (1 << i) != 0 {
+            ratio = ((U256::from(ratio) * U256::from(*tick_ratio)) >> 64).as_u128();
+        }
+    }
+
+    // the table walks down in price, positive ticks are the inverse
+    if tick > 0 {
+        ratio = u128::MAX / ratio;
+    }
+
+    Some(ratio)
+}
+
+// greatest tick whose sqrt price is at or below `sqrt_price`
+pub fn tick_at_sqrt_price(sqrt_price: u128) -> Option<i32> {
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price) {
+        return None;
+    }
+
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if sqrt_price_at_tick(mid)? <= sqrt_price {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Some(low)
+}
+
+// amount of a between two sqrt prices: L * (upper - lower) / (upper * lower)
+pub fn amount_a_delta(
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u128> {
+    if sqrt_price_lower == 0 || sqrt_price_lower > sqrt_price_upper {
+        return None;
+    }
+
+    let numerator =
+        U256::from(liquidity).checked_mul(U256::from(sqrt_price_upper - sqrt_price_lower))?;
+    if numerator.bits() > 192 {
+        return None;
+    }
+    let numerator = numerator << 64;
+
+    let amount = div(numerator, U256::from(sqrt_price_upper), round_up);
+    let amount = div(amount, U256::from(sqrt_price_lower), round_up);
+    u128::try_from(amount).ok()
+}
+
+// amount of b between two sqrt prices: L * (upper - lower)
+pub fn amount_b_delta(
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u128> {
+    if sqrt_price_lower > sqrt_price_upper {
+        return None;
+    }
+
+    let amount =
+        U256::from(liquidity).checked_mul(U256::from(sqrt_price_upper - sqrt_price_lower))?;
+    u128::try_from(div(amount, U256::from(Q64), round_up)).ok()
+}
+
+// tokens needed to add `liquidity` to [lower, upper) at the current price,
+// or released when removing it
+pub fn amounts_for_liquidity(
+    sqrt_price: u128,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<(u64, u64)> {
+    let (amount_a, amount_b) = if sqrt_price < sqrt_price_lower {
+        // only a is needed below the range
+        (
+            amount_a_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?,
+            0,
+        )
+    } else if sqrt_price < sqrt_price_upper {
+        (
+            amount_a_delta(sqrt_price, sqrt_price_upper, liquidity, round_up)?,
+            amount_b_delta(sqrt_price_lower, sqrt_price, liquidity, round_up)?,
+        )
+    } else {
+        // only b is needed above the range
+        (
+            0,
+            amount_b_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?,
+        )
+    };
+
+    Some((u64::try_from(amount_a).ok()?, u64::try_from(amount_b).ok()?))
+}
+
+// sqrt price after `amount_in` enters the pool, rounded in favor of the pool
+pub fn next_sqrt_price_from_input(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount_in: u64,
+    a_to_b: bool,
+) -> Option<u128> {
+    if liquidity == 0 {
+        return None;
+    }
+    if amount_in == 0 {
+        return Some(sqrt_price);
+    }
+
+    let liquidity_x64 = U256::from(liquidity) << 64;
+    if a_to_b {
+        // L / (L / sqrt_price + amount_in), rounding the price up
+        let denominator = liquidity_x64 / U256::from(sqrt_price) + U256::from(amount_in);
+        u128::try_from(div(liquidity_x64, denominator, true)).ok()
+    } else {
+        // sqrt_price + amount_in / L, rounding the price down
+        let delta = (U256::from(amount_in) << 64) / U256::from(liquidity);
+        sqrt_price.checked_add(u128::try_from(delta).ok()?)
+    }
+}
+
+pub struct SwapStep {
+    pub sqrt_price_next: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+// one step of a swap within a range of constant liquidity, moving from
+// `sqrt_price` toward `sqrt_price_target` with at most `amount_remaining` in
+pub fn compute_swap_step(
+    sqrt_price: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee: u16,
+    a_to_b: bool,
+) -> Option<SwapStep> {
+    let amount_remaining_less_fee = (amount_remaining as u128
+        * (FEE_DENOMINATOR - fee as u64) as u128
+        / FEE_DENOMINATOR as u128) as u64;
+
+    let amount_in_to_target = if a_to_b {
+        amount_a_delta(sqrt_price_target, sqrt_price, liquidity, true)?
+    } else {
+        amount_b_delta(sqrt_price, sqrt_price_target, liquidity, true)?
+    };
+
+    let reached_target = amount_remaining_less_fee as u128 >= amount_in_to_target;
+    let sqrt_price_next = if reached_target {
+        sqrt_price_target
+    } else {
+        next_sqrt_price_from_input(sqrt_price, liquidity, amount_remaining_less_fee, a_to_b)?
+    };
+
+    let (amount_in, amount_out) = if a_to_b {
+        (
+            amount_a_delta(sqrt_price_next, sqrt_price, liquidity, true)?,
+            amount_b_delta(sqrt_price_next, sqrt_price, liquidity, false)?,
+        )
+    } else {
+        (
+            amount_b_delta(sqrt_price, sqrt_price_next, liquidity, true)?,
+            amount_a_delta(sqrt_price, sqrt_price_next, liquidity, false)?,
+        )
+    };
+    let amount_in = u64::try_from(amount_in).ok()?;
+    let amount_out = u64::try_from(amount_out).ok()?;
+
+    let fee_amount = if reached_target {
+        let fee_amount = div(
+            U256::from(amount_in) * U256::from(fee),
+            U256::from(FEE_DENOMINATOR - fee as u64),
+            true,
+        );
+        u64::try_from(fee_amount).ok()?
+    } else {
+        // whatever is left of the input after this step is the fee
+        amount_remaining.checked_sub(amount_in)?
+    };
+
+    Some(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+// fees earned by `liquidity` over a Q64.64 fee growth delta
+pub fn fees_earned(fee_growth_delta: u128, liquidity: u128) -> Option<u64> {
+    u64::try_from((U256::from(fee_growth_delta) * U256::from(liquidity)) >> 64).ok()
+}
+
+// Q64.64 fee growth per unit of liquidity
+pub fn fee_growth(fee_amount: u64, liquidity: u128) -> u128 {
+    ((fee_amount as u128) << 64) / liquidity
+}
+
+fn div(numerator: U256, denominator: U256, round_up: bool) -> U256 {
+    let quotient = numerator / denominator;
+    if round_up && !(numerator % denominator).is_zero() {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
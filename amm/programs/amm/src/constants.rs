@@ -3,8 +3,70 @@ use anchor_lang::prelude::*;
 #[constant]
 pub const MINIMUM_LIQUIDITY: u64 = 100;
 
+// the literal is what ends up in the idl, the math crate has to agree with it
+const _: () = assert!(MINIMUM_LIQUIDITY == amm_math::MINIMUM_LIQUIDITY);
+
 #[constant]
 pub const AUTHORITY_SEED: &[u8] = b"authority";
 
 #[constant]
 pub const LIQUIDITY_SEED: &[u8] = b"liquidity";
+
+// token account owned by the pool that holds the minimum liquidity
+#[constant]
+pub const LOCKED_LIQUIDITY_SEED: &[u8] = b"locked_liquidity";
+
+// number of twap observations kept on every pool
+pub const OBSERVATION_CAPACITY: usize = 16;
+
+// minimum number of seconds between two recorded observations
+#[constant]
+pub const OBSERVATION_PERIOD: i64 = 60;
+
+// bounds of the stableswap amplification coefficient
+#[constant]
+pub const MIN_AMP: u64 = 1;
+
+#[constant]
+pub const MAX_AMP: u64 = 1_000_000;
+
+// an amp ramp can at most multiply or divide the amp by this much
+#[constant]
+pub const MAX_AMP_CHANGE: u64 = 10;
+
+// shortest amp ramp, and shortest time between the start of two ramps
+#[constant]
+pub const MIN_AMP_RAMP_DURATION: i64 = 86400;
+
+#[constant]
+pub const CONCENTRATED_SEED: &[u8] = b"concentrated";
+
+#[constant]
+pub const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+
+#[constant]
+pub const POSITION_SEED: &[u8] = b"position";
+
+// ticks are powers of sqrt(1.0001), these bounds keep sqrt prices within Q64.64
+#[constant]
+pub const MIN_TICK: i32 = -443636;
+
+#[constant]
+pub const MAX_TICK: i32 = 443636;
+
+// Q64.64 sqrt prices at MIN_TICK and MAX_TICK
+#[constant]
+pub const MIN_SQRT_PRICE: u128 = 4295048016u128;
+
+#[constant]
+pub const MAX_SQRT_PRICE: u128 = 79226673521066979257578248091u128;
+
+#[constant]
+pub const MAX_TICK_SPACING: u16 = 1000;
+
+// number of ticks stored in every tick array account
+pub const TICK_ARRAY_SIZE: usize = 16;
+
+// flash loan fee in basis points of the borrowed amount
+#[constant]
+pub const FLASH_LOAN_FEE: u16 = 9;
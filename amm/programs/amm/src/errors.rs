@@ -1,3 +1,4 @@
+use amm_math::MathError;
 use anchor_lang::prelude::*;
 
 #[error_code]
@@ -22,4 +23,101 @@ pub enum ErrorType {
 
     #[msg("You dont have sufficient funds to withdraw")]
     ZeroWithdrawal,
+
+    #[msg("Invalid protocol fee value")]
+    InvalidProtocolFee,
+
+    #[msg("You are not the admin")]
+    UnauthorizedAdmin,
+
+    #[msg("You are not the pending admin")]
+    NotPendingAdmin,
+
+    #[msg("AMM is paused")]
+    AmmPaused,
+
+    #[msg("Pool is paused")]
+    PoolPaused,
+
+    #[msg("Already paused")]
+    AlreadyPaused,
+
+    #[msg("Not paused")]
+    NotPaused,
+
+    #[msg("Input is above the maximum expected")]
+    InputTooLarge,
+
+    #[msg("Pool does not have enough reserves for this output")]
+    InsufficientReserves,
+
+    #[msg("Invalid swap route")]
+    InvalidRoute,
+
+    #[msg("Transaction deadline has passed")]
+    DeadlineExceeded,
+
+    #[msg("Not enough price history for this window")]
+    ObservationTooOld,
+
+    #[msg("Mint has an unsupported token extension")]
+    UnsupportedMintExtension,
+
+    #[msg("Could not compute the transfer fee")]
+    InvalidTransferFee,
+
+    #[msg("Not supported by this pool's curve")]
+    InvalidCurveType,
+
+    #[msg("Invalid amplification coefficient")]
+    InvalidAmp,
+
+    #[msg("Amplification ramp is too short or too soon after the last one")]
+    InvalidAmpRamp,
+
+    #[msg("Curve math did not converge")]
+    CurveMathFailed,
+
+    #[msg("Invalid tick spacing")]
+    InvalidTickSpacing,
+
+    #[msg("Invalid tick range")]
+    InvalidTickRange,
+
+    #[msg("Invalid or missing tick array")]
+    InvalidTickArray,
+
+    #[msg("Sqrt price is out of bounds")]
+    InvalidSqrtPrice,
+
+    #[msg("Concentrated liquidity math overflowed")]
+    LiquidityMathOverflow,
+
+    #[msg("Pool is locked by a flash loan")]
+    PoolLocked,
+
+    #[msg("Flash loan was not repaid with its fee")]
+    FlashLoanNotRepaid,
+
+    #[msg("Math overflowed")]
+    MathOverflow,
+
+    #[msg("Pool mints have to be different")]
+    IdenticalMints,
+
+    #[msg("Pool mints have to be sorted, mint_a below mint_b")]
+    UnorderedMints,
+
+    #[msg("Stable swap pool mints need the same decimals")]
+    MismatchedDecimals,
+}
+
+impl From<MathError> for ErrorType {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Overflow => ErrorType::MathOverflow,
+            MathError::InsufficientReserves => ErrorType::InsufficientReserves,
+            MathError::NoConvergence => ErrorType::CurveMathFailed,
+        }
+    }
 }
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ErrorType, state::Amm};
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+
+    let old_admin = amm.admin;
+    amm.admin = ctx.accounts.pending_admin.key();
+    amm.pending_admin = Pubkey::default();
+
+    emit!(AdminTransferred {
+        amm: amm.key(),
+        old_admin,
+        new_admin: amm.admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = pending_admin @ ErrorType::NotPendingAdmin
+    )]
+    pub amm: Account<'info, Amm>,
+
+    pub pending_admin: Signer<'info>,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub amm: Pubkey,
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ErrorType,
+    instructions::{transfer_from_pool, update_position, ModifyLiquidity},
+};
+
+// swap fees earned by a position, they are never added back to its liquidity
+pub fn collect_fees(ctx: Context<ModifyLiquidity>) -> Result<()> {
+    require!(!ctx.accounts.amm.withdrawals_paused, ErrorType::AmmPaused);
+
+    // credits the fees earned since the last update without touching the liquidity
+    update_position(ctx.accounts, 0)?;
+
+    let position = &mut ctx.accounts.position;
+    let amount_a = position.fees_owed_a;
+    let amount_b = position.fees_owed_b;
+    position.fees_owed_a = 0;
+    position.fees_owed_b = 0;
+
+    transfer_from_pool(ctx.accounts, ctx.bumps.pool_authority, amount_a, amount_b)?;
+
+    emit!(FeesCollected {
+        pool: ctx.accounts.pool.key(),
+        position: ctx.accounts.position.key(),
+        owner: ctx.accounts.owner.key(),
+        amount_a,
+        amount_b,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct FeesCollected {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
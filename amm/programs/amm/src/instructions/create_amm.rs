@@ -2,18 +2,27 @@ use anchor_lang::prelude::*;
 
 use crate::{errors::ErrorType, state::Amm};
 
-pub fn create_amm(ctx: Context<CreateAmm>, id: Pubkey, fee: u16) -> Result<()> {
+pub fn create_amm(ctx: Context<CreateAmm>, id: Pubkey, fee: u16, protocol_fee: u16) -> Result<()> {
     let amm = &mut ctx.accounts.amm;
 
     amm.id = id;
     amm.admin = ctx.accounts.admin.key();
     amm.fee = fee;
+    amm.protocol_fee = protocol_fee;
+
+    emit!(AmmCreated {
+        amm: amm.key(),
+        id,
+        admin: amm.admin,
+        fee,
+        protocol_fee,
+    });
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(id: Pubkey, fee: u16)]
+#[instruction(id: Pubkey, fee: u16, protocol_fee: u16)]
 pub struct CreateAmm<'info> {
     #[account(
         init,
@@ -21,7 +30,8 @@ pub struct CreateAmm<'info> {
         space = Amm::LEN,
         seeds=[id.as_ref()],
         bump,
-        constraint = fee < 10000 @ ErrorType::InvalidFee
+        constraint = fee < 10000 @ ErrorType::InvalidFee,
+        constraint = protocol_fee <= 10000 @ ErrorType::InvalidProtocolFee
     )]
     pub amm: Account<'info, Amm>,
 
@@ -33,3 +43,12 @@ pub struct CreateAmm<'info> {
 
     pub system_program: Program<'info, System>,
 }
+
+#[event]
+pub struct AmmCreated {
+    pub amm: Pubkey,
+    pub id: Pubkey,
+    pub admin: Pubkey,
+    pub fee: u16,
+    pub protocol_fee: u16,
+}
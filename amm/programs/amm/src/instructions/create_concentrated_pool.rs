@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    concentrated::tick_at_sqrt_price,
+    constants::{AUTHORITY_SEED, CONCENTRATED_SEED, MAX_SQRT_PRICE, MAX_TICK_SPACING},
+    errors::ErrorType,
+    state::{Amm, ConcentratedPool},
+    utils::{check_mint_extensions, check_mint_order},
+};
+
+pub fn create_concentrated_pool(
+    ctx: Context<CreateConcentratedPool>,
+    tick_spacing: u16,
+    sqrt_price: u128,
+) -> Result<()> {
+    check_mint_extensions(&ctx.accounts.mint_a)?;
+    check_mint_extensions(&ctx.accounts.mint_b)?;
+
+    require!(
+        (1..=MAX_TICK_SPACING).contains(&tick_spacing),
+        ErrorType::InvalidTickSpacing
+    );
+    // the max price is excluded so the current tick always has one above it
+    require!(sqrt_price < MAX_SQRT_PRICE, ErrorType::InvalidSqrtPrice);
+    let tick_current = tick_at_sqrt_price(sqrt_price).ok_or(ErrorType::InvalidSqrtPrice)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.amm = ctx.accounts.amm.key();
+    pool.mint_a = ctx.accounts.mint_a.key();
+    pool.mint_b = ctx.accounts.mint_b.key();
+    pool.tick_spacing = tick_spacing;
+    pool.sqrt_price = sqrt_price;
+    pool.tick_current = tick_current;
+
+    emit!(ConcentratedPoolCreated {
+        amm: pool.amm,
+        pool: pool.key(),
+        mint_a: pool.mint_a,
+        mint_b: pool.mint_b,
+        tick_spacing,
+        sqrt_price,
+        tick_current,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateConcentratedPool<'info> {
+    #[account(
+        seeds=[amm.id.as_ref()],
+        bump
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ConcentratedPool::LEN,
+        seeds = [
+            amm.key().as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            CONCENTRATED_SEED,
+        ],
+        bump,
+        // before the vaults below are created
+        constraint = { check_mint_order(&mint_a.key(), &mint_b.key())?; true }
+    )]
+    pub pool: Box<Account<'info, ConcentratedPool>>,
+
+    /// CHECK: PDA, safe because seeds are verified
+    #[account(
+        seeds=[
+            pool.key().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    // each mint can belong to either token program
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[event]
+pub struct ConcentratedPoolCreated {
+    pub amm: Pubkey,
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub tick_spacing: u16,
+    pub sqrt_price: u128,
+    pub tick_current: i32,
+}
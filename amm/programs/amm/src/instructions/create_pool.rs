@@ -1,19 +1,61 @@
+use amm_math::liquidity::lp_decimals;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 use crate::{
-    constants::{AUTHORITY_SEED, LIQUIDITY_SEED},
-    state::{Amm, Pool},
+    constants::{AUTHORITY_SEED, LIQUIDITY_SEED, LOCKED_LIQUIDITY_SEED, MAX_AMP, MIN_AMP},
+    errors::ErrorType,
+    state::{Amm, CurveType, Observation, Pool},
+    utils::{check_mint_extensions, check_mint_order},
 };
 
-pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
+pub fn create_pool(ctx: Context<CreatePool>, curve_type: CurveType, amp: u64) -> Result<()> {
+    check_mint_extensions(&ctx.accounts.mint_a)?;
+    check_mint_extensions(&ctx.accounts.mint_b)?;
+
+    match curve_type {
+        CurveType::ConstantProduct => require!(amp == 0, ErrorType::InvalidAmp),
+        CurveType::StableSwap => {
+            require!((MIN_AMP..=MAX_AMP).contains(&amp), ErrorType::InvalidAmp);
+            // the invariant adds raw reserves together, so a unit of a has to
+            // be worth a unit of b at the peg
+            require!(
+                ctx.accounts.mint_a.decimals == ctx.accounts.mint_b.decimals,
+                ErrorType::MismatchedDecimals
+            );
+        }
+    }
+
     let pool = &mut ctx.accounts.pool;
     pool.amm = ctx.accounts.amm.key();
     pool.mint_a = ctx.accounts.mint_a.key();
     pool.mint_b = ctx.accounts.mint_b.key();
+    pool.curve_type = curve_type;
+    pool.amp_initial = amp;
+    pool.amp_target = amp;
+
+    // first observation anchors the twap history at pool creation
+    let now = Clock::get()?.unix_timestamp;
+    pool.last_update_ts = now;
+    pool.observations[0] = Observation {
+        timestamp: now,
+        price_a_cumulative: 0,
+        price_b_cumulative: 0,
+    };
+
+    emit!(PoolCreated {
+        amm: pool.amm,
+        pool: pool.key(),
+        mint_a: pool.mint_a,
+        mint_b: pool.mint_b,
+        mint_liquidity: ctx.accounts.mint_liqudity.key(),
+        curve_type,
+        amp,
+    });
+
     Ok(())
 }
 
@@ -34,9 +76,11 @@ pub struct CreatePool<'info> {
             mint_a.key().as_ref(),
             mint_b.key().as_ref(),
         ],
-        bump
+        bump,
+        // before the vaults below are created
+        constraint = { check_mint_order(&mint_a.key(), &mint_b.key())?; true }
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: Box<Account<'info, Pool>>,
 
     /// CHECK: PDA, safe because seeds are verified
     #[account(
@@ -50,6 +94,13 @@ pub struct CreatePool<'info> {
     )]
     pub pool_authority: UncheckedAccount<'info>,
 
+    // each mint can belong to either token program, both come before the
+    // lp mint whose decimals they set
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(
         init,
         payer = payer,
@@ -60,34 +111,66 @@ pub struct CreatePool<'info> {
             LIQUIDITY_SEED,
         ],
         bump,
-        mint::decimals = 6,
-        mint::authority = pool_authority
+        mint::decimals = lp_decimals(mint_a.decimals, mint_b.decimals),
+        mint::authority = pool_authority,
+        mint::token_program = token_program
     )]
-    pub mint_liqudity: Account<'info, Mint>, // for minting lptokens
+    pub mint_liqudity: Box<InterfaceAccount<'info, Mint>>, // for minting lptokens
 
-    pub mint_a: Account<'info, Mint>,
-    pub mint_b: Account<'info, Mint>,
+    // the first deposit mints the minimum liquidity here, nothing ever signs
+    // for the pool so it stays locked
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            amm.key().as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            LOCKED_LIQUIDITY_SEED,
+        ],
+        bump,
+        token::mint = mint_liqudity,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub locked_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
         payer = payer,
         associated_token::mint = mint_a,
-        associated_token::authority = pool_authority
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a
     )]
-    pub pool_account_a: Account<'info, TokenAccount>,
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
         payer = payer,
         associated_token::mint = mint_b,
-        associated_token::authority = pool_authority
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b
     )]
-    pub pool_account_b: Account<'info, TokenAccount>,
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    // token program of the lp mint
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
+
+#[event]
+pub struct PoolCreated {
+    pub amm: Pubkey,
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub mint_liquidity: Pubkey,
+    pub curve_type: CurveType,
+    pub amp: u64,
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+
+use crate::{
+    constants::AUTHORITY_SEED,
+    errors::ErrorType,
+    instructions::{position_amounts, update_position, ModifyLiquidity},
+    utils::check_deadline,
+};
+
+pub fn decrease_liquidity(
+    ctx: Context<ModifyLiquidity>,
+    liquidity: u128,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    require!(!ctx.accounts.amm.withdrawals_paused, ErrorType::AmmPaused);
+    require!(liquidity > 0, ErrorType::ZeroWithdrawal);
+    require!(
+        liquidity <= ctx.accounts.position.liquidity,
+        ErrorType::InsufficientLiquidity
+    );
+
+    let liquidity_delta =
+        i128::try_from(liquidity).map_err(|_| ErrorType::LiquidityMathOverflow)?;
+    update_position(ctx.accounts, -liquidity_delta)?;
+
+    let (amount_a, amount_b) = position_amounts(ctx.accounts, liquidity, false)?;
+    require!(
+        amount_a >= min_amount_a && amount_b >= min_amount_b,
+        ErrorType::OutputTooSmall
+    );
+
+    transfer_from_pool(ctx.accounts, ctx.bumps.pool_authority, amount_a, amount_b)?;
+
+    emit!(LiquidityDecreased {
+        pool: ctx.accounts.pool.key(),
+        position: ctx.accounts.position.key(),
+        owner: ctx.accounts.owner.key(),
+        liquidity,
+        amount_a,
+        amount_b,
+    });
+
+    Ok(())
+}
+
+// sends tokens from the pool vaults to the position owner
+pub(crate) fn transfer_from_pool(
+    accounts: &ModifyLiquidity,
+    authority_bump: u8,
+    amount_a: u64,
+    amount_b: u64,
+) -> Result<()> {
+    let pool_key = accounts.pool.key();
+    let authority_seeds = &[pool_key.as_ref(), AUTHORITY_SEED, &[authority_bump]];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    if amount_a > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program_a.to_account_info(),
+                TransferChecked {
+                    from: accounts.pool_account_a.to_account_info(),
+                    mint: accounts.mint_a.to_account_info(),
+                    to: accounts.owner_account_a.to_account_info(),
+                    authority: accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_a,
+            accounts.mint_a.decimals,
+        )?;
+    }
+
+    if amount_b > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program_b.to_account_info(),
+                TransferChecked {
+                    from: accounts.pool_account_b.to_account_info(),
+                    mint: accounts.mint_b.to_account_info(),
+                    to: accounts.owner_account_b.to_account_info(),
+                    authority: accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_b,
+            accounts.mint_b.decimals,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[event]
+pub struct LiquidityDecreased {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub liquidity: u128,
+    // amounts sent out of the pool
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
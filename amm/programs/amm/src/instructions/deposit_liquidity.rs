@@ -1,131 +1,132 @@
+use amm_math::liquidity::{balanced_amounts, deposit_liquidity as liquidity_for_deposit, is_empty};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+    token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
 };
-use fixed::types::I64F64;
 
 use crate::{
-    constants::{AUTHORITY_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY},
+    constants::{AUTHORITY_SEED, LIQUIDITY_SEED, LOCKED_LIQUIDITY_SEED, MINIMUM_LIQUIDITY},
     errors::ErrorType,
-    state::Pool,
+    state::{Amm, Pool},
+    utils::{check_deadline, reserves},
 };
 
 pub fn deposit_liquidity(
     ctx: Context<DepositLiquidity>,
     amount_a: u64,
     amount_b: u64,
+    min_liquidity_out: u64,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
+    require!(!ctx.accounts.amm.paused, ErrorType::AmmPaused);
+    require!(!ctx.accounts.pool.paused, ErrorType::PoolPaused);
+    require!(!ctx.accounts.pool.locked, ErrorType::PoolLocked);
+
     if amount_a == 0 || amount_b == 0 {
         return err!(ErrorType::DepositTooSmall);
     }
 
-    let mut amount_a = if amount_a > ctx.accounts.depositer_mint_a_ata.amount {
+    let amount_a = if amount_a > ctx.accounts.depositer_mint_a_ata.amount {
         ctx.accounts.depositer_mint_a_ata.amount
     } else {
         amount_a
     };
-    let mut amount_b = if amount_b > ctx.accounts.depositer_mint_b_ata.amount {
+    let amount_b = if amount_b > ctx.accounts.depositer_mint_b_ata.amount {
         ctx.accounts.depositer_mint_b_ata.amount
     } else {
         amount_b
     };
 
+    let pool = &ctx.accounts.pool;
     let pool_a = &ctx.accounts.pool_account_a;
     let pool_b = &ctx.accounts.pool_account_b;
 
-    let reserve_a = pool_a.amount;
-    let reserve_b = pool_b.amount;
+    // accrued protocol fees are owed to the admin, not to LPs
+    let reserve_a = reserves(pool_a.amount, pool.protocol_fees_a)?;
+    let reserve_b = reserves(pool_b.amount, pool.protocol_fees_b)?;
 
-    // lp total supply
+    // lp total supply, the locked minimum liquidity included
     let lp_supply = ctx.accounts.mint_liquidity.supply;
 
-    let pool_creation = reserve_a == 0 && reserve_b == 0 && lp_supply == 0;
-
-    if !pool_creation {
-        // ideal_b = (amount_a * reserve_b)/reserve_a
-        let ideal_b = I64F64::from_num(amount_a)
-            .checked_mul(I64F64::from_num(reserve_b))
-            .unwrap()
-            .checked_div(I64F64::from_num(reserve_a))
-            .unwrap()
-            .to_num::<u64>();
-
-        if ideal_b <= amount_b {
-            // user is trying to give too much b than needed
-            amount_b = ideal_b;
-        } else {
-            // ideal_a = (amount_b * reserve_a)/ reserve_b
-            let ideal_a = I64F64::from_num(amount_b)
-                .checked_mul(I64F64::from_num(reserve_a))
-                .unwrap()
-                .checked_div(I64F64::from_num(reserve_b))
-                .unwrap()
-                .to_num::<u64>();
-            amount_a = ideal_a
-        }
-    }
-
-    let mut liquidity: u64;
-
-    if pool_creation {
-        liquidity = I64F64::from_num(amount_a)
-            .checked_mul(I64F64::from_num(amount_b))
-            .unwrap()
-            .sqrt()
-            .to_num::<u64>();
+    let pool_creation = is_empty(reserve_a, reserve_b, lp_supply);
 
-        if liquidity < MINIMUM_LIQUIDITY {
-            return err!(ErrorType::DepositTooSmall);
-        }
-
-        liquidity -= MINIMUM_LIQUIDITY;
+    // the side given in excess is cut down to the pool ratio
+    let (amount_a, amount_b) = if pool_creation {
+        (amount_a, amount_b)
     } else {
-        let liquidity_from_a = I64F64::from_num(amount_a)
-            .checked_mul(I64F64::from_num(lp_supply))
-            .unwrap()
-            .checked_div(I64F64::from_num(reserve_a))
-            .unwrap()
-            .to_num::<u64>();
-
-        let liquidity_from_b = I64F64::from_num(amount_b)
-            .checked_mul(I64F64::from_num(lp_supply))
-            .unwrap()
-            .checked_div(I64F64::from_num(reserve_b))
-            .unwrap()
-            .to_num::<u64>();
-
-        liquidity = liquidity_from_a.min(liquidity_from_b);
-
-        if liquidity == 0 {
-            return err!(ErrorType::DepositTooSmall);
-        }
-    }
+        balanced_amounts(amount_a, amount_b, reserve_a, reserve_b).map_err(ErrorType::from)?
+    };
 
     // adding tokens to there respective vaults
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_program_a.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.depositer_mint_a_ata.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
                 to: ctx.accounts.pool_account_a.to_account_info(),
                 authority: ctx.accounts.depositor.to_account_info(),
             },
         ),
         amount_a,
+        ctx.accounts.mint_a.decimals,
     )?;
 
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_program_b.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.depositer_mint_b_ata.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
                 to: ctx.accounts.pool_account_b.to_account_info(),
                 authority: ctx.accounts.depositor.to_account_info(),
             },
         ),
         amount_b,
+        ctx.accounts.mint_b.decimals,
+    )?;
+
+    // transfer fee mints deliver less than what was sent, lp tokens are
+    // minted for what the pool actually received
+    ctx.accounts.pool_account_a.reload()?;
+    ctx.accounts.pool_account_b.reload()?;
+    let reserve_a_after = reserves(
+        ctx.accounts.pool_account_a.amount,
+        ctx.accounts.pool.protocol_fees_a,
+    )?;
+    let reserve_b_after = reserves(
+        ctx.accounts.pool_account_b.amount,
+        ctx.accounts.pool.protocol_fees_b,
     )?;
+    let amount_a = reserve_a_after
+        .checked_sub(reserve_a)
+        .ok_or(ErrorType::MathOverflow)?;
+    let amount_b = reserve_b_after
+        .checked_sub(reserve_b)
+        .ok_or(ErrorType::MathOverflow)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .pool
+        .update_oracle(reserve_a_after, reserve_b_after, now);
+
+    let curve = ctx.accounts.pool.curve(now);
+    let liquidity =
+        liquidity_for_deposit(curve, amount_a, amount_b, reserve_a, reserve_b, lp_supply)
+            .map_err(ErrorType::from)?;
+    // the first deposit locks MINIMUM_LIQUIDITY in the pool
+    let locked = if pool_creation { MINIMUM_LIQUIDITY } else { 0 };
+    let liquidity = match liquidity.checked_sub(locked) {
+        Some(liquidity) if liquidity > 0 => liquidity,
+        _ => return err!(ErrorType::DepositTooSmall),
+    };
+
+    // the pool ratio may have moved since the deposit was quoted
+    if liquidity < min_liquidity_out {
+        return err!(ErrorType::OutputTooSmall);
+    }
 
     // minting the lp tokens
     let authority_bump = ctx.bumps.pool_authority;
@@ -137,7 +138,21 @@ pub fn deposit_liquidity(
         &[authority_bump],
     ];
     let signer_seeds = &[&seeds[..]];
-    token::mint_to(
+    if locked > 0 {
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint_liquidity.to_account_info(),
+                    to: ctx.accounts.locked_liquidity.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            locked,
+        )?;
+    }
+    token_interface::mint_to(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             MintTo {
@@ -149,13 +164,30 @@ pub fn deposit_liquidity(
         ),
         liquidity,
     )?;
+
+    emit!(LiquidityAdded {
+        pool: ctx.accounts.pool.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount_a,
+        amount_b,
+        liquidity,
+        reserve_a: reserve_a_after,
+        reserve_b: reserve_b_after,
+    });
+
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct DepositLiquidity<'info> {
-    pub mint_a: Account<'info, Mint>,
-    pub mint_b: Account<'info, Mint>,
+    #[account(
+        seeds=[amm.id.as_ref()],
+        bump
+    )]
+    pub amm: Account<'info, Amm>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -165,10 +197,11 @@ pub struct DepositLiquidity<'info> {
             mint_b.key().as_ref()
         ],
         bump,
+        has_one = amm,
         has_one = mint_a,
         has_one = mint_b,
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: Box<Account<'info, Pool>>,
 
     /// CHECK: Read only authority
     #[account(
@@ -192,37 +225,68 @@ pub struct DepositLiquidity<'info> {
             LIQUIDITY_SEED
         ],
         bump,
-        mint::authority = pool_authority
+        mint::authority = pool_authority,
+        mint::token_program = token_program
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    // pools created before the minimum liquidity was locked need
+    // lock_minimum_liquidity first
+    #[account(
+        mut,
+        seeds=[
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            LOCKED_LIQUIDITY_SEED
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = pool,
+        token::token_program = token_program
     )]
-    pub mint_liquidity: Account<'info, Mint>,
+    pub locked_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_a,
-        associated_token::authority = pool_authority
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a
     )]
-    pub pool_account_a: Account<'info, TokenAccount>,
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_b,
-        associated_token::authority = pool_authority
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b
     )]
-    pub pool_account_b: Account<'info, TokenAccount>,
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_liquidity,
         associated_token::authority = depositor,
+        associated_token::token_program = token_program,
     )]
-    pub depositer_mint_lp_ata: Account<'info, TokenAccount>,
+    pub depositer_mint_lp_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut, associated_token::mint = mint_a, associated_token::authority = depositor)]
-    pub depositer_mint_a_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program_a
+    )]
+    pub depositer_mint_a_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut, associated_token::mint = mint_b, associated_token::authority = depositor)]
-    pub depositer_mint_b_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = depositor,
+        associated_token::token_program = token_program_b
+    )]
+    pub depositer_mint_b_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub depositor: Signer<'info>,
@@ -231,6 +295,21 @@ pub struct DepositLiquidity<'info> {
     pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    // token program of the lp mint
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub depositor: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    // lp tokens minted to the depositor
+    pub liquidity: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
@@ -0,0 +1,263 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+    },
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    constants::{AUTHORITY_SEED, FLASH_LOAN_FEE},
+    errors::ErrorType,
+    state::{Amm, Pool},
+    utils::reserves,
+};
+
+// lends vault tokens to the borrower, calls the receiver program with `data`
+// and the remaining accounts, then checks the vaults got the loan back with the fee
+pub fn flash_loan<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FlashLoan<'info>>,
+    amount_a: u64,
+    amount_b: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    require!(!ctx.accounts.amm.paused, ErrorType::AmmPaused);
+    require!(!ctx.accounts.pool.paused, ErrorType::PoolPaused);
+    require!(!ctx.accounts.pool.locked, ErrorType::PoolLocked);
+    // the receiver can't reenter this program, the pool lock backs this up
+    require_keys_neq!(
+        ctx.accounts.receiver_program.key(),
+        crate::ID,
+        ErrorType::PoolLocked
+    );
+
+    // accrued protocol fees are owed to the admin and can't be lent out
+    let reserve_a = reserves(
+        ctx.accounts.pool_account_a.amount,
+        ctx.accounts.pool.protocol_fees_a,
+    )?;
+    let reserve_b = reserves(
+        ctx.accounts.pool_account_b.amount,
+        ctx.accounts.pool.protocol_fees_b,
+    )?;
+    require!(
+        amount_a <= reserve_a && amount_b <= reserve_b,
+        ErrorType::InsufficientReserves
+    );
+
+    let fee_a = flash_loan_fee(amount_a);
+    let fee_b = flash_loan_fee(amount_b);
+
+    // the lock has to be written before the receiver runs to stop reentrant
+    // swaps, deposits or withdrawals against the drained vaults
+    let pool = &mut ctx.accounts.pool;
+    pool.locked = true;
+    pool.exit(&crate::ID)?;
+
+    let balance_a_before = ctx.accounts.pool_account_a.amount;
+    let balance_b_before = ctx.accounts.pool_account_b.amount;
+
+    let authority_bump = ctx.bumps.pool_authority;
+    let authority_seeds = &[
+        &ctx.accounts.pool.amm.to_bytes(),
+        &ctx.accounts.mint_a.key().to_bytes(),
+        &ctx.accounts.mint_b.key().to_bytes(),
+        AUTHORITY_SEED,
+        &[authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    if amount_a > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program_a.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.pool_account_a.to_account_info(),
+                    mint: ctx.accounts.mint_a.to_account_info(),
+                    to: ctx.accounts.borrower_account_a.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_a,
+            ctx.accounts.mint_a.decimals,
+        )?;
+    }
+
+    if amount_b > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program_b.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.pool_account_b.to_account_info(),
+                    mint: ctx.accounts.mint_b.to_account_info(),
+                    to: ctx.accounts.borrower_account_b.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_b,
+            ctx.accounts.mint_b.decimals,
+        )?;
+    }
+
+    // plain invoke, the pool authority never signs for the receiver
+    let receiver_accounts = ctx.remaining_accounts;
+    let instruction = Instruction {
+        program_id: ctx.accounts.receiver_program.key(),
+        accounts: receiver_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    };
+    let mut account_infos = receiver_accounts.to_vec();
+    account_infos.push(ctx.accounts.receiver_program.to_account_info());
+    invoke(&instruction, &account_infos)?;
+
+    // same reload pattern as the swap invariant check, the receiver had to
+    // send the loan and the fee straight back to the vaults
+    ctx.accounts.pool_account_a.reload()?;
+    ctx.accounts.pool_account_b.reload()?;
+    let owed_a = balance_a_before
+        .checked_add(fee_a)
+        .ok_or(ErrorType::MathOverflow)?;
+    let owed_b = balance_b_before
+        .checked_add(fee_b)
+        .ok_or(ErrorType::MathOverflow)?;
+    require!(
+        ctx.accounts.pool_account_a.amount >= owed_a
+            && ctx.accounts.pool_account_b.amount >= owed_b,
+        ErrorType::FlashLoanNotRepaid
+    );
+
+    // the fee is split between lps and the admin like a swap fee
+    let protocol_fee = ctx.accounts.amm.protocol_fee;
+    let pool = &mut ctx.accounts.pool;
+    pool.reload()?;
+    pool.protocol_fees_a = pool
+        .protocol_fees_a
+        .checked_add(amm_math::protocol_fee(fee_a, protocol_fee))
+        .ok_or(ErrorType::MathOverflow)?;
+    pool.protocol_fees_b = pool
+        .protocol_fees_b
+        .checked_add(amm_math::protocol_fee(fee_b, protocol_fee))
+        .ok_or(ErrorType::MathOverflow)?;
+    pool.locked = false;
+    let reserve_a = reserves(ctx.accounts.pool_account_a.amount, pool.protocol_fees_a)?;
+    let reserve_b = reserves(ctx.accounts.pool_account_b.amount, pool.protocol_fees_b)?;
+    pool.update_oracle(reserve_a, reserve_b, Clock::get()?.unix_timestamp);
+
+    emit!(FlashLoanRepaid {
+        pool: pool.key(),
+        borrower: ctx.accounts.borrower.key(),
+        receiver_program: ctx.accounts.receiver_program.key(),
+        amount_a,
+        amount_b,
+        fee_a,
+        fee_b,
+    });
+
+    Ok(())
+}
+
+// rounded up, borrowing a single unit still costs one
+fn flash_loan_fee(amount: u64) -> u64 {
+    (amount as u128 * FLASH_LOAN_FEE as u128).div_ceil(10000) as u64
+}
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// CHECK: PDA used only as authority
+    #[account(
+        seeds=[
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            AUTHORITY_SEED
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // any token accounts of the pool mints, the loan is sent there
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::token_program = token_program_a,
+    )]
+    pub borrower_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::token_program = token_program_b,
+    )]
+    pub borrower_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub borrower: Signer<'info>,
+
+    /// CHECK: any program, it gets called with the remaining accounts
+    #[account(executable)]
+    pub receiver_program: UncheckedAccount<'info>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+}
+
+#[event]
+pub struct FlashLoanRepaid {
+    pub pool: Pubkey,
+    pub borrower: Pubkey,
+    pub receiver_program: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub fee_a: u64,
+    pub fee_b: u64,
+}
@@ -0,0 +1,290 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    concentrated::{amounts_for_liquidity, sqrt_price_at_tick},
+    constants::{AUTHORITY_SEED, CONCENTRATED_SEED},
+    errors::ErrorType,
+    state::{Amm, ConcentratedPool, Position, Tick, TickArray},
+    utils::{amount_with_transfer_fee, check_deadline},
+};
+
+pub fn increase_liquidity(
+    ctx: Context<ModifyLiquidity>,
+    liquidity: u128,
+    max_amount_a: u64,
+    max_amount_b: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    require!(!ctx.accounts.amm.paused, ErrorType::AmmPaused);
+    require!(liquidity > 0, ErrorType::DepositTooSmall);
+
+    let liquidity_delta =
+        i128::try_from(liquidity).map_err(|_| ErrorType::LiquidityMathOverflow)?;
+    update_position(ctx.accounts, liquidity_delta)?;
+
+    let (amount_a, amount_b) = position_amounts(ctx.accounts, liquidity, true)?;
+
+    // transfer fee mints have to send a bit more for the pool to receive the amounts
+    let transfer_a = amount_with_transfer_fee(&ctx.accounts.mint_a, amount_a)?;
+    let transfer_b = amount_with_transfer_fee(&ctx.accounts.mint_b, amount_b)?;
+    require!(
+        transfer_a <= max_amount_a && transfer_b <= max_amount_b,
+        ErrorType::InputTooLarge
+    );
+
+    if transfer_a > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program_a.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.owner_account_a.to_account_info(),
+                    mint: ctx.accounts.mint_a.to_account_info(),
+                    to: ctx.accounts.pool_account_a.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            transfer_a,
+            ctx.accounts.mint_a.decimals,
+        )?;
+    }
+
+    if transfer_b > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program_b.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.owner_account_b.to_account_info(),
+                    mint: ctx.accounts.mint_b.to_account_info(),
+                    to: ctx.accounts.pool_account_b.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            transfer_b,
+            ctx.accounts.mint_b.decimals,
+        )?;
+    }
+
+    emit!(LiquidityIncreased {
+        pool: ctx.accounts.pool.key(),
+        position: ctx.accounts.position.key(),
+        owner: ctx.accounts.owner.key(),
+        liquidity,
+        amount_a,
+        amount_b,
+    });
+
+    Ok(())
+}
+
+// adds `liquidity_delta` to the position and its two ticks, crediting the fees
+// the position earned so far, a zero delta only credits the fees
+pub(crate) fn update_position(accounts: &mut ModifyLiquidity, liquidity_delta: i128) -> Result<()> {
+    let tick_spacing = accounts.pool.tick_spacing;
+    let tick_lower_index = accounts.position.tick_lower;
+    let tick_upper_index = accounts.position.tick_upper;
+
+    // both ticks can live in the same array, it's then passed twice
+    let same_array = accounts.tick_array_lower.key() == accounts.tick_array_upper.key();
+    let lower_offset = accounts
+        .tick_array_lower
+        .tick_offset(tick_lower_index, tick_spacing)
+        .ok_or(ErrorType::InvalidTickArray)?;
+    let upper_offset = if same_array {
+        accounts
+            .tick_array_lower
+            .tick_offset(tick_upper_index, tick_spacing)
+    } else {
+        accounts
+            .tick_array_upper
+            .tick_offset(tick_upper_index, tick_spacing)
+    }
+    .ok_or(ErrorType::InvalidTickArray)?;
+
+    let mut tick_lower = accounts.tick_array_lower.ticks[lower_offset];
+    let mut tick_upper = if same_array {
+        accounts.tick_array_lower.ticks[upper_offset]
+    } else {
+        accounts.tick_array_upper.ticks[upper_offset]
+    };
+
+    let pool = &mut accounts.pool;
+    tick_lower
+        .update(tick_lower_index, pool, liquidity_delta, false)
+        .ok_or(ErrorType::LiquidityMathOverflow)?;
+    tick_upper
+        .update(tick_upper_index, pool, liquidity_delta, true)
+        .ok_or(ErrorType::LiquidityMathOverflow)?;
+
+    let (fee_growth_inside_a, fee_growth_inside_b) =
+        pool.fee_growth_inside(tick_lower_index, &tick_lower, tick_upper_index, &tick_upper);
+    let position = &mut accounts.position;
+    position
+        .update_fees(fee_growth_inside_a, fee_growth_inside_b)
+        .ok_or(ErrorType::LiquidityMathOverflow)?;
+    position.liquidity = position
+        .liquidity
+        .checked_add_signed(liquidity_delta)
+        .ok_or(ErrorType::InsufficientLiquidity)?;
+
+    // the current price is in range, the liquidity is active right away
+    if pool.tick_current >= tick_lower_index && pool.tick_current < tick_upper_index {
+        pool.liquidity = pool
+            .liquidity
+            .checked_add_signed(liquidity_delta)
+            .ok_or(ErrorType::LiquidityMathOverflow)?;
+    }
+
+    // ticks no position uses anymore are cleared
+    if !tick_lower.is_initialized() {
+        tick_lower = Tick::default();
+    }
+    if !tick_upper.is_initialized() {
+        tick_upper = Tick::default();
+    }
+
+    accounts.tick_array_lower.ticks[lower_offset] = tick_lower;
+    if same_array {
+        accounts.tick_array_lower.ticks[upper_offset] = tick_upper;
+        // both accounts are written back on exit, the upper one last
+        let tick_array: TickArray = (**accounts.tick_array_lower).clone();
+        accounts.tick_array_upper.set_inner(tick_array);
+    } else {
+        accounts.tick_array_upper.ticks[upper_offset] = tick_upper;
+    }
+
+    Ok(())
+}
+
+// tokens behind `liquidity` of the position at the current price
+pub(crate) fn position_amounts(
+    accounts: &ModifyLiquidity,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<(u64, u64)> {
+    let sqrt_price_lower =
+        sqrt_price_at_tick(accounts.position.tick_lower).ok_or(ErrorType::InvalidTickRange)?;
+    let sqrt_price_upper =
+        sqrt_price_at_tick(accounts.position.tick_upper).ok_or(ErrorType::InvalidTickRange)?;
+
+    Ok(amounts_for_liquidity(
+        accounts.pool.sqrt_price,
+        sqrt_price_lower,
+        sqrt_price_upper,
+        liquidity,
+        round_up,
+    )
+    .ok_or(ErrorType::LiquidityMathOverflow)?)
+}
+
+#[derive(Accounts)]
+pub struct ModifyLiquidity<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            CONCENTRATED_SEED,
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, ConcentratedPool>>,
+
+    /// CHECK: PDA used only as authority
+    #[account(
+        seeds = [
+            pool.key().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = owner,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    // the arrays holding the position's lower and upper ticks, possibly the same one
+    #[account(mut, has_one = pool @ ErrorType::InvalidTickArray)]
+    pub tick_array_lower: Box<Account<'info, TickArray>>,
+
+    #[account(mut, has_one = pool @ ErrorType::InvalidTickArray)]
+    pub tick_array_upper: Box<Account<'info, TickArray>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint_a,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program_a,
+    )]
+    pub owner_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint_b,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program_b,
+    )]
+    pub owner_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[event]
+pub struct LiquidityIncreased {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub liquidity: u128,
+    // amounts the pool received
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
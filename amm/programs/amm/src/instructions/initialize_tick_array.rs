@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONCENTRATED_SEED, MAX_TICK, MIN_TICK, TICK_ARRAY_SEED, TICK_ARRAY_SIZE},
+    errors::ErrorType,
+    state::{ConcentratedPool, TickArray},
+};
+
+// tick arrays are permissionless, whoever needs one first pays for it
+pub fn initialize_tick_array(
+    ctx: Context<InitializeTickArray>,
+    start_tick_index: i32,
+) -> Result<()> {
+    let ticks_in_array = ctx.accounts.pool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    require!(
+        start_tick_index.rem_euclid(ticks_in_array) == 0,
+        ErrorType::InvalidTickArray
+    );
+    // the array has to cover at least one valid tick
+    require!(
+        start_tick_index <= MAX_TICK && start_tick_index + ticks_in_array > MIN_TICK,
+        ErrorType::InvalidTickArray
+    );
+
+    let tick_array = &mut ctx.accounts.tick_array;
+    tick_array.pool = ctx.accounts.pool.key();
+    tick_array.start_tick_index = start_tick_index;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(start_tick_index: i32)]
+pub struct InitializeTickArray<'info> {
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            CONCENTRATED_SEED,
+        ],
+        bump,
+    )]
+    pub pool: Box<Account<'info, ConcentratedPool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TickArray::LEN,
+        seeds = [
+            pool.key().as_ref(),
+            TICK_ARRAY_SEED,
+            start_tick_index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub tick_array: Box<Account<'info, TickArray>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
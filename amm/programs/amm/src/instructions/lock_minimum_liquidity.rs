@@ -0,0 +1,242 @@
+use amm_math::liquidity::is_empty;
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::*,
+    system_program::{self, Transfer},
+    Discriminator,
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{AUTHORITY_SEED, LIQUIDITY_SEED, LOCKED_LIQUIDITY_SEED, MINIMUM_LIQUIDITY},
+    errors::ErrorType,
+    state::{Amm, CurveType, Observation, Pool},
+    utils::reserves,
+};
+
+// migrates a pool created before the minimum liquidity was locked. its first
+// deposit held MINIMUM_LIQUIDITY back without minting it, and withdrawals
+// added it to the supply instead. minting it into the locked account now
+// keeps every lp token worth the same. anyone can pay for it
+pub fn lock_minimum_liquidity(ctx: Context<LockMinimumLiquidity>) -> Result<()> {
+    let pool = load_pool(ctx.accounts)?;
+    require_keys_eq!(
+        pool.amm,
+        ctx.accounts.amm.key(),
+        ErrorCode::ConstraintHasOne
+    );
+    require_keys_eq!(
+        pool.mint_a,
+        ctx.accounts.mint_a.key(),
+        ErrorCode::ConstraintHasOne
+    );
+    require_keys_eq!(
+        pool.mint_b,
+        ctx.accounts.mint_b.key(),
+        ErrorCode::ConstraintHasOne
+    );
+    require!(!pool.locked, ErrorType::PoolLocked);
+
+    let reserve_a = reserves(ctx.accounts.pool_account_a.amount, pool.protocol_fees_a)?;
+    let reserve_b = reserves(ctx.accounts.pool_account_b.amount, pool.protocol_fees_b)?;
+    let lp_supply = ctx.accounts.mint_liquidity.supply;
+
+    // a pool that never got a deposit mints it on the first one
+    let amount = if is_empty(reserve_a, reserve_b, lp_supply) {
+        0
+    } else {
+        MINIMUM_LIQUIDITY
+    };
+
+    if amount > 0 {
+        let authority_bump = ctx.bumps.pool_authority;
+        let seeds = &[
+            &pool.amm.to_bytes(),
+            &ctx.accounts.mint_a.key().to_bytes(),
+            &ctx.accounts.mint_b.key().to_bytes(),
+            AUTHORITY_SEED,
+            &[authority_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint_liquidity.to_account_info(),
+                    to: ctx.accounts.locked_liquidity.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+    }
+
+    emit!(MinimumLiquidityLocked {
+        pool: ctx.accounts.pool.key(),
+        locked_liquidity: ctx.accounts.locked_liquidity.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+// the pool as it is now. a legacy pool is grown to the current size first,
+// the payer covering the rent, and starts out as a constant product pool
+// without protocol fees whose oracle history begins now
+fn load_pool(accounts: &LockMinimumLiquidity) -> Result<Pool> {
+    let pool_info = accounts.pool.to_account_info();
+    if pool_info.data_len() != Pool::LEGACY_LEN {
+        return Pool::try_deserialize(&mut &pool_info.try_borrow_data()?[..]);
+    }
+
+    let (amm, mint_a, mint_b) = {
+        let data = pool_info.try_borrow_data()?;
+        require!(
+            data.starts_with(Pool::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        <(Pubkey, Pubkey, Pubkey)>::deserialize(&mut &data[8..])?
+    };
+
+    let rent = Rent::get()?
+        .minimum_balance(Pool::LEN)
+        .saturating_sub(pool_info.lamports());
+    if rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.payer.to_account_info(),
+                    to: pool_info.clone(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    pool_info.resize(Pool::LEN)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut pool = Pool {
+        amm,
+        mint_a,
+        mint_b,
+        curve_type: CurveType::ConstantProduct,
+        last_update_ts: now,
+        reserve_a: accounts.pool_account_a.amount,
+        reserve_b: accounts.pool_account_b.amount,
+        ..Pool::default()
+    };
+    pool.observations[0] = Observation {
+        timestamp: now,
+        price_a_cumulative: 0,
+        price_b_cumulative: 0,
+    };
+    pool.try_serialize(&mut &mut pool_info.try_borrow_mut_data()?[..])?;
+
+    Ok(pool)
+}
+
+#[derive(Accounts)]
+pub struct LockMinimumLiquidity<'info> {
+    #[account(
+        seeds=[amm.id.as_ref()],
+        bump
+    )]
+    pub amm: Account<'info, Amm>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: PDA, safe because seeds are verified. read in the handler,
+    /// which migrates pools still at Pool::LEGACY_LEN
+    #[account(
+        mut,
+        seeds=[
+            amm.key().as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref()
+        ],
+        bump,
+        owner = crate::ID,
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: PDA, safe because seeds are verified
+    #[account(
+        seeds=[
+            amm.key().as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            AUTHORITY_SEED
+        ],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds=[
+            amm.key().as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            LIQUIDITY_SEED
+        ],
+        bump,
+        mint::authority = pool_authority,
+        mint::token_program = token_program
+    )]
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
+
+    // init fails once the account exists, so a pool is only migrated once
+    #[account(
+        init,
+        payer = payer,
+        seeds=[
+            amm.key().as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            LOCKED_LIQUIDITY_SEED
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub locked_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    // token program of the lp mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[event]
+pub struct MinimumLiquidityLocked {
+    pub pool: Pubkey,
+    pub locked_liquidity: Pubkey,
+    // zero for a pool that had no deposit yet
+    pub amount: u64,
+}
@@ -1,11 +1,55 @@
+mod accept_admin;
+mod collect_fees;
+mod collect_protocol_fees;
 mod create_amm;
+mod create_concentrated_pool;
 mod create_pool;
+mod decrease_liquidity;
 mod deposit_liquidity;
+mod flash_loan;
+mod increase_liquidity;
+mod initialize_tick_array;
+mod lock_minimum_liquidity;
+mod observe;
+mod open_position;
+mod pause_amm;
+mod pause_pool;
+mod propose_admin;
+mod ramp_amp;
+mod resume_amm;
+mod resume_pool;
+mod set_fee;
+mod stop_ramp_amp;
+mod swap_concentrated;
+mod swap_route;
 mod swap_tokens;
+mod swap_tokens_for_exact_tokens;
 mod withdraw_liquidity;
 
+pub use accept_admin::*;
+pub use collect_fees::*;
+pub use collect_protocol_fees::*;
 pub use create_amm::*;
+pub use create_concentrated_pool::*;
 pub use create_pool::*;
+pub use decrease_liquidity::*;
 pub use deposit_liquidity::*;
+pub use flash_loan::*;
+pub use increase_liquidity::*;
+pub use initialize_tick_array::*;
+pub use lock_minimum_liquidity::*;
+pub use observe::*;
+pub use open_position::*;
+pub use pause_amm::*;
+pub use pause_pool::*;
+pub use propose_admin::*;
+pub use ramp_amp::*;
+pub use resume_amm::*;
+pub use resume_pool::*;
+pub use set_fee::*;
+pub use stop_ramp_amp::*;
+pub use swap_concentrated::*;
+pub use swap_route::*;
 pub use swap_tokens::*;
+pub use swap_tokens_for_exact_tokens::*;
 pub use withdraw_liquidity::*;
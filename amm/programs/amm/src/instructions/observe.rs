@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ErrorType, state::Pool};
+
+// read only, other programs get the prices back through return data
+pub fn observe(ctx: Context<Observe>, seconds_ago: u32) -> Result<TwapPrices> {
+    // priced from the reserves stored at the last pool update, vault
+    // balances can be moved by donations and flash loans
+    let (price_a, price_b) = ctx
+        .accounts
+        .pool
+        .observe(Clock::get()?.unix_timestamp, seconds_ago)
+        .ok_or(ErrorType::ObservationTooOld)?;
+
+    Ok(TwapPrices { price_a, price_b })
+}
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    #[account(
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+// Q64.64 time weighted average prices, price_a is the price of a in b
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TwapPrices {
+    pub price_a: u128,
+    pub price_b: u128,
+}
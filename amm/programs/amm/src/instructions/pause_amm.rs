@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ErrorType, state::Amm};
+
+pub fn pause_amm(ctx: Context<PauseAmm>, allow_withdrawals: bool) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+
+    require!(!amm.paused, ErrorType::AlreadyPaused);
+
+    // swaps and deposits always stop, LPs can optionally still get out
+    amm.paused = true;
+    amm.withdrawals_paused = !allow_withdrawals;
+
+    emit!(AmmPaused {
+        amm: amm.key(),
+        allow_withdrawals,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PauseAmm<'info> {
+    #[account(
+        mut,
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = admin @ ErrorType::UnauthorizedAdmin
+    )]
+    pub amm: Account<'info, Amm>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct AmmPaused {
+    pub amm: Pubkey,
+    pub allow_withdrawals: bool,
+}
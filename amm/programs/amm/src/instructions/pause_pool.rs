@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ErrorType,
+    state::{Amm, Pool},
+};
+
+pub fn pause_pool(ctx: Context<PausePool>, allow_withdrawals: bool) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    require!(!pool.paused, ErrorType::AlreadyPaused);
+
+    // swaps and deposits always stop, LPs can optionally still get out
+    pool.paused = true;
+    pool.withdrawals_paused = !allow_withdrawals;
+
+    emit!(PoolPaused {
+        amm: pool.amm,
+        pool: pool.key(),
+        allow_withdrawals,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PausePool<'info> {
+    #[account(
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = admin @ ErrorType::UnauthorizedAdmin
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct PoolPaused {
+    pub amm: Pubkey,
+    pub pool: Pubkey,
+    pub allow_withdrawals: bool,
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ErrorType, state::Amm};
+
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+
+    // the handover only happens once new_admin accepts it,
+    // proposing the default pubkey cancels a pending proposal
+    amm.pending_admin = new_admin;
+
+    emit!(AdminProposed {
+        amm: amm.key(),
+        admin: amm.admin,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = admin @ ErrorType::UnauthorizedAdmin
+    )]
+    pub amm: Account<'info, Amm>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub amm: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
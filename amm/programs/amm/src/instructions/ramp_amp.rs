@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_AMP, MAX_AMP_CHANGE, MIN_AMP, MIN_AMP_RAMP_DURATION},
+    errors::ErrorType,
+    state::{Amm, CurveType, Pool},
+};
+
+pub fn ramp_amp(ctx: Context<RampAmp>, target_amp: u64, ramp_end_ts: i64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        pool.curve_type == CurveType::StableSwap,
+        ErrorType::InvalidCurveType
+    );
+    require!(
+        (MIN_AMP..=MAX_AMP).contains(&target_amp),
+        ErrorType::InvalidAmp
+    );
+    // slow ramps keep the amp from being yanked around to move prices
+    require!(
+        now >= pool.amp_ramp_start_ts + MIN_AMP_RAMP_DURATION
+            && ramp_end_ts >= now + MIN_AMP_RAMP_DURATION,
+        ErrorType::InvalidAmpRamp
+    );
+
+    let current_amp = pool.current_amp(now);
+    require!(
+        target_amp <= current_amp * MAX_AMP_CHANGE && target_amp * MAX_AMP_CHANGE >= current_amp,
+        ErrorType::InvalidAmp
+    );
+
+    pool.amp_initial = current_amp;
+    pool.amp_target = target_amp;
+    pool.amp_ramp_start_ts = now;
+    pool.amp_ramp_end_ts = ramp_end_ts;
+
+    emit!(AmpRampStarted {
+        pool: pool.key(),
+        initial_amp: current_amp,
+        target_amp,
+        ramp_start_ts: now,
+        ramp_end_ts,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RampAmp<'info> {
+    #[account(
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = admin @ ErrorType::UnauthorizedAdmin
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct AmpRampStarted {
+    pub pool: Pubkey,
+    pub initial_amp: u64,
+    pub target_amp: u64,
+    pub ramp_start_ts: i64,
+    pub ramp_end_ts: i64,
+}
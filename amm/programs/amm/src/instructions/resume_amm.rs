@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ErrorType, state::Amm};
+
+pub fn resume_amm(ctx: Context<ResumeAmm>) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+
+    require!(amm.paused, ErrorType::NotPaused);
+
+    amm.paused = false;
+    amm.withdrawals_paused = false;
+
+    emit!(AmmResumed { amm: amm.key() });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResumeAmm<'info> {
+    #[account(
+        mut,
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = admin @ ErrorType::UnauthorizedAdmin
+    )]
+    pub amm: Account<'info, Amm>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct AmmResumed {
+    pub amm: Pubkey,
+}
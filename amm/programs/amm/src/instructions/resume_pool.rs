@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ErrorType,
+    state::{Amm, Pool},
+};
+
+pub fn resume_pool(ctx: Context<ResumePool>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    require!(pool.paused, ErrorType::NotPaused);
+
+    pool.paused = false;
+    pool.withdrawals_paused = false;
+
+    emit!(PoolResumed {
+        amm: pool.amm,
+        pool: pool.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResumePool<'info> {
+    #[account(
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = admin @ ErrorType::UnauthorizedAdmin
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct PoolResumed {
+    pub amm: Pubkey,
+    pub pool: Pubkey,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::ErrorType, state::Amm};
+
+pub fn set_fee(ctx: Context<SetFee>, fee: u16, protocol_fee: u16) -> Result<()> {
+    let amm = &mut ctx.accounts.amm;
+
+    let old_fee = amm.fee;
+    let old_protocol_fee = amm.protocol_fee;
+
+    amm.fee = fee;
+    amm.protocol_fee = protocol_fee;
+
+    emit!(FeeUpdated {
+        amm: amm.key(),
+        old_fee,
+        new_fee: fee,
+        old_protocol_fee,
+        new_protocol_fee: protocol_fee,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(fee: u16, protocol_fee: u16)]
+pub struct SetFee<'info> {
+    #[account(
+        mut,
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = admin @ ErrorType::UnauthorizedAdmin,
+        constraint = fee < 10000 @ ErrorType::InvalidFee,
+        constraint = protocol_fee <= 10000 @ ErrorType::InvalidProtocolFee
+    )]
+    pub amm: Account<'info, Amm>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct FeeUpdated {
+    pub amm: Pubkey,
+    pub old_fee: u16,
+    pub new_fee: u16,
+    pub old_protocol_fee: u16,
+    pub new_protocol_fee: u16,
+}
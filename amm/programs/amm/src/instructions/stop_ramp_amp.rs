@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ErrorType,
+    state::{Amm, CurveType, Pool},
+};
+
+pub fn stop_ramp_amp(ctx: Context<StopRampAmp>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        pool.curve_type == CurveType::StableSwap,
+        ErrorType::InvalidCurveType
+    );
+
+    // freeze the amp wherever the ramp got to
+    let current_amp = pool.current_amp(now);
+    pool.amp_initial = current_amp;
+    pool.amp_target = current_amp;
+    pool.amp_ramp_start_ts = now;
+    pool.amp_ramp_end_ts = now;
+
+    emit!(AmpRampStopped {
+        pool: pool.key(),
+        amp: current_amp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct StopRampAmp<'info> {
+    #[account(
+        seeds = [amm.id.as_ref()],
+        bump,
+        has_one = admin @ ErrorType::UnauthorizedAdmin
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.key().as_ref(),
+            pool.mint_b.key().as_ref(),
+        ],
+        bump,
+        has_one = amm,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[event]
+pub struct AmpRampStopped {
+    pub pool: Pubkey,
+    pub amp: u64,
+}
@@ -0,0 +1,404 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    concentrated::{compute_swap_step, fee_growth, sqrt_price_at_tick, tick_at_sqrt_price},
+    constants::{
+        AUTHORITY_SEED, CONCENTRATED_SEED, MAX_SQRT_PRICE, MAX_TICK, MIN_SQRT_PRICE, MIN_TICK,
+        TICK_ARRAY_SIZE,
+    },
+    errors::ErrorType,
+    state::{Amm, ConcentratedPool, TickArray},
+    utils::{amount_with_transfer_fee, check_deadline},
+};
+
+// exact input swap, the tick arrays the price moves through are passed in
+// remaining_accounts starting with the one holding the current tick
+pub fn swap_concentrated<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapConcentrated<'info>>,
+    a_to_b: bool,
+    input_amount: u64,
+    min_output_amount: u64,
+    sqrt_price_limit: Option<u128>,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    require!(!ctx.accounts.amm.paused, ErrorType::AmmPaused);
+
+    let fee = ctx.accounts.amm.fee;
+    let pool = &mut ctx.accounts.pool;
+
+    // the bounds themselves are excluded so the current tick stays valid
+    let sqrt_price_limit = if a_to_b {
+        let limit = sqrt_price_limit.unwrap_or(MIN_SQRT_PRICE + 1);
+        require!(
+            limit > MIN_SQRT_PRICE && limit < pool.sqrt_price,
+            ErrorType::InvalidSqrtPrice
+        );
+        limit
+    } else {
+        let limit = sqrt_price_limit.unwrap_or(MAX_SQRT_PRICE - 1);
+        require!(
+            limit < MAX_SQRT_PRICE && limit > pool.sqrt_price,
+            ErrorType::InvalidSqrtPrice
+        );
+        limit
+    };
+
+    let mut tick_arrays = load_tick_arrays(ctx.remaining_accounts, pool, a_to_b)?;
+
+    let mut amount_remaining = input_amount;
+    let mut output = 0u64;
+    let mut fee_amount = 0u64;
+    let mut sqrt_price = pool.sqrt_price;
+    let mut tick_current = pool.tick_current;
+    let mut liquidity = pool.liquidity;
+
+    while amount_remaining > 0 && sqrt_price != sqrt_price_limit {
+        let (next_tick, next_tick_location) =
+            next_initialized_tick(&tick_arrays, tick_current, pool.tick_spacing, a_to_b);
+        let next_tick = next_tick.clamp(MIN_TICK, MAX_TICK);
+        let sqrt_price_next_tick =
+            sqrt_price_at_tick(next_tick).ok_or(ErrorType::LiquidityMathOverflow)?;
+
+        // already at the edge of the last tick array
+        if next_tick_location.is_none() && sqrt_price_next_tick == sqrt_price {
+            break;
+        }
+
+        let sqrt_price_target = if a_to_b {
+            sqrt_price_next_tick.max(sqrt_price_limit)
+        } else {
+            sqrt_price_next_tick.min(sqrt_price_limit)
+        };
+        let step = compute_swap_step(
+            sqrt_price,
+            sqrt_price_target,
+            liquidity,
+            amount_remaining,
+            fee,
+            a_to_b,
+        )
+        .ok_or(ErrorType::LiquidityMathOverflow)?;
+
+        amount_remaining = amount_remaining
+            .checked_sub(step.amount_in + step.fee_amount)
+            .ok_or(ErrorType::LiquidityMathOverflow)?;
+        output = output
+            .checked_add(step.amount_out)
+            .ok_or(ErrorType::LiquidityMathOverflow)?;
+        fee_amount += step.fee_amount;
+
+        // fees go to the liquidity that was in range for this step
+        if liquidity > 0 {
+            let growth = fee_growth(step.fee_amount, liquidity);
+            if a_to_b {
+                pool.fee_growth_global_a = pool.fee_growth_global_a.wrapping_add(growth);
+            } else {
+                pool.fee_growth_global_b = pool.fee_growth_global_b.wrapping_add(growth);
+            }
+        }
+
+        sqrt_price = step.sqrt_price_next;
+        if sqrt_price == sqrt_price_next_tick {
+            if let Some((index, offset)) = next_tick_location {
+                let tick = &mut tick_arrays[index].ticks[offset];
+                tick.cross(pool.fee_growth_global_a, pool.fee_growth_global_b);
+                let liquidity_net = if a_to_b {
+                    -tick.liquidity_net
+                } else {
+                    tick.liquidity_net
+                };
+                liquidity = liquidity
+                    .checked_add_signed(liquidity_net)
+                    .ok_or(ErrorType::LiquidityMathOverflow)?;
+            }
+
+            // an uncrossed tick at the edge of the arrays stays above the
+            // current one, so the next swap going up still crosses it
+            tick_current = if a_to_b || next_tick_location.is_none() {
+                next_tick - 1
+            } else {
+                next_tick
+            };
+        } else {
+            tick_current = tick_at_sqrt_price(sqrt_price).ok_or(ErrorType::InvalidSqrtPrice)?;
+        }
+    }
+
+    // only the price limit can leave part of the input unused
+    require!(
+        amount_remaining == 0 || sqrt_price == sqrt_price_limit,
+        ErrorType::InvalidTickArray
+    );
+    require!(output >= min_output_amount, ErrorType::OutputTooSmall);
+
+    pool.sqrt_price = sqrt_price;
+    pool.tick_current = tick_current;
+    pool.liquidity = liquidity;
+
+    for tick_array in &tick_arrays {
+        tick_array.exit(&crate::ID)?;
+    }
+
+    let input = input_amount - amount_remaining;
+    let accounts = &ctx.accounts;
+    let (
+        mint_in,
+        mint_out,
+        trader_account_in,
+        trader_account_out,
+        pool_account_in,
+        pool_account_out,
+        token_program_in,
+        token_program_out,
+    ) = if a_to_b {
+        (
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.trader_account_a,
+            &accounts.trader_account_b,
+            &accounts.pool_account_a,
+            &accounts.pool_account_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        )
+    } else {
+        (
+            &accounts.mint_b,
+            &accounts.mint_a,
+            &accounts.trader_account_b,
+            &accounts.trader_account_a,
+            &accounts.pool_account_b,
+            &accounts.pool_account_a,
+            &accounts.token_program_b,
+            &accounts.token_program_a,
+        )
+    };
+
+    // the swap was priced on what the pool receives, transfer fee mints send a bit more
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program_in.to_account_info(),
+            TransferChecked {
+                from: trader_account_in.to_account_info(),
+                mint: mint_in.to_account_info(),
+                to: pool_account_in.to_account_info(),
+                authority: accounts.trader.to_account_info(),
+            },
+        ),
+        amount_with_transfer_fee(mint_in, input)?,
+        mint_in.decimals,
+    )?;
+
+    let pool_key = accounts.pool.key();
+    let authority_seeds = &[
+        pool_key.as_ref(),
+        AUTHORITY_SEED,
+        &[ctx.bumps.pool_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program_out.to_account_info(),
+            TransferChecked {
+                from: pool_account_out.to_account_info(),
+                mint: mint_out.to_account_info(),
+                to: trader_account_out.to_account_info(),
+                authority: accounts.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        output,
+        mint_out.decimals,
+    )?;
+
+    emit!(ConcentratedSwap {
+        pool: pool_key,
+        trader: accounts.trader.key(),
+        a_to_b,
+        input_amount: input,
+        output_amount: output,
+        fee_amount,
+        sqrt_price,
+        tick_current,
+        liquidity,
+    });
+
+    Ok(())
+}
+
+// tick arrays have to belong to the pool and follow each other in the swap direction
+fn load_tick_arrays<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    pool: &Account<ConcentratedPool>,
+    a_to_b: bool,
+) -> Result<Vec<Account<'info, TickArray>>> {
+    require!(!accounts.is_empty(), ErrorType::InvalidTickArray);
+
+    let ticks_in_array = pool.tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    let mut start_tick_index = pool.tick_current.div_euclid(ticks_in_array) * ticks_in_array;
+    let mut tick_arrays = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        let tick_array = Account::<TickArray>::try_from(account)?;
+        require!(
+            tick_array.pool == pool.key() && tick_array.start_tick_index == start_tick_index,
+            ErrorType::InvalidTickArray
+        );
+        tick_arrays.push(tick_array);
+
+        start_tick_index = if a_to_b {
+            start_tick_index - ticks_in_array
+        } else {
+            start_tick_index + ticks_in_array
+        };
+    }
+
+    Ok(tick_arrays)
+}
+
+// next initialized tick in the swap direction with its (array, offset), or the
+// edge of the last tick array when there is none
+fn next_initialized_tick(
+    tick_arrays: &[Account<TickArray>],
+    tick_current: i32,
+    tick_spacing: u16,
+    a_to_b: bool,
+) -> (i32, Option<(usize, usize)>) {
+    let spacing = tick_spacing as i32;
+    let ticks_in_array = spacing * TICK_ARRAY_SIZE as i32;
+    let first_start = tick_arrays[0].start_tick_index;
+    let last_start = tick_arrays[tick_arrays.len() - 1].start_tick_index;
+
+    let location = |tick: i32| {
+        let array_start = tick.div_euclid(ticks_in_array) * ticks_in_array;
+        let index = ((array_start - first_start).abs() / ticks_in_array) as usize;
+        let offset = ((tick - array_start) / spacing) as usize;
+        (index, offset)
+    };
+
+    if a_to_b {
+        // the current tick itself is crossed when going down
+        let mut tick = tick_current.div_euclid(spacing) * spacing;
+        while tick >= last_start {
+            let (index, offset) = location(tick);
+            if tick_arrays[index].ticks[offset].is_initialized() {
+                return (tick, Some((index, offset)));
+            }
+            tick -= spacing;
+        }
+        (last_start, None)
+    } else {
+        let end = last_start + ticks_in_array;
+        let mut tick = tick_current.div_euclid(spacing) * spacing + spacing;
+        while tick < end {
+            let (index, offset) = location(tick);
+            if tick_arrays[index].ticks[offset].is_initialized() {
+                return (tick, Some((index, offset)));
+            }
+            tick += spacing;
+        }
+        (end, None)
+    }
+}
+
+#[derive(Accounts)]
+pub struct SwapConcentrated<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump
+    )]
+    pub amm: Box<Account<'info, Amm>>,
+
+    #[account(
+        mut,
+        seeds = [
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            CONCENTRATED_SEED,
+        ],
+        bump,
+        has_one = amm,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, ConcentratedPool>>,
+
+    /// CHECK: PDA used only as authority
+    #[account(
+        seeds = [
+            pool.key().as_ref(),
+            AUTHORITY_SEED,
+        ],
+        bump
+    )]
+    pub pool_authority: AccountInfo<'info>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
+    )]
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
+    )]
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint_a,
+        associated_token::authority = trader,
+        associated_token::token_program = token_program_a,
+    )]
+    pub trader_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint_b,
+        associated_token::authority = trader,
+        associated_token::token_program = token_program_b,
+    )]
+    pub trader_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub trader: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[event]
+pub struct ConcentratedSwap {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub a_to_b: bool,
+    // amount the pool received, fee included
+    pub input_amount: u64,
+    pub output_amount: u64,
+    // fee taken from the input, all of it goes to the positions in range
+    pub fee_amount: u64,
+    pub sqrt_price: u128,
+    pub tick_current: i32,
+    pub liquidity: u128,
+}
@@ -0,0 +1,310 @@
+use amm_math::{protocol_fee, swap_fee};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::AUTHORITY_SEED,
+    errors::ErrorType,
+    instructions::Swap,
+    state::{Amm, Pool},
+    utils::{check_deadline, reserves},
+};
+
+// every hop of the route is passed in remaining_accounts as
+// [pool, pool_authority, pool_account_in, pool_account_out, mint_out, token_program_out],
+// token_program_out is the token program of mint_out
+pub const ACCOUNTS_PER_HOP: usize = 6;
+
+pub fn swap_route<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    input_amount: u64,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+
+    let amm = &ctx.accounts.amm;
+    require!(!amm.paused, ErrorType::AmmPaused);
+
+    let hops = ctx.remaining_accounts;
+    require!(
+        !hops.is_empty() && hops.len() % ACCOUNTS_PER_HOP == 0,
+        ErrorType::InvalidRoute
+    );
+
+    let input = if input_amount > ctx.accounts.trader_source.amount {
+        ctx.accounts.trader_source.amount
+    } else {
+        input_amount
+    };
+
+    // tokens only enter the route once, every hop sends its output
+    // straight into the vault of the next pool
+    let first_pool_account_in = &hops[2];
+    let balance_before = InterfaceAccount::<TokenAccount>::try_from(first_pool_account_in)?.amount;
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.trader_source.to_account_info(),
+                mint: ctx.accounts.source_mint.to_account_info(),
+                to: first_pool_account_in.to_account_info(),
+                authority: ctx.accounts.trader.to_account_info(),
+            },
+        ),
+        input,
+        ctx.accounts.source_mint.decimals,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    // transfer fee mints deliver less than what was sent, every hop is
+    // priced on what its pool actually received
+    let mut amount = InterfaceAccount::<TokenAccount>::try_from(first_pool_account_in)?
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(ErrorType::MathOverflow)?;
+    let mut mint_in = ctx.accounts.trader_source.mint;
+    // the input vault of a hop belongs to the output token program of the one before
+    let mut token_program_in = ctx.accounts.token_program.key();
+    let mut visited_pools: Vec<Pubkey> = Vec::with_capacity(hops.len() / ACCOUNTS_PER_HOP);
+
+    for (i, hop) in hops.chunks(ACCOUNTS_PER_HOP).enumerate() {
+        let mut pool = Box::new(Account::<Pool>::try_from(&hop[0])?);
+        let pool_authority = &hop[1];
+        let mut pool_account_in = InterfaceAccount::<TokenAccount>::try_from(&hop[2])?;
+        let mut pool_account_out = InterfaceAccount::<TokenAccount>::try_from(&hop[3])?;
+        let hop_mint_out = InterfaceAccount::<Mint>::try_from(&hop[4])?;
+        let token_program_out = Interface::<TokenInterface>::try_from(&hop[5])?;
+        require_keys_eq!(
+            *hop_mint_out.to_account_info().owner,
+            token_program_out.key(),
+            ErrorType::InvalidRoute
+        );
+
+        require!(pool.amm == amm.key(), ErrorType::InvalidRoute);
+        require!(!pool.paused, ErrorType::PoolPaused);
+        require!(!pool.locked, ErrorType::PoolLocked);
+        // balances below are read once, a pool can't be traded twice in one route
+        require!(
+            !visited_pools.contains(&pool.key()),
+            ErrorType::InvalidRoute
+        );
+        visited_pools.push(pool.key());
+
+        let swap_a = if mint_in == pool.mint_a {
+            true
+        } else if mint_in == pool.mint_b {
+            false
+        } else {
+            return err!(ErrorType::InvalidMint);
+        };
+        let mint_out = if swap_a { pool.mint_b } else { pool.mint_a };
+        require_keys_eq!(hop_mint_out.key(), mint_out, ErrorType::InvalidRoute);
+
+        let (authority_key, authority_bump) = Pubkey::find_program_address(
+            &[
+                pool.amm.as_ref(),
+                pool.mint_a.as_ref(),
+                pool.mint_b.as_ref(),
+                AUTHORITY_SEED,
+            ],
+            ctx.program_id,
+        );
+        require_keys_eq!(pool_authority.key(), authority_key, ErrorType::InvalidRoute);
+        require_keys_eq!(
+            pool_account_in.key(),
+            get_associated_token_address_with_program_id(
+                &authority_key,
+                &mint_in,
+                &token_program_in
+            ),
+            ErrorType::InvalidRoute
+        );
+        require_keys_eq!(
+            pool_account_out.key(),
+            get_associated_token_address_with_program_id(
+                &authority_key,
+                &mint_out,
+                &token_program_out.key()
+            ),
+            ErrorType::InvalidRoute
+        );
+
+        let fee_amount = swap_fee(amount, amm.fee);
+        let taxed_input = amount - fee_amount;
+        let protocol_fee_amount = protocol_fee(fee_amount, amm.protocol_fee);
+
+        let (protocol_fees_in, protocol_fees_out) = if swap_a {
+            (pool.protocol_fees_a, pool.protocol_fees_b)
+        } else {
+            (pool.protocol_fees_b, pool.protocol_fees_a)
+        };
+        // the input of this hop has already landed in the vault
+        let reserve_in = reserves(pool_account_in.amount, protocol_fees_in)?
+            .checked_sub(amount)
+            .ok_or(ErrorType::MathOverflow)?;
+        let reserve_out = reserves(pool_account_out.amount, protocol_fees_out)?;
+
+        let output = pool
+            .curve(now)
+            .amount_out(taxed_input, reserve_in, reserve_out)
+            .map_err(ErrorType::from)?;
+
+        let is_last_hop = (i + 1) * ACCOUNTS_PER_HOP == hops.len();
+        let next_pool_account_in = if is_last_hop {
+            require_keys_eq!(
+                ctx.accounts.trader_destination.mint,
+                mint_out,
+                ErrorType::InvalidRoute
+            );
+            None
+        } else {
+            Some(&hops[(i + 1) * ACCOUNTS_PER_HOP + 2])
+        };
+        let destination = match next_pool_account_in {
+            Some(next_pool_account_in) => next_pool_account_in.to_account_info(),
+            None => ctx.accounts.trader_destination.to_account_info(),
+        };
+
+        let authority_seeds = &[
+            pool.amm.as_ref(),
+            pool.mint_a.as_ref(),
+            pool.mint_b.as_ref(),
+            AUTHORITY_SEED,
+            &[authority_bump],
+        ];
+        let signer_seeds = &[&authority_seeds[..]];
+
+        let destination_balance_before = match next_pool_account_in {
+            Some(next_pool_account_in) => {
+                InterfaceAccount::<TokenAccount>::try_from(next_pool_account_in)?.amount
+            }
+            None => 0,
+        };
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program_out.to_account_info(),
+                TransferChecked {
+                    from: pool_account_out.to_account_info(),
+                    mint: hop_mint_out.to_account_info(),
+                    to: destination,
+                    authority: pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            output,
+            hop_mint_out.decimals,
+        )?;
+
+        let protocol_fees = if swap_a {
+            &mut pool.protocol_fees_a
+        } else {
+            &mut pool.protocol_fees_b
+        };
+        *protocol_fees = protocol_fees
+            .checked_add(protocol_fee_amount)
+            .ok_or(ErrorType::MathOverflow)?;
+
+        // same check as a single swap, the protocol fee set aside above is
+        // left out on both sides
+        pool_account_in.reload()?;
+        pool_account_out.reload()?;
+        let (reserves_before, reserves_after) = if swap_a {
+            (
+                (reserve_in, reserve_out),
+                (
+                    reserves(pool_account_in.amount, pool.protocol_fees_a)?,
+                    reserves(pool_account_out.amount, pool.protocol_fees_b)?,
+                ),
+            )
+        } else {
+            (
+                (reserve_out, reserve_in),
+                (
+                    reserves(pool_account_out.amount, pool.protocol_fees_a)?,
+                    reserves(pool_account_in.amount, pool.protocol_fees_b)?,
+                ),
+            )
+        };
+        let invariant_held = pool
+            .curve(now)
+            .invariant_held(reserves_before, reserves_after)
+            .map_err(ErrorType::from)?;
+        require!(invariant_held, ErrorType::InvariantViolated);
+        pool.update_oracle(reserves_after.0, reserves_after.1, now);
+        pool.exit(ctx.program_id)?;
+
+        msg!(
+            "Hop {}: traded {} tokens ({} after fees) for {}",
+            i,
+            amount,
+            taxed_input,
+            output
+        );
+
+        let (reserve_a, reserve_b) = reserves_after;
+        emit!(Swap {
+            pool: pool.key(),
+            trader: ctx.accounts.trader.key(),
+            mint_in,
+            mint_out,
+            input_amount: amount,
+            output_amount: output,
+            fee_amount,
+            reserve_a,
+            reserve_b,
+        });
+
+        // the last hop is checked against what the pool sends, like a single swap
+        amount = match next_pool_account_in {
+            Some(next_pool_account_in) => {
+                InterfaceAccount::<TokenAccount>::try_from(next_pool_account_in)?.amount
+                    - destination_balance_before
+            }
+            None => output,
+        };
+        mint_in = mint_out;
+        token_program_in = token_program_out.key();
+    }
+
+    if amount < min_output_amount {
+        return err!(ErrorType::OutputTooSmall);
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(
+        seeds = [
+            amm.id.as_ref()
+        ],
+        bump
+    )]
+    pub amm: Account<'info, Amm>,
+
+    #[account(mint::token_program = token_program)]
+    pub source_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = source_mint,
+        token::token_program = token_program,
+        constraint = trader_source.owner == trader.key()
+    )]
+    pub trader_source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = trader_destination.owner == trader.key()
+    )]
+    pub trader_destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub trader: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,14 +1,15 @@
+use amm_math::{protocol_fee, swap_fee};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
-use fixed::types::I64F64;
 
 use crate::{
     constants::AUTHORITY_SEED,
     errors::ErrorType,
     state::{Amm, Pool},
+    utils::{check_deadline, reserves},
 };
 
 pub fn swap_tokens_for_tokens(
@@ -16,7 +17,13 @@ pub fn swap_tokens_for_tokens(
     swap_a: bool,
     input_amount: u64,
     min_output_amount: u64,
+    deadline: Option<i64>,
 ) -> Result<()> {
+    check_deadline(deadline)?;
+    require!(!ctx.accounts.amm.paused, ErrorType::AmmPaused);
+    require!(!ctx.accounts.pool.paused, ErrorType::PoolPaused);
+    require!(!ctx.accounts.pool.locked, ErrorType::PoolLocked);
+
     let input = if swap_a && input_amount > ctx.accounts.trader_account_a.amount {
         ctx.accounts.trader_account_a.amount
     } else if !swap_a && input_amount > ctx.accounts.trader_account_b.amount {
@@ -25,40 +32,122 @@ pub fn swap_tokens_for_tokens(
         input_amount
     };
 
+    // transfer fee mints deliver less than what was sent, the swap is priced
+    // on what the pool actually received
+    let input = transfer_in(ctx.accounts, swap_a, input)?;
+
     let amm = &ctx.accounts.amm;
-    let taxed_input = input - input * amm.fee as u64 / 10000;
+    let fee_amount = swap_fee(input, amm.fee);
+    let taxed_input = input - fee_amount;
+    // part of the fee set aside for the admin, it never becomes part of the reserves
+    let protocol_fee_amount = protocol_fee(fee_amount, amm.protocol_fee);
 
+    let pool = &ctx.accounts.pool;
     let pool_a = &ctx.accounts.pool_account_a;
     let pool_b = &ctx.accounts.pool_account_b;
+    let curve = pool.curve(Clock::get()?.unix_timestamp);
 
     let output = if swap_a {
-        I64F64::from_num(taxed_input)
-            .checked_mul(I64F64::from_num(pool_b.amount))
-            .unwrap()
-            .checked_div(
-                I64F64::from_num(pool_a.amount)
-                    .checked_add(I64F64::from_num(taxed_input))
-                    .unwrap(),
-            )
-            .unwrap()
+        let reserve_a = reserves(pool_a.amount, pool.protocol_fees_a)?
+            .checked_sub(input)
+            .ok_or(ErrorType::MathOverflow)?;
+        let reserve_b = reserves(pool_b.amount, pool.protocol_fees_b)?;
+        curve.amount_out(taxed_input, reserve_a, reserve_b)
     } else {
-        I64F64::from_num(taxed_input)
-            .checked_mul(I64F64::from_num(pool_a.amount))
-            .unwrap()
-            .checked_div(
-                I64F64::from_num(pool_b.amount)
-                    .checked_add(I64F64::from_num(taxed_input))
-                    .unwrap(),
-            )
-            .unwrap()
+        let reserve_a = reserves(pool_a.amount, pool.protocol_fees_a)?;
+        let reserve_b = reserves(pool_b.amount, pool.protocol_fees_b)?
+            .checked_sub(input)
+            .ok_or(ErrorType::MathOverflow)?;
+        curve.amount_out(taxed_input, reserve_b, reserve_a)
     }
-    .to_num::<u64>();
+    .map_err(ErrorType::from)?;
 
     if output < min_output_amount {
         return err!(ErrorType::OutputTooSmall);
     }
 
-    let invariant = pool_a.amount * pool_b.amount;
+    execute_swap(ctx, swap_a, input, taxed_input, output, protocol_fee_amount)
+}
+
+// moves `amount` from the trader into the pool and returns how much arrived
+pub(crate) fn transfer_in(
+    accounts: &mut SwapExactTokensForTokens,
+    swap_a: bool,
+    amount: u64,
+) -> Result<u64> {
+    let (from, to, mint, token_program) = if swap_a {
+        (
+            &accounts.trader_account_a,
+            &mut accounts.pool_account_a,
+            &accounts.mint_a,
+            &accounts.token_program_a,
+        )
+    } else {
+        (
+            &accounts.trader_account_b,
+            &mut accounts.pool_account_b,
+            &accounts.mint_b,
+            &accounts.token_program_b,
+        )
+    };
+
+    let balance_before = to.amount;
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: accounts.trader.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )?;
+    to.reload()?;
+
+    Ok(to
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(ErrorType::MathOverflow)?)
+}
+
+// pays out an already priced swap whose input is sitting in the pool and
+// checks the invariant, shared by the exact input and exact output swaps
+pub(crate) fn execute_swap(
+    ctx: Context<SwapExactTokensForTokens>,
+    swap_a: bool,
+    input: u64,
+    taxed_input: u64,
+    output: u64,
+    protocol_fee_amount: u64,
+) -> Result<()> {
+    // reserves from before the input came in
+    let reserve_a = reserves(
+        ctx.accounts.pool_account_a.amount,
+        ctx.accounts.pool.protocol_fees_a,
+    )?;
+    let reserve_b = reserves(
+        ctx.accounts.pool_account_b.amount,
+        ctx.accounts.pool.protocol_fees_b,
+    )?;
+    let (reserve_a, reserve_b) = if swap_a {
+        (
+            reserve_a
+                .checked_sub(input)
+                .ok_or(ErrorType::MathOverflow)?,
+            reserve_b,
+        )
+    } else {
+        (
+            reserve_a,
+            reserve_b
+                .checked_sub(input)
+                .ok_or(ErrorType::MathOverflow)?,
+        )
+    };
+    let now = Clock::get()?.unix_timestamp;
 
     let authority_bump = ctx.bumps.pool_authority;
     let authority_seeds = &[
@@ -70,63 +159,49 @@ pub fn swap_tokens_for_tokens(
     ];
     let signer_seeds = &[&authority_seeds[..]];
 
-    // swapping with a
+    // transfer from pool to user
     if swap_a {
-        // transfer from user to pool
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.trader_account_a.to_account_info(),
-                    to: ctx.accounts.pool_account_a.to_account_info(),
-                    authority: ctx.accounts.trader.to_account_info(),
-                },
-            ),
-            input,
-        )?;
-
-        // transfer from pool to user
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                ctx.accounts.token_program_b.to_account_info(),
+                TransferChecked {
                     from: ctx.accounts.pool_account_b.to_account_info(),
+                    mint: ctx.accounts.mint_b.to_account_info(),
                     to: ctx.accounts.trader_account_b.to_account_info(),
                     authority: ctx.accounts.pool_authority.to_account_info(),
                 },
                 signer_seeds,
             ),
             output,
+            ctx.accounts.mint_b.decimals,
         )?;
-    }
-    // swapping with b
-    else {
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.trader_account_b.to_account_info(),
-                    to: ctx.accounts.pool_account_b.to_account_info(),
-                    authority: ctx.accounts.trader.to_account_info(),
-                },
-            ),
-            input,
-        )?;
-
-        token::transfer(
+    } else {
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                ctx.accounts.token_program_a.to_account_info(),
+                TransferChecked {
                     from: ctx.accounts.pool_account_a.to_account_info(),
+                    mint: ctx.accounts.mint_a.to_account_info(),
                     to: ctx.accounts.trader_account_a.to_account_info(),
                     authority: ctx.accounts.pool_authority.to_account_info(),
                 },
                 signer_seeds,
             ),
             output,
+            ctx.accounts.mint_a.decimals,
         )?;
     }
 
+    let pool = &mut ctx.accounts.pool;
+    let protocol_fees = if swap_a {
+        &mut pool.protocol_fees_a
+    } else {
+        &mut pool.protocol_fees_b
+    };
+    *protocol_fees = protocol_fees
+        .checked_add(protocol_fee_amount)
+        .ok_or(ErrorType::MathOverflow)?;
+
     msg!(
         "Traded {} tokens ({} after fees) for {}",
         input,
@@ -134,11 +209,40 @@ pub fn swap_tokens_for_tokens(
         output
     );
 
+    // the protocol fee set aside above is left out on both sides
     ctx.accounts.pool_account_a.reload()?;
     ctx.accounts.pool_account_b.reload()?;
-    if invariant > ctx.accounts.pool_account_a.amount * ctx.accounts.pool_account_a.amount {
-        return err!(ErrorType::InvariantViolated);
-    }
+    let pool = &ctx.accounts.pool;
+    let reserves_after = (
+        reserves(ctx.accounts.pool_account_a.amount, pool.protocol_fees_a)?,
+        reserves(ctx.accounts.pool_account_b.amount, pool.protocol_fees_b)?,
+    );
+    let invariant_held = pool
+        .curve(now)
+        .invariant_held((reserve_a, reserve_b), reserves_after)
+        .map_err(ErrorType::from)?;
+    require!(invariant_held, ErrorType::InvariantViolated);
+
+    ctx.accounts
+        .pool
+        .update_oracle(reserves_after.0, reserves_after.1, now);
+
+    let (mint_in, mint_out) = if swap_a {
+        (ctx.accounts.mint_a.key(), ctx.accounts.mint_b.key())
+    } else {
+        (ctx.accounts.mint_b.key(), ctx.accounts.mint_a.key())
+    };
+    emit!(Swap {
+        pool: ctx.accounts.pool.key(),
+        trader: ctx.accounts.trader.key(),
+        mint_in,
+        mint_out,
+        input_amount: input,
+        output_amount: output,
+        fee_amount: input - taxed_input,
+        reserve_a: reserves_after.0,
+        reserve_b: reserves_after.1,
+    });
 
     Ok(())
 }
@@ -154,6 +258,7 @@ pub struct SwapExactTokensForTokens<'info> {
     pub amm: Account<'info, Amm>,
 
     #[account(
+          mut,
           seeds = [
               pool.amm.as_ref(),
               pool.mint_a.key().as_ref(),
@@ -164,7 +269,7 @@ pub struct SwapExactTokensForTokens<'info> {
           has_one = mint_a,
           has_one = mint_b,
       )]
-    pub pool: Account<'info, Pool>,
+    pub pool: Box<Account<'info, Pool>>,
 
     /// CHECK: PDA used only as authority
     #[account(
@@ -179,38 +284,42 @@ pub struct SwapExactTokensForTokens<'info> {
     )]
     pub pool_authority: AccountInfo<'info>,
 
-    pub mint_a: Account<'info, Mint>,
-    pub mint_b: Account<'info, Mint>,
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_a,
     )]
-    pub pool_account_a: Account<'info, TokenAccount>,
+    pub pool_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = pool_authority,
+        associated_token::token_program = token_program_b,
     )]
-    pub pool_account_b: Account<'info, TokenAccount>,
+    pub pool_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_a,
-        associated_token::authority = trader
+        associated_token::authority = trader,
+        associated_token::token_program = token_program_a,
     )]
-    pub trader_account_a: Account<'info, TokenAccount>,
+    pub trader_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_b,
-        associated_token::authority = trader
+        associated_token::authority = trader,
+        associated_token::token_program = token_program_b,
     )]
-    pub trader_account_b: Account<'info, TokenAccount>,
+    pub trader_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub trader: Signer<'info>,
@@ -219,6 +328,21 @@ pub struct SwapExactTokensForTokens<'info> {
     pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
+
+#[event]
+pub struct Swap {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub input_amount: u64,
+    pub output_amount: u64,
+    // total fee taken from the input, protocol share included
+    pub fee_amount: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
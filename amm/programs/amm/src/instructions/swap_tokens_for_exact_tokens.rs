@@ -0,0 +1,78 @@
+use amm_math::{input_before_fee, protocol_fee, swap_fee};
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::ErrorType,
+    instructions::{execute_swap, transfer_in, SwapExactTokensForTokens},
+    utils::{amount_with_transfer_fee, check_deadline, reserves},
+};
+
+pub fn swap_tokens_for_exact_tokens(
+    ctx: Context<SwapExactTokensForTokens>,
+    swap_a: bool,
+    output_amount: u64,
+    max_input_amount: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    require!(!ctx.accounts.amm.paused, ErrorType::AmmPaused);
+    require!(!ctx.accounts.pool.paused, ErrorType::PoolPaused);
+    require!(!ctx.accounts.pool.locked, ErrorType::PoolLocked);
+
+    let amm = &ctx.accounts.amm;
+    let pool = &ctx.accounts.pool;
+
+    let reserve_a = reserves(ctx.accounts.pool_account_a.amount, pool.protocol_fees_a)?;
+    let reserve_b = reserves(ctx.accounts.pool_account_b.amount, pool.protocol_fees_b)?;
+
+    let (reserve_in, reserve_out) = if swap_a {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+
+    require!(
+        output_amount > 0 && output_amount < reserve_out,
+        ErrorType::InsufficientReserves
+    );
+
+    let taxed_input = pool
+        .curve(Clock::get()?.unix_timestamp)
+        .amount_in(output_amount, reserve_in, reserve_out)
+        .map_err(ErrorType::from)?;
+
+    // rounded up in favor of the pool
+    let input = input_before_fee(taxed_input, amm.fee).map_err(ErrorType::from)?;
+    // the reserves after the swap still have to fit in a u64
+    reserve_in
+        .checked_add(input)
+        .ok_or(ErrorType::MathOverflow)?;
+
+    // the trader pays the mint's transfer fee on top so `input` reaches the pool
+    let mint_in = if swap_a {
+        &ctx.accounts.mint_a
+    } else {
+        &ctx.accounts.mint_b
+    };
+    let gross_input = amount_with_transfer_fee(mint_in, input)?;
+
+    if gross_input > max_input_amount {
+        return err!(ErrorType::InputTooLarge);
+    }
+
+    let input = transfer_in(ctx.accounts, swap_a, gross_input)?;
+
+    // fee is charged exactly like an exact input swap of `input` would be
+    let amm = &ctx.accounts.amm;
+    let fee_amount = swap_fee(input, amm.fee);
+    let protocol_fee_amount = protocol_fee(fee_amount, amm.protocol_fee);
+
+    execute_swap(
+        ctx,
+        swap_a,
+        input,
+        input - fee_amount,
+        output_amount,
+        protocol_fee_amount,
+    )
+}
@@ -1,17 +1,29 @@
+use amm_math::liquidity::withdraw_amounts;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Burn, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
-use fixed::types::I64F64;
 
 use crate::{
-    constants::{AUTHORITY_SEED, LIQUIDITY_SEED, MINIMUM_LIQUIDITY},
+    constants::{AUTHORITY_SEED, LIQUIDITY_SEED, LOCKED_LIQUIDITY_SEED},
     errors::ErrorType,
     state::{Amm, Pool},
+    utils::{check_deadline, reserves},
 };
 
-pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, amount: u64) -> Result<()> {
+pub fn withdraw_liquidity(
+    ctx: Context<WithdrawLiquidity>,
+    amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: Option<i64>,
+) -> Result<()> {
+    check_deadline(deadline)?;
+    require!(!ctx.accounts.amm.withdrawals_paused, ErrorType::AmmPaused);
+    require!(!ctx.accounts.pool.withdrawals_paused, ErrorType::PoolPaused);
+    require!(!ctx.accounts.pool.locked, ErrorType::PoolLocked);
+
     let authority_bump = ctx.bumps.pool_authority;
     let seeds = &[
         &ctx.accounts.pool.amm.to_bytes(),
@@ -27,57 +39,70 @@ pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, amount: u64) -> Resul
         ErrorType::InsufficientLiquidity
     );
 
-    let supply = ctx.accounts.mint_liquidity.supply + MINIMUM_LIQUIDITY;
-
-    // amount_a = (amount * pool_token_account_a.amount)/supply
-    let amount_a = I64F64::from_num(amount)
-        .checked_mul(I64F64::from_num(ctx.accounts.pool_token_account_a.amount))
-        .unwrap()
-        .checked_div(I64F64::from_num(supply))
-        .unwrap()
-        .floor()
-        .to_num::<u64>();
-
-    let amount_b = I64F64::from_num(amount)
-        .checked_mul(I64F64::from_num(ctx.accounts.pool_token_account_b.amount))
-        .unwrap()
-        .checked_div(I64F64::from_num(supply))
-        .unwrap()
-        .floor()
-        .to_num::<u64>();
+    // the locked minimum liquidity is part of the supply, so its share of the
+    // reserves never leaves the pool
+    let supply = ctx.accounts.mint_liquidity.supply;
+
+    // accrued protocol fees are owed to the admin, not to LPs
+    let reserve_a = reserves(
+        ctx.accounts.pool_token_account_a.amount,
+        ctx.accounts.pool.protocol_fees_a,
+    )?;
+    let reserve_b = reserves(
+        ctx.accounts.pool_token_account_b.amount,
+        ctx.accounts.pool.protocol_fees_b,
+    )?;
+
+    // amount_a = (amount * reserve_a)/supply
+    let (amount_a, amount_b) =
+        withdraw_amounts(amount, reserve_a, reserve_b, supply).map_err(ErrorType::from)?;
 
     require!(amount_a > 0 && amount_b > 0, ErrorType::ZeroWithdrawal);
+    require!(
+        amount_a >= min_amount_a && amount_b >= min_amount_b,
+        ErrorType::OutputTooSmall
+    );
+
+    ctx.accounts.pool.update_oracle(
+        reserve_a - amount_a,
+        reserve_b - amount_b,
+        Clock::get()?.unix_timestamp,
+    );
 
     // transfer from pool 1
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_program_a.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.pool_token_account_a.to_account_info(),
+                mint: ctx.accounts.mint_a.to_account_info(),
                 to: ctx.accounts.depositer_account_token_a.to_account_info(),
                 authority: ctx.accounts.pool_authority.to_account_info(),
             },
             signer_seeds,
         ),
         amount_a,
+        ctx.accounts.mint_a.decimals,
     )?;
 
     // transfer from pool 2
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            ctx.accounts.token_program_b.to_account_info(),
+            TransferChecked {
                 from: ctx.accounts.pool_token_account_b.to_account_info(),
+                mint: ctx.accounts.mint_b.to_account_info(),
                 to: ctx.accounts.depositer_account_token_b.to_account_info(),
                 authority: ctx.accounts.pool_authority.to_account_info(),
             },
             signer_seeds,
         ),
         amount_b,
+        ctx.accounts.mint_b.decimals,
     )?;
 
     // burn lp tokens
-    token::burn(
+    token_interface::burn(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             Burn {
@@ -89,6 +114,16 @@ pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, amount: u64) -> Resul
         amount,
     )?;
 
+    emit!(LiquidityRemoved {
+        pool: ctx.accounts.pool.key(),
+        depositor: ctx.accounts.depositer.key(),
+        amount_a,
+        amount_b,
+        liquidity: amount,
+        reserve_a: reserve_a - amount_a,
+        reserve_b: reserve_b - amount_b,
+    });
+
     Ok(())
 }
 
@@ -100,8 +135,8 @@ pub struct WithdrawLiquidity<'info> {
     )]
     pub amm: Box<Account<'info, Amm>>,
 
-    pub mint_a: Account<'info, Mint>,
-    pub mint_b: Account<'info, Mint>,
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -111,9 +146,10 @@ pub struct WithdrawLiquidity<'info> {
             mint_b.key().as_ref(),
             LIQUIDITY_SEED
         ],
-        bump
+        bump,
+        mint::token_program = token_program
     )]
-    pub mint_liquidity: Account<'info, Mint>,
+    pub mint_liquidity: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -129,6 +165,22 @@ pub struct WithdrawLiquidity<'info> {
     )]
     pub pool: Box<Account<'info, Pool>>,
 
+    // only has to exist, withdrawals are priced against a supply that includes
+    // the locked minimum liquidity. older pools need lock_minimum_liquidity first
+    #[account(
+        seeds=[
+            pool.amm.as_ref(),
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            LOCKED_LIQUIDITY_SEED
+        ],
+        bump,
+        token::mint = mint_liquidity,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub locked_liquidity: Box<InterfaceAccount<'info, TokenAccount>>,
+
     /// CHECK: This is a PDA derived from known seeds; Anchor verifies the seeds and bump.
     #[account(
         mut,
@@ -146,40 +198,43 @@ pub struct WithdrawLiquidity<'info> {
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = pool_authority,
-
+        associated_token::token_program = token_program_a,
     )]
-    pub pool_token_account_a: Account<'info, TokenAccount>,
+    pub pool_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = pool_authority,
-
+        associated_token::token_program = token_program_b,
     )]
-    pub pool_token_account_b: Account<'info, TokenAccount>,
+    pub pool_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_liquidity,
-        associated_token::authority = depositer
+        associated_token::authority = depositer,
+        associated_token::token_program = token_program
     )]
-    pub depositer_account_token_lp: Account<'info, TokenAccount>,
+    pub depositer_account_token_lp: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_a,
-        associated_token::authority = depositer
+        associated_token::authority = depositer,
+        associated_token::token_program = token_program_a
     )]
-    pub depositer_account_token_a: Account<'info, TokenAccount>,
+    pub depositer_account_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_b,
-        associated_token::authority = depositer
+        associated_token::authority = depositer,
+        associated_token::token_program = token_program_b
     )]
-    pub depositer_account_token_b: Account<'info, TokenAccount>,
+    pub depositer_account_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub depositer: Signer<'info>,
@@ -187,7 +242,22 @@ pub struct WithdrawLiquidity<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    // token program of the lp mint
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
+
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub depositor: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    // lp tokens burned from the depositor
+    pub liquidity: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
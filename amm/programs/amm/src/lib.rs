@@ -2,10 +2,15 @@ use anchor_lang::prelude::*;
 
 declare_id!("5ozBoN2Ajg7YxTKgFgykmt23k2kHGKKQ9yievwpNRDKb");
 
-mod constants;
-mod errors;
-mod instructions;
-mod state;
+// public so off-chain clients can share the account types and math
+pub mod concentrated;
+pub mod constants;
+pub mod errors;
+pub mod instructions;
+pub mod state;
+mod utils;
+
+use state::CurveType;
 
 #[program]
 pub mod swap_example {
@@ -13,20 +18,40 @@ pub mod swap_example {
     pub use super::instructions::*;
     pub use super::*;
 
-    pub fn create_amm(ctx: Context<CreateAmm>, id: Pubkey, fee: u16) -> Result<()> {
-        instructions::create_amm(ctx, id, fee)
+    pub fn create_amm(
+        ctx: Context<CreateAmm>,
+        id: Pubkey,
+        fee: u16,
+        protocol_fee: u16,
+    ) -> Result<()> {
+        instructions::create_amm(ctx, id, fee, protocol_fee)
     }
 
-    pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
-        instructions::create_pool(ctx)
+    pub fn create_pool(ctx: Context<CreatePool>, curve_type: CurveType, amp: u64) -> Result<()> {
+        instructions::create_pool(ctx, curve_type, amp)
     }
 
     pub fn deposit_liquidity(
         ctx: Context<DepositLiquidity>,
         amount_a: u64,
         amount_b: u64,
+        min_liquidity_out: u64,
+    ) -> Result<()> {
+        instructions::deposit_liquidity(ctx, amount_a, amount_b, min_liquidity_out, None)
+    }
+
+    pub fn deposit_liquidity_with_deadline(
+        ctx: Context<DepositLiquidity>,
+        amount_a: u64,
+        amount_b: u64,
+        min_liquidity_out: u64,
+        deadline: i64,
     ) -> Result<()> {
-        instructions::deposit_liquidity(ctx, amount_a, amount_b)
+        instructions::deposit_liquidity(ctx, amount_a, amount_b, min_liquidity_out, Some(deadline))
+    }
+
+    pub fn lock_minimum_liquidity(ctx: Context<LockMinimumLiquidity>) -> Result<()> {
+        instructions::lock_minimum_liquidity(ctx)
     }
 
     pub fn swap_tokens(
@@ -35,10 +60,184 @@ pub mod swap_example {
         input_amount: u64,
         min_output_amount: u64,
     ) -> Result<()> {
-        instructions::swap_tokens_for_tokens(ctx, swap_a, input_amount, min_output_amount)
+        instructions::swap_tokens_for_tokens(ctx, swap_a, input_amount, min_output_amount, None)
+    }
+
+    pub fn swap_tokens_with_deadline(
+        ctx: Context<SwapExactTokensForTokens>,
+        swap_a: bool,
+        input_amount: u64,
+        min_output_amount: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::swap_tokens_for_tokens(
+            ctx,
+            swap_a,
+            input_amount,
+            min_output_amount,
+            Some(deadline),
+        )
+    }
+
+    pub fn swap_tokens_for_exact_tokens(
+        ctx: Context<SwapExactTokensForTokens>,
+        swap_a: bool,
+        output_amount: u64,
+        max_input_amount: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::swap_tokens_for_exact_tokens(
+            ctx,
+            swap_a,
+            output_amount,
+            max_input_amount,
+            deadline,
+        )
+    }
+
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        input_amount: u64,
+        min_output_amount: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::swap_route(ctx, input_amount, min_output_amount, deadline)
+    }
+
+    pub fn withdraw_liquidity(
+        ctx: Context<WithdrawLiquidity>,
+        amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        instructions::withdraw_liquidity(ctx, amount, min_amount_a, min_amount_b, None)
+    }
+
+    pub fn withdraw_liquidity_with_deadline(
+        ctx: Context<WithdrawLiquidity>,
+        amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::withdraw_liquidity(ctx, amount, min_amount_a, min_amount_b, Some(deadline))
+    }
+
+    pub fn flash_loan<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FlashLoan<'info>>,
+        amount_a: u64,
+        amount_b: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash_loan(ctx, amount_a, amount_b, data)
+    }
+
+    pub fn observe(ctx: Context<Observe>, seconds_ago: u32) -> Result<TwapPrices> {
+        instructions::observe(ctx, seconds_ago)
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        instructions::collect_protocol_fees(ctx)
+    }
+
+    pub fn set_fee(ctx: Context<SetFee>, fee: u16, protocol_fee: u16) -> Result<()> {
+        instructions::set_fee(ctx, fee, protocol_fee)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    pub fn pause_amm(ctx: Context<PauseAmm>, allow_withdrawals: bool) -> Result<()> {
+        instructions::pause_amm(ctx, allow_withdrawals)
+    }
+
+    pub fn resume_amm(ctx: Context<ResumeAmm>) -> Result<()> {
+        instructions::resume_amm(ctx)
+    }
+
+    pub fn pause_pool(ctx: Context<PausePool>, allow_withdrawals: bool) -> Result<()> {
+        instructions::pause_pool(ctx, allow_withdrawals)
+    }
+
+    pub fn resume_pool(ctx: Context<ResumePool>) -> Result<()> {
+        instructions::resume_pool(ctx)
+    }
+
+    pub fn ramp_amp(ctx: Context<RampAmp>, target_amp: u64, ramp_end_ts: i64) -> Result<()> {
+        instructions::ramp_amp(ctx, target_amp, ramp_end_ts)
+    }
+
+    pub fn stop_ramp_amp(ctx: Context<StopRampAmp>) -> Result<()> {
+        instructions::stop_ramp_amp(ctx)
+    }
+
+    pub fn create_concentrated_pool(
+        ctx: Context<CreateConcentratedPool>,
+        tick_spacing: u16,
+        sqrt_price: u128,
+    ) -> Result<()> {
+        instructions::create_concentrated_pool(ctx, tick_spacing, sqrt_price)
+    }
+
+    pub fn initialize_tick_array(
+        ctx: Context<InitializeTickArray>,
+        start_tick_index: i32,
+    ) -> Result<()> {
+        instructions::initialize_tick_array(ctx, start_tick_index)
+    }
+
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<()> {
+        instructions::open_position(ctx, tick_lower, tick_upper)
+    }
+
+    pub fn increase_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        liquidity: u128,
+        max_amount_a: u64,
+        max_amount_b: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::increase_liquidity(ctx, liquidity, max_amount_a, max_amount_b, deadline)
+    }
+
+    pub fn decrease_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        liquidity: u128,
+        min_amount_a: u64,
+        min_amount_b: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::decrease_liquidity(ctx, liquidity, min_amount_a, min_amount_b, deadline)
     }
 
-    pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, amount: u64) -> Result<()> {
-        instructions::withdraw_liquidity(ctx, amount)
+    pub fn collect_fees(ctx: Context<ModifyLiquidity>) -> Result<()> {
+        instructions::collect_fees(ctx)
+    }
+
+    pub fn swap_concentrated<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapConcentrated<'info>>,
+        a_to_b: bool,
+        input_amount: u64,
+        min_output_amount: u64,
+        sqrt_price_limit: Option<u128>,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::swap_concentrated(
+            ctx,
+            a_to_b,
+            input_amount,
+            min_output_amount,
+            sqrt_price_limit,
+            deadline,
+        )
     }
 }
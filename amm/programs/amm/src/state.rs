@@ -1,15 +1,28 @@
+use amm_math::Curve;
 use anchor_lang::prelude::*;
 
+use crate::{
+    concentrated::fees_earned,
+    constants::{OBSERVATION_CAPACITY, OBSERVATION_PERIOD, TICK_ARRAY_SIZE},
+};
+
 #[account]
 #[derive(Default)]
 pub struct Amm {
     pub id: Pubkey,
     pub admin: Pubkey,
     pub fee: u16,
+    // share of the swap fee (in basis points of the fee) that accrues to the admin
+    pub protocol_fee: u16,
+    // admin proposed by propose_admin, default pubkey when there is none
+    pub pending_admin: Pubkey,
+    // circuit breaker for every pool of this amm
+    pub paused: bool,
+    pub withdrawals_paused: bool,
 }
 
 impl Amm {
-    pub const LEN: usize = 8 + 32 + 32 + 2;
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 2 + 32 + 1 + 1;
 }
 
 #[account]
@@ -18,8 +31,366 @@ pub struct Pool {
     pub amm: Pubkey,
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
+    // protocol fees sitting in the vaults that are not part of the reserves
+    pub protocol_fees_a: u64,
+    pub protocol_fees_b: u64,
+    pub paused: bool,
+    pub withdrawals_paused: bool,
+    // uniswap v2 style price accumulators, prices are Q64.64 and wrap on overflow
+    pub price_a_cumulative: u128,
+    pub price_b_cumulative: u128,
+    pub last_update_ts: i64,
+    // ring buffer of past accumulator values, observation_index is the latest one
+    pub observations: [Observation; OBSERVATION_CAPACITY],
+    pub observation_index: u8,
+    pub curve_type: CurveType,
+    // stableswap amplification, moves linearly from amp_initial to amp_target
+    // between the two ramp timestamps, unused for constant product pools
+    pub amp_initial: u64,
+    pub amp_target: u64,
+    pub amp_ramp_start_ts: i64,
+    pub amp_ramp_end_ts: i64,
+    // set while a flash loan is out, every other pool instruction refuses to run
+    pub locked: bool,
+    // reserves as of the last pool update, the price the accumulators use.
+    // tokens sent straight to the vaults don't move it until the next update
+    pub reserve_a: u64,
+    pub reserve_b: u64,
 }
 
 impl Pool {
-    pub const LEN: usize = 8 + 32 + 32 + 32;
+    pub const LEN: usize = 8
+        + 32
+        + 32
+        + 32
+        + 8
+        + 8
+        + 1
+        + 1
+        + 16
+        + 16
+        + 8
+        + Observation::LEN * OBSERVATION_CAPACITY
+        + 1
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 8
+        + 8;
+
+    // the discriminator and the three keys, all pools held before they grew
+    // fees, the oracle and the curve. lock_minimum_liquidity migrates them
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 32;
+
+    pub fn current_amp(&self, now: i64) -> u64 {
+        if now >= self.amp_ramp_end_ts || self.amp_ramp_end_ts <= self.amp_ramp_start_ts {
+            return self.amp_target;
+        }
+
+        let elapsed = (now - self.amp_ramp_start_ts).max(0) as u128;
+        let duration = (self.amp_ramp_end_ts - self.amp_ramp_start_ts) as u128;
+        if self.amp_target > self.amp_initial {
+            let change = (self.amp_target - self.amp_initial) as u128 * elapsed / duration;
+            self.amp_initial + change as u64
+        } else {
+            let change = (self.amp_initial - self.amp_target) as u128 * elapsed / duration;
+            self.amp_initial - change as u64
+        }
+    }
+
+    // the pool's pricing curve for the shared swap and liquidity math
+    pub fn curve(&self, now: i64) -> Curve {
+        match self.curve_type {
+            CurveType::ConstantProduct => Curve::ConstantProduct,
+            CurveType::StableSwap => Curve::StableSwap {
+                amp: self.current_amp(now),
+            },
+        }
+    }
+
+    // runs after the reserves change, the stored reserves priced the pool
+    // since the last update and get accumulated before the new ones replace them
+    pub fn update_oracle(&mut self, reserve_a: u64, reserve_b: u64, now: i64) {
+        let elapsed = now - self.last_update_ts;
+        if elapsed > 0 {
+            if self.reserve_a != 0 && self.reserve_b != 0 {
+                let (price_a, price_b) = spot_prices(self.reserve_a, self.reserve_b);
+                self.price_a_cumulative = self
+                    .price_a_cumulative
+                    .wrapping_add(price_a.wrapping_mul(elapsed as u128));
+                self.price_b_cumulative = self
+                    .price_b_cumulative
+                    .wrapping_add(price_b.wrapping_mul(elapsed as u128));
+            }
+            self.last_update_ts = now;
+
+            let latest = self.observations[self.observation_index as usize];
+            if now - latest.timestamp >= OBSERVATION_PERIOD {
+                self.observation_index =
+                    ((self.observation_index as usize + 1) % OBSERVATION_CAPACITY) as u8;
+                self.observations[self.observation_index as usize] = Observation {
+                    timestamp: now,
+                    price_a_cumulative: self.price_a_cumulative,
+                    price_b_cumulative: self.price_b_cumulative,
+                };
+            }
+        }
+
+        self.reserve_a = reserve_a;
+        self.reserve_b = reserve_b;
+    }
+
+    // time weighted average prices (Q64.64) over at least the last `seconds_ago` seconds,
+    // None when the ring buffer doesn't reach back that far
+    pub fn observe(&self, now: i64, seconds_ago: u32) -> Option<(u128, u128)> {
+        let target = now - seconds_ago as i64;
+
+        // newest observation that is at least `seconds_ago` old
+        let start = (0..OBSERVATION_CAPACITY)
+            .map(|i| {
+                let index = (self.observation_index as usize + OBSERVATION_CAPACITY - i)
+                    % OBSERVATION_CAPACITY;
+                self.observations[index]
+            })
+            .find(|observation| observation.timestamp != 0 && observation.timestamp <= target)?;
+
+        // bring the accumulators up to now with the last stored price
+        let mut price_a_cumulative = self.price_a_cumulative;
+        let mut price_b_cumulative = self.price_b_cumulative;
+        let elapsed = now - self.last_update_ts;
+        if elapsed > 0 && self.reserve_a != 0 && self.reserve_b != 0 {
+            let (price_a, price_b) = spot_prices(self.reserve_a, self.reserve_b);
+            price_a_cumulative =
+                price_a_cumulative.wrapping_add(price_a.wrapping_mul(elapsed as u128));
+            price_b_cumulative =
+                price_b_cumulative.wrapping_add(price_b.wrapping_mul(elapsed as u128));
+        }
+
+        let window = (now - start.timestamp) as u128;
+        if window == 0 {
+            return None;
+        }
+
+        Some((
+            price_a_cumulative.wrapping_sub(start.price_a_cumulative) / window,
+            price_b_cumulative.wrapping_sub(start.price_b_cumulative) / window,
+        ))
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct ConcentratedPool {
+    pub amm: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    // positions can only start and end on multiples of the spacing
+    pub tick_spacing: u16,
+    // Q64.64 square root of the price of a in b
+    pub sqrt_price: u128,
+    // greatest tick at or below the current price
+    pub tick_current: i32,
+    // liquidity of the positions whose range holds the current price
+    pub liquidity: u128,
+    // Q64.64 fees earned per unit of liquidity since the pool was created,
+    // these wrap on overflow and only their differences matter
+    pub fee_growth_global_a: u128,
+    pub fee_growth_global_b: u128,
+}
+
+impl ConcentratedPool {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 2 + 16 + 4 + 16 + 16 + 16;
+
+    // fees earned per unit of liquidity between two ticks
+    pub fn fee_growth_inside(
+        &self,
+        tick_lower_index: i32,
+        tick_lower: &Tick,
+        tick_upper_index: i32,
+        tick_upper: &Tick,
+    ) -> (u128, u128) {
+        let (below_a, below_b) = if self.tick_current >= tick_lower_index {
+            (
+                tick_lower.fee_growth_outside_a,
+                tick_lower.fee_growth_outside_b,
+            )
+        } else {
+            (
+                self.fee_growth_global_a
+                    .wrapping_sub(tick_lower.fee_growth_outside_a),
+                self.fee_growth_global_b
+                    .wrapping_sub(tick_lower.fee_growth_outside_b),
+            )
+        };
+
+        let (above_a, above_b) = if self.tick_current < tick_upper_index {
+            (
+                tick_upper.fee_growth_outside_a,
+                tick_upper.fee_growth_outside_b,
+            )
+        } else {
+            (
+                self.fee_growth_global_a
+                    .wrapping_sub(tick_upper.fee_growth_outside_a),
+                self.fee_growth_global_b
+                    .wrapping_sub(tick_upper.fee_growth_outside_b),
+            )
+        };
+
+        (
+            self.fee_growth_global_a
+                .wrapping_sub(below_a)
+                .wrapping_sub(above_a),
+            self.fee_growth_global_b
+                .wrapping_sub(below_b)
+                .wrapping_sub(above_b),
+        )
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct TickArray {
+    pub pool: Pubkey,
+    // ticks start_tick_index, start_tick_index + tick_spacing, ...
+    pub start_tick_index: i32,
+    pub ticks: [Tick; TICK_ARRAY_SIZE],
+}
+
+impl TickArray {
+    pub const LEN: usize = 8 + 32 + 4 + Tick::LEN * TICK_ARRAY_SIZE;
+
+    // index of `tick` in this array, None when it's not covered or not on the spacing
+    pub fn tick_offset(&self, tick: i32, tick_spacing: u16) -> Option<usize> {
+        let spacing = tick_spacing as i32;
+        let distance = tick.checked_sub(self.start_tick_index)?;
+        if distance < 0 || distance % spacing != 0 {
+            return None;
+        }
+
+        let offset = (distance / spacing) as usize;
+        (offset < TICK_ARRAY_SIZE).then_some(offset)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Tick {
+    // liquidity added to the pool when the price crosses this tick going up
+    pub liquidity_net: i128,
+    // liquidity of every position using this tick, zero when uninitialized
+    pub liquidity_gross: u128,
+    // Q64.64 fee growth on the other side of this tick from the current price
+    pub fee_growth_outside_a: u128,
+    pub fee_growth_outside_b: u128,
+}
+
+impl Tick {
+    pub const LEN: usize = 16 + 16 + 16 + 16;
+
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross > 0
+    }
+
+    // adds liquidity to (or removes it from) a position bound, None on overflow
+    pub fn update(
+        &mut self,
+        tick_index: i32,
+        pool: &ConcentratedPool,
+        liquidity_delta: i128,
+        upper: bool,
+    ) -> Option<()> {
+        // all the fees so far are assumed to be earned below the current price
+        if !self.is_initialized() && tick_index <= pool.tick_current {
+            self.fee_growth_outside_a = pool.fee_growth_global_a;
+            self.fee_growth_outside_b = pool.fee_growth_global_b;
+        }
+
+        self.liquidity_gross = self.liquidity_gross.checked_add_signed(liquidity_delta)?;
+        self.liquidity_net = if upper {
+            self.liquidity_net.checked_sub(liquidity_delta)?
+        } else {
+            self.liquidity_net.checked_add(liquidity_delta)?
+        };
+
+        Some(())
+    }
+
+    // flips the fee growth to the other side when the price moves through the tick
+    pub fn cross(&mut self, fee_growth_global_a: u128, fee_growth_global_b: u128) {
+        self.fee_growth_outside_a = fee_growth_global_a.wrapping_sub(self.fee_growth_outside_a);
+        self.fee_growth_outside_b = fee_growth_global_b.wrapping_sub(self.fee_growth_outside_b);
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    // fee growth inside the range when the fees were last credited
+    pub fee_growth_inside_a_last: u128,
+    pub fee_growth_inside_b_last: u128,
+    pub fees_owed_a: u64,
+    pub fees_owed_b: u64,
+}
+
+impl Position {
+    pub const LEN: usize = 8 + 32 + 32 + 4 + 4 + 16 + 16 + 16 + 8 + 8;
+
+    // credits the fees earned since the last update, None on overflow
+    pub fn update_fees(
+        &mut self,
+        fee_growth_inside_a: u128,
+        fee_growth_inside_b: u128,
+    ) -> Option<()> {
+        let earned_a = fees_earned(
+            fee_growth_inside_a.wrapping_sub(self.fee_growth_inside_a_last),
+            self.liquidity,
+        )?;
+        let earned_b = fees_earned(
+            fee_growth_inside_b.wrapping_sub(self.fee_growth_inside_b_last),
+            self.liquidity,
+        )?;
+
+        self.fees_owed_a = self.fees_owed_a.checked_add(earned_a)?;
+        self.fees_owed_b = self.fees_owed_b.checked_add(earned_b)?;
+        self.fee_growth_inside_a_last = fee_growth_inside_a;
+        self.fee_growth_inside_b_last = fee_growth_inside_b;
+
+        Some(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CurveType {
+    // x * y = k, the default for any pair
+    #[default]
+    ConstantProduct,
+    // curve style invariant for pegged pairs
+    StableSwap,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct Observation {
+    pub timestamp: i64,
+    pub price_a_cumulative: u128,
+    pub price_b_cumulative: u128,
+}
+
+impl Observation {
+    pub const LEN: usize = 8 + 16 + 16;
+}
+
+// price of a in b and of b in a, both Q64.64
+fn spot_prices(reserve_a: u64, reserve_b: u64) -> (u128, u128) {
+    (
+        ((reserve_b as u128) << 64) / reserve_a as u128,
+        ((reserve_a as u128) << 64) / reserve_b as u128,
+    )
 }
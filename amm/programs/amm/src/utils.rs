@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensions,
+        },
+    },
+    token_interface::Mint,
+};
+
+use crate::errors::ErrorType;
+
+// transactions that linger past their deadline would execute at stale prices
+pub fn check_deadline(deadline: Option<i64>) -> Result<()> {
+    if let Some(deadline) = deadline {
+        require!(
+            Clock::get()?.unix_timestamp <= deadline,
+            ErrorType::DeadlineExceeded
+        );
+    }
+
+    Ok(())
+}
+
+// accrued protocol fees sit in the vault but are owed to the admin, the rest
+// of the vault is what backs the pool
+pub fn reserves(vault: u64, fees: u64) -> Result<u64> {
+    Ok(vault.checked_sub(fees).ok_or(ErrorType::MathOverflow)?)
+}
+
+// pools are keyed by their mints in this order, so a pair only ever gets one
+// pool. both create instructions run this in their account constraints
+pub fn check_mint_order(mint_a: &Pubkey, mint_b: &Pubkey) -> Result<()> {
+    require_keys_neq!(*mint_a, *mint_b, ErrorType::IdenticalMints);
+    require!(mint_a < mint_b, ErrorType::UnorderedMints);
+
+    Ok(())
+}
+
+// token-2022 extensions a pool can live with, anything else (non transferable,
+// permanent delegate, transfer hooks, confidential transfers...) could freeze
+// or drain the vaults behind the pool's back
+const SUPPORTED_MINT_EXTENSIONS: &[ExtensionType] = &[
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
+
+pub fn check_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(());
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    for extension in mint.get_extension_types()? {
+        require!(
+            SUPPORTED_MINT_EXTENSIONS.contains(&extension),
+            ErrorType::UnsupportedMintExtension
+        );
+    }
+
+    Ok(())
+}
+
+// how much has to be sent so that `amount` arrives after the mint's transfer fee
+pub fn amount_with_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(amount);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(ErrorType::InvalidTransferFee)?,
+        Err(_) => 0,
+    };
+
+    Ok(amount.checked_add(fee).ok_or(ErrorType::MathOverflow)?)
+}
@@ -0,0 +1,765 @@
+use amm::{
+    concentrated::sqrt_price_at_tick,
+    constants::{
+        AUTHORITY_SEED, CONCENTRATED_SEED, MAX_TICK_SPACING, MIN_SQRT_PRICE, MIN_TICK,
+        POSITION_SEED, TICK_ARRAY_SIZE,
+    },
+    errors::ErrorType,
+    state::{Amm, ConcentratedPool, Position, Tick, TickArray},
+};
+use amm_client::pda::amm_address;
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{instruction::Instruction, system_program},
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    token::ID as TOKEN_PROGRAM_ID,
+    token_2022::ID as TOKEN_2022_PROGRAM_ID,
+};
+
+use crate::{
+    fixtures::{
+        assert_anchor_error, assert_error, ix, mint_account, ordered_keys, program_account,
+        token_account, with_remaining, DEFAULT_SUPPLY, FEE, PROTOCOL_FEE,
+    },
+    svm::{Svm, TxResult},
+};
+
+const TICK_SPACING: u16 = 10;
+const TICKS_IN_ARRAY: i32 = TICK_SPACING as i32 * TICK_ARRAY_SIZE as i32;
+const TICK_LOWER: i32 = -100;
+const TICK_UPPER: i32 = 100;
+const LIQUIDITY: u128 = 1_000_000_000;
+const VAULT: u64 = 10_000_000;
+
+// a pool priced at tick 0 holding a single position over [TICK_LOWER, TICK_UPPER),
+// with the tick arrays on both sides of the current price
+struct TestConcentratedPool {
+    svm: Svm,
+    amm: Pubkey,
+    pool: Pubkey,
+    pool_authority: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    // starting at -TICKS_IN_ARRAY and 0
+    tick_array_below: Pubkey,
+    tick_array_above: Pubkey,
+    position: Pubkey,
+    owner: Pubkey,
+    admin: Pubkey,
+    token_program_a: Pubkey,
+    token_program_b: Pubkey,
+}
+
+impl TestConcentratedPool {
+    fn new() -> Self {
+        Self::with_token_programs(TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID)
+    }
+
+    // each mint belongs to its own token program
+    fn with_token_programs(token_program_a: Pubkey, token_program_b: Pubkey) -> Self {
+        let mut svm = Svm::new();
+        let admin = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        svm.airdrop(&owner, 10_000_000_000);
+
+        let id = Pubkey::new_unique();
+        let amm = amm_address(&id);
+        svm.set_account(
+            amm,
+            program_account(
+                &Amm {
+                    id,
+                    admin,
+                    fee: FEE,
+                    protocol_fee: PROTOCOL_FEE,
+                    ..Amm::default()
+                },
+                Amm::LEN,
+            ),
+        );
+
+        let (mint_a, mint_b) = ordered_keys();
+        let pool = Pubkey::find_program_address(
+            &[
+                amm.as_ref(),
+                mint_a.as_ref(),
+                mint_b.as_ref(),
+                CONCENTRATED_SEED,
+            ],
+            &amm::ID,
+        )
+        .0;
+        let pool_authority =
+            Pubkey::find_program_address(&[pool.as_ref(), AUTHORITY_SEED], &amm::ID).0;
+        svm.set_account(
+            pool,
+            program_account(
+                &ConcentratedPool {
+                    amm,
+                    mint_a,
+                    mint_b,
+                    tick_spacing: TICK_SPACING,
+                    sqrt_price: sqrt_price_at_tick(0).unwrap(),
+                    tick_current: 0,
+                    liquidity: LIQUIDITY,
+                    ..ConcentratedPool::default()
+                },
+                ConcentratedPool::LEN,
+            ),
+        );
+
+        for (mint, token_program) in [(mint_a, token_program_a), (mint_b, token_program_b)] {
+            svm.set_account(mint, mint_account(&admin, DEFAULT_SUPPLY, &token_program));
+            for (holder, amount) in [(pool_authority, VAULT), (owner, DEFAULT_SUPPLY - VAULT)] {
+                svm.set_account(
+                    get_associated_token_address_with_program_id(&holder, &mint, &token_program),
+                    token_account(&mint, &holder, amount, &token_program),
+                );
+            }
+        }
+
+        let mut test = Self {
+            svm,
+            amm,
+            pool,
+            pool_authority,
+            mint_a,
+            mint_b,
+            tick_array_below: Pubkey::new_unique(),
+            tick_array_above: Pubkey::new_unique(),
+            position: Pubkey::new_unique(),
+            owner,
+            admin,
+            token_program_a,
+            token_program_b,
+        };
+
+        let mut below = TickArray {
+            pool,
+            start_tick_index: -TICKS_IN_ARRAY,
+            ticks: [Tick::default(); TICK_ARRAY_SIZE],
+        };
+        let mut above = TickArray {
+            pool,
+            start_tick_index: 0,
+            ticks: [Tick::default(); TICK_ARRAY_SIZE],
+        };
+        below.ticks[below.tick_offset(TICK_LOWER, TICK_SPACING).unwrap()] = Tick {
+            liquidity_net: LIQUIDITY as i128,
+            liquidity_gross: LIQUIDITY,
+            ..Tick::default()
+        };
+        above.ticks[above.tick_offset(TICK_UPPER, TICK_SPACING).unwrap()] = Tick {
+            liquidity_net: -(LIQUIDITY as i128),
+            liquidity_gross: LIQUIDITY,
+            ..Tick::default()
+        };
+        test.set_tick_array(test.tick_array_below, &below);
+        test.set_tick_array(test.tick_array_above, &above);
+
+        test.set_position(&Position {
+            pool,
+            owner,
+            tick_lower: TICK_LOWER,
+            tick_upper: TICK_UPPER,
+            liquidity: LIQUIDITY,
+            ..Position::default()
+        });
+
+        test
+    }
+
+    fn account(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &self.token_program(mint))
+    }
+
+    fn token_program(&self, mint: &Pubkey) -> Pubkey {
+        if *mint == self.mint_b {
+            self.token_program_b
+        } else {
+            self.token_program_a
+        }
+    }
+
+    // what the owner and the pool hold of a and b
+    fn balances(&self) -> ((u64, u64), (u64, u64)) {
+        let balance = |owner: &Pubkey, mint: &Pubkey| self.svm.balance(&self.account(owner, mint));
+        (
+            (
+                balance(&self.owner, &self.mint_a),
+                balance(&self.owner, &self.mint_b),
+            ),
+            (
+                balance(&self.pool_authority, &self.mint_a),
+                balance(&self.pool_authority, &self.mint_b),
+            ),
+        )
+    }
+
+    fn set_tick_array(&mut self, key: Pubkey, tick_array: &TickArray) {
+        self.svm
+            .set_account(key, program_account(tick_array, TickArray::LEN));
+    }
+
+    fn set_position(&mut self, position: &Position) {
+        self.svm
+            .set_account(self.position, program_account(position, Position::LEN));
+    }
+
+    fn update_amm(&mut self, update: impl FnOnce(&mut Amm)) {
+        let mut amm: Amm = self.svm.get(&self.amm);
+        update(&mut amm);
+        self.svm
+            .set_account(self.amm, program_account(&amm, Amm::LEN));
+    }
+
+    fn update_pool(&mut self, update: impl FnOnce(&mut ConcentratedPool)) {
+        let mut pool: ConcentratedPool = self.svm.get(&self.pool);
+        update(&mut pool);
+        self.svm
+            .set_account(self.pool, program_account(&pool, ConcentratedPool::LEN));
+    }
+
+    fn send(&mut self, instruction: Instruction) -> TxResult {
+        self.svm.send(&[instruction])
+    }
+
+    fn swap_ix(
+        &self,
+        a_to_b: bool,
+        input_amount: u64,
+        min_output_amount: u64,
+        sqrt_price_limit: Option<u128>,
+        tick_arrays: &[Pubkey],
+    ) -> Instruction {
+        let instruction = ix(
+            amm::accounts::SwapConcentrated {
+                amm: self.amm,
+                pool: self.pool,
+                pool_authority: self.pool_authority,
+                mint_a: self.mint_a,
+                mint_b: self.mint_b,
+                pool_account_a: self.account(&self.pool_authority, &self.mint_a),
+                pool_account_b: self.account(&self.pool_authority, &self.mint_b),
+                trader_account_a: self.account(&self.owner, &self.mint_a),
+                trader_account_b: self.account(&self.owner, &self.mint_b),
+                trader: self.owner,
+                payer: self.owner,
+                system_program: system_program::ID,
+                token_program_a: self.token_program_a,
+                token_program_b: self.token_program_b,
+                associated_token_program: associated_token::ID,
+            },
+            amm::instruction::SwapConcentrated {
+                a_to_b,
+                input_amount,
+                min_output_amount,
+                sqrt_price_limit,
+                deadline: None,
+            },
+        );
+        let tick_arrays: Vec<_> = tick_arrays
+            .iter()
+            .map(|key| AccountMeta::new(*key, false))
+            .collect();
+        with_remaining(instruction, &tick_arrays)
+    }
+
+    fn modify_liquidity(&self, data: impl anchor_lang::InstructionData) -> Instruction {
+        ix(
+            amm::accounts::ModifyLiquidity {
+                amm: self.amm,
+                pool: self.pool,
+                pool_authority: self.pool_authority,
+                position: self.position,
+                tick_array_lower: self.tick_array_below,
+                tick_array_upper: self.tick_array_above,
+                mint_a: self.mint_a,
+                mint_b: self.mint_b,
+                pool_account_a: self.account(&self.pool_authority, &self.mint_a),
+                pool_account_b: self.account(&self.pool_authority, &self.mint_b),
+                owner_account_a: self.account(&self.owner, &self.mint_a),
+                owner_account_b: self.account(&self.owner, &self.mint_b),
+                owner: self.owner,
+                payer: self.owner,
+                system_program: system_program::ID,
+                token_program_a: self.token_program_a,
+                token_program_b: self.token_program_b,
+                associated_token_program: associated_token::ID,
+            },
+            data,
+        )
+    }
+
+    fn increase_ix(&self, liquidity: u128, max_amount_a: u64, max_amount_b: u64) -> Instruction {
+        self.modify_liquidity(amm::instruction::IncreaseLiquidity {
+            liquidity,
+            max_amount_a,
+            max_amount_b,
+            deadline: None,
+        })
+    }
+
+    fn decrease_ix(&self, liquidity: u128, min_amount_a: u64, min_amount_b: u64) -> Instruction {
+        self.modify_liquidity(amm::instruction::DecreaseLiquidity {
+            liquidity,
+            min_amount_a,
+            min_amount_b,
+            deadline: None,
+        })
+    }
+
+    fn collect_fees_ix(&self) -> Instruction {
+        self.modify_liquidity(amm::instruction::CollectFees {})
+    }
+}
+
+#[test]
+fn swaps_trade_at_the_pool_price() {
+    let mut test = TestConcentratedPool::new();
+    let (below, above) = (test.tick_array_below, test.tick_array_above);
+
+    test.send(test.swap_ix(true, 1000, 0, None, &[above, below]))
+        .unwrap();
+    let ((owner_a, owner_b), (vault_a, vault_b)) = test.balances();
+    // a price of 1 and deep liquidity, little more than the fee comes off
+    let output = owner_b - (DEFAULT_SUPPLY - VAULT);
+    assert!((940..950).contains(&output));
+    assert_eq!(
+        (owner_a, vault_a),
+        (DEFAULT_SUPPLY - VAULT - 1000, VAULT + 1000)
+    );
+    assert_eq!(vault_b, VAULT - output);
+
+    // the price is now just below tick 0, in `below`
+    test.send(test.swap_ix(false, 1000, 0, None, &[below, above]))
+        .unwrap();
+    let ((_, owner_b_after), (vault_a_after, _)) = test.balances();
+    assert_eq!(owner_b_after, owner_b - 1000);
+    assert!(vault_a - vault_a_after < 1000);
+}
+
+#[test]
+fn swap_needs_the_tick_arrays_it_moves_through() {
+    let mut test = TestConcentratedPool::new();
+    let (below, above) = (test.tick_array_below, test.tick_array_above);
+
+    // none at all, or not starting with the one holding the current tick
+    assert_error(
+        test.send(test.swap_ix(true, 1000, 0, None, &[])),
+        ErrorType::InvalidTickArray,
+    );
+    assert_error(
+        test.send(test.swap_ix(true, 1000, 0, None, &[below])),
+        ErrorType::InvalidTickArray,
+    );
+    // in the wrong direction
+    assert_error(
+        test.send(test.swap_ix(false, 1000, 0, None, &[above, below])),
+        ErrorType::InvalidTickArray,
+    );
+    // the price sits on the lower edge of `above`, going down needs `below`
+    assert_error(
+        test.send(test.swap_ix(true, 1000, 0, None, &[above])),
+        ErrorType::InvalidTickArray,
+    );
+}
+
+#[test]
+fn swap_rejects_tick_arrays_of_another_pool() {
+    let mut test = TestConcentratedPool::new();
+    let other = Pubkey::new_unique();
+    test.set_tick_array(
+        other,
+        &TickArray {
+            pool: Pubkey::new_unique(),
+            start_tick_index: 0,
+            ticks: [Tick::default(); TICK_ARRAY_SIZE],
+        },
+    );
+    assert_error(
+        test.send(test.swap_ix(false, 1000, 0, None, &[other])),
+        ErrorType::InvalidTickArray,
+    );
+}
+
+#[test]
+fn swap_price_limit_is_on_the_right_side() {
+    let mut test = TestConcentratedPool::new();
+    let (below, above) = (test.tick_array_below, test.tick_array_above);
+    let sqrt_price = sqrt_price_at_tick(0).unwrap();
+
+    for limit in [sqrt_price, sqrt_price + 1, MIN_SQRT_PRICE] {
+        assert_error(
+            test.send(test.swap_ix(true, 1000, 0, Some(limit), &[above, below])),
+            ErrorType::InvalidSqrtPrice,
+        );
+    }
+    assert_error(
+        test.send(test.swap_ix(false, 1000, 0, Some(sqrt_price - 1), &[above])),
+        ErrorType::InvalidSqrtPrice,
+    );
+    // the swap stops at the limit, only what fit is taken
+    test.send(test.swap_ix(true, 1000, 0, Some(sqrt_price - 1), &[above, below]))
+        .unwrap();
+    let ((owner_a, _), _) = test.balances();
+    assert!(owner_a > DEFAULT_SUPPLY - VAULT - 1000);
+}
+
+#[test]
+fn swap_honors_minimum_output() {
+    let mut test = TestConcentratedPool::new();
+    let (below, above) = (test.tick_array_below, test.tick_array_above);
+    // 1000 in at a price of 1 minus the fee and the price impact
+    assert_error(
+        test.send(test.swap_ix(true, 1000, 1000, None, &[above, below])),
+        ErrorType::OutputTooSmall,
+    );
+}
+
+#[test]
+fn swap_rejects_paused_amm() {
+    let mut test = TestConcentratedPool::new();
+    let (below, above) = (test.tick_array_below, test.tick_array_above);
+    test.update_amm(|amm| amm.paused = true);
+    assert_error(
+        test.send(test.swap_ix(true, 1000, 0, None, &[above, below])),
+        ErrorType::AmmPaused,
+    );
+}
+
+#[test]
+fn increase_takes_both_sides_into_the_pool() {
+    let mut test = TestConcentratedPool::new();
+    let ((owner_a, owner_b), (vault_a, vault_b)) = test.balances();
+    test.send(test.increase_ix(LIQUIDITY, u64::MAX, u64::MAX))
+        .unwrap();
+
+    let position: Position = test.svm.get(&test.position);
+    assert_eq!(position.liquidity, 2 * LIQUIDITY);
+    let pool: ConcentratedPool = test.svm.get(&test.pool);
+    assert_eq!(pool.liquidity, 2 * LIQUIDITY);
+
+    let ((owner_a_after, owner_b_after), (vault_a_after, vault_b_after)) = test.balances();
+    let (amount_a, amount_b) = (owner_a - owner_a_after, owner_b - owner_b_after);
+    // the price sits in the middle of the range, it takes about the same of both
+    assert!(amount_a > 0 && amount_a.abs_diff(amount_b) <= 1);
+    assert_eq!(
+        (vault_a_after, vault_b_after),
+        (vault_a + amount_a, vault_b + amount_b)
+    );
+}
+
+#[test]
+fn increase_rejects_bad_amounts() {
+    let mut test = TestConcentratedPool::new();
+    assert_error(
+        test.send(test.increase_ix(0, u64::MAX, u64::MAX)),
+        ErrorType::DepositTooSmall,
+    );
+    assert_error(
+        test.send(test.increase_ix(u128::MAX, u64::MAX, u64::MAX)),
+        ErrorType::LiquidityMathOverflow,
+    );
+    // the price is in range, both sides are needed
+    assert_error(
+        test.send(test.increase_ix(LIQUIDITY, 0, u64::MAX)),
+        ErrorType::InputTooLarge,
+    );
+    assert_error(
+        test.send(test.increase_ix(LIQUIDITY, u64::MAX, 0)),
+        ErrorType::InputTooLarge,
+    );
+}
+
+#[test]
+fn increase_rejects_paused_amm() {
+    let mut test = TestConcentratedPool::new();
+    test.update_amm(|amm| amm.paused = true);
+    assert_error(
+        test.send(test.increase_ix(LIQUIDITY, u64::MAX, u64::MAX)),
+        ErrorType::AmmPaused,
+    );
+}
+
+#[test]
+fn decrease_is_limited_to_the_position() {
+    let mut test = TestConcentratedPool::new();
+    assert_error(
+        test.send(test.decrease_ix(0, 0, 0)),
+        ErrorType::ZeroWithdrawal,
+    );
+    assert_error(
+        test.send(test.decrease_ix(LIQUIDITY + 1, 0, 0)),
+        ErrorType::InsufficientLiquidity,
+    );
+    let ((owner_a, owner_b), (vault_a, vault_b)) = test.balances();
+    test.send(test.decrease_ix(LIQUIDITY, 0, 0)).unwrap();
+
+    let position: Position = test.svm.get(&test.position);
+    assert_eq!(position.liquidity, 0);
+    let ((owner_a_after, owner_b_after), (vault_a_after, vault_b_after)) = test.balances();
+    assert!(owner_a_after > owner_a && owner_b_after > owner_b);
+    assert_eq!(
+        (vault_a - vault_a_after, vault_b - vault_b_after),
+        (owner_a_after - owner_a, owner_b_after - owner_b)
+    );
+}
+
+#[test]
+fn decrease_honors_minimum_amounts() {
+    let mut test = TestConcentratedPool::new();
+    assert_error(
+        test.send(test.decrease_ix(LIQUIDITY, u64::MAX, 0)),
+        ErrorType::OutputTooSmall,
+    );
+    assert_error(
+        test.send(test.decrease_ix(LIQUIDITY, 0, u64::MAX)),
+        ErrorType::OutputTooSmall,
+    );
+}
+
+#[test]
+fn decrease_updates_the_position_and_its_ticks() {
+    let mut test = TestConcentratedPool::new();
+    // one unit of liquidity is worth less than a token on either side, so
+    // nothing is transferred and the bookkeeping can be checked
+    test.send(test.decrease_ix(1, 0, 0)).unwrap();
+
+    let position: Position = test.svm.get(&test.position);
+    assert_eq!(position.liquidity, LIQUIDITY - 1);
+    let pool: ConcentratedPool = test.svm.get(&test.pool);
+    assert_eq!(pool.liquidity, LIQUIDITY - 1);
+
+    let below: TickArray = test.svm.get(&test.tick_array_below);
+    let lower = below.ticks[below.tick_offset(TICK_LOWER, TICK_SPACING).unwrap()];
+    assert_eq!(lower.liquidity_gross, LIQUIDITY - 1);
+    assert_eq!(lower.liquidity_net, LIQUIDITY as i128 - 1);
+    let above: TickArray = test.svm.get(&test.tick_array_above);
+    let upper = above.ticks[above.tick_offset(TICK_UPPER, TICK_SPACING).unwrap()];
+    assert_eq!(upper.liquidity_gross, LIQUIDITY - 1);
+    assert_eq!(upper.liquidity_net, -(LIQUIDITY as i128) + 1);
+}
+
+#[test]
+fn modify_liquidity_needs_the_position_ticks() {
+    let mut test = TestConcentratedPool::new();
+    let mut decrease = test.decrease_ix(LIQUIDITY, 0, 0);
+    // the upper tick isn't in the lower array
+    decrease.accounts[5].pubkey = test.tick_array_below;
+    assert_error(test.send(decrease), ErrorType::InvalidTickArray);
+}
+
+#[test]
+fn only_the_owner_modifies_a_position() {
+    let mut test = TestConcentratedPool::new();
+    let mut position: Position = test.svm.get(&test.position);
+    position.owner = Pubkey::new_unique();
+    test.set_position(&position);
+
+    assert_anchor_error(
+        test.send(test.decrease_ix(LIQUIDITY, 0, 0)),
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+}
+
+#[test]
+fn fees_are_collected_from_their_growth() {
+    let mut test = TestConcentratedPool::new();
+    // nothing earned yet, nothing transferred
+    test.send(test.collect_fees_ix()).unwrap();
+    let position: Position = test.svm.get(&test.position);
+    assert_eq!((position.fees_owed_a, position.fees_owed_b), (0, 0));
+
+    // a 1024th of a token of a per unit of liquidity
+    test.update_pool(|pool| pool.fee_growth_global_a = 1 << 54);
+    let ((owner_a, _), _) = test.balances();
+    test.send(test.collect_fees_ix()).unwrap();
+    let ((owner_a_after, _), _) = test.balances();
+    assert_eq!(owner_a_after - owner_a, LIQUIDITY as u64 / 1024);
+
+    // and only once
+    test.send(test.collect_fees_ix()).unwrap();
+    let ((owner_a, _), _) = test.balances();
+    assert_eq!(owner_a, owner_a_after);
+}
+
+#[test]
+fn paused_withdrawals_stop_decreases_and_collection() {
+    let mut test = TestConcentratedPool::new();
+    test.update_amm(|amm| amm.withdrawals_paused = true);
+    assert_error(
+        test.send(test.decrease_ix(LIQUIDITY, 0, 0)),
+        ErrorType::AmmPaused,
+    );
+    assert_error(test.send(test.collect_fees_ix()), ErrorType::AmmPaused);
+}
+
+// a new pool for two fresh mints
+fn create_pool_ix(test: &mut TestConcentratedPool, tick_spacing: u16) -> Instruction {
+    let (mint_a, mint_b) = ordered_keys();
+    let (token_program_a, token_program_b) = (test.token_program_a, test.token_program_b);
+    for (mint, token_program) in [(mint_a, token_program_a), (mint_b, token_program_b)] {
+        test.svm.set_account(
+            mint,
+            mint_account(&test.admin, DEFAULT_SUPPLY, &token_program),
+        );
+    }
+    let pool = Pubkey::find_program_address(
+        &[
+            test.amm.as_ref(),
+            mint_a.as_ref(),
+            mint_b.as_ref(),
+            CONCENTRATED_SEED,
+        ],
+        &amm::ID,
+    )
+    .0;
+    let pool_authority = Pubkey::find_program_address(&[pool.as_ref(), AUTHORITY_SEED], &amm::ID).0;
+    ix(
+        amm::accounts::CreateConcentratedPool {
+            amm: test.amm,
+            pool,
+            pool_authority,
+            mint_a,
+            mint_b,
+            pool_account_a: get_associated_token_address_with_program_id(
+                &pool_authority,
+                &mint_a,
+                &token_program_a,
+            ),
+            pool_account_b: get_associated_token_address_with_program_id(
+                &pool_authority,
+                &mint_b,
+                &token_program_b,
+            ),
+            payer: test.owner,
+            token_program_a,
+            token_program_b,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        amm::instruction::CreateConcentratedPool {
+            tick_spacing,
+            sqrt_price: sqrt_price_at_tick(0).unwrap(),
+        },
+    )
+}
+
+#[test]
+fn pools_are_created_with_a_valid_tick_spacing() {
+    let mut test = TestConcentratedPool::new();
+    for tick_spacing in [0, MAX_TICK_SPACING + 1] {
+        let create = create_pool_ix(&mut test, tick_spacing);
+        assert_error(test.send(create), ErrorType::InvalidTickSpacing);
+    }
+
+    let create = create_pool_ix(&mut test, TICK_SPACING);
+    let pool_key = create.accounts[1].pubkey;
+    test.send(create).unwrap();
+    let pool: ConcentratedPool = test.svm.get(&pool_key);
+    assert_eq!((pool.tick_spacing, pool.tick_current), (TICK_SPACING, 0));
+}
+
+#[test]
+fn pools_can_mix_token_programs() {
+    let mut test =
+        TestConcentratedPool::with_token_programs(TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID);
+    let create = create_pool_ix(&mut test, TICK_SPACING);
+    test.send(create).unwrap();
+
+    let ((owner_a, owner_b), _) = test.balances();
+    test.send(test.swap_ix(
+        true,
+        1000,
+        0,
+        None,
+        &[test.tick_array_above, test.tick_array_below],
+    ))
+    .unwrap();
+    test.send(test.swap_ix(
+        false,
+        1000,
+        0,
+        None,
+        &[test.tick_array_below, test.tick_array_above],
+    ))
+    .unwrap();
+    test.send(test.increase_ix(LIQUIDITY, u64::MAX, u64::MAX))
+        .unwrap();
+    test.send(test.decrease_ix(LIQUIDITY, 0, 0)).unwrap();
+    // every transfer went through, nothing was created or lost on the way
+    let ((owner_a_after, owner_b_after), (vault_a, vault_b)) = test.balances();
+    assert_eq!(
+        (owner_a_after + vault_a, owner_b_after + vault_b),
+        (owner_a + VAULT, owner_b + VAULT)
+    );
+
+    // each vault has to belong to its own mint's token program
+    let mut swap = test.swap_ix(true, 1000, 0, None, &[test.tick_array_above]);
+    swap.accounts
+        .iter_mut()
+        .filter(|meta| meta.pubkey == TOKEN_2022_PROGRAM_ID)
+        .for_each(|meta| meta.pubkey = TOKEN_PROGRAM_ID);
+    assert_anchor_error(
+        test.send(swap),
+        anchor_lang::error::ErrorCode::ConstraintAssociatedTokenTokenProgram,
+    );
+}
+
+fn open_position_ix(test: &TestConcentratedPool, tick_lower: i32, tick_upper: i32) -> Instruction {
+    let position = Pubkey::find_program_address(
+        &[
+            POSITION_SEED,
+            test.pool.as_ref(),
+            test.owner.as_ref(),
+            tick_lower.to_le_bytes().as_ref(),
+            tick_upper.to_le_bytes().as_ref(),
+        ],
+        &amm::ID,
+    )
+    .0;
+    ix(
+        amm::accounts::OpenPosition {
+            pool: test.pool,
+            position,
+            owner: test.owner,
+            payer: test.owner,
+            system_program: system_program::ID,
+        },
+        amm::instruction::OpenPosition {
+            tick_lower,
+            tick_upper,
+        },
+    )
+}
+
+#[test]
+fn positions_open_on_a_valid_tick_range() {
+    let mut test = TestConcentratedPool::new();
+    let spacing = TICK_SPACING as i32;
+    // empty, upside down, off the tick spacing, past the lowest tick
+    for (tick_lower, tick_upper) in [
+        (0, 0),
+        (spacing, -spacing),
+        (spacing / 2, spacing),
+        (MIN_TICK - spacing - MIN_TICK % spacing, 0),
+    ] {
+        assert_error(
+            test.send(open_position_ix(&test, tick_lower, tick_upper)),
+            ErrorType::InvalidTickRange,
+        );
+    }
+
+    let open = open_position_ix(&test, -spacing, spacing);
+    let position_key = open.accounts[1].pubkey;
+    test.send(open).unwrap();
+    let position: Position = test.svm.get(&position_key);
+    assert_eq!(
+        (position.tick_lower, position.tick_upper),
+        (-spacing, spacing)
+    );
+    assert_eq!((position.owner, position.liquidity), (test.owner, 0));
+}
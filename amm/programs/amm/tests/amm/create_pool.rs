@@ -0,0 +1,148 @@
+use amm::{
+    constants::MINIMUM_LIQUIDITY,
+    errors::ErrorType,
+    state::{CurveType, Pool},
+};
+use amm_client::{instructions, pda::amm_address, PoolKeys};
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, program_pack::Pack},
+};
+use anchor_spl::token::{spl_token, ID as TOKEN_PROGRAM_ID};
+
+use crate::{
+    fixtures::{
+        assert_error, mint_account, ordered_keys, token_account, TestPool, DECIMALS,
+        DEFAULT_SUPPLY, DEPOSIT_A, DEPOSIT_B, FEE, LIQUIDITY, PROTOCOL_FEE,
+    },
+    svm::{Account, Svm},
+};
+
+fn mint_with_decimals(authority: &Pubkey, decimals: u8) -> Account {
+    let mut account = mint_account(authority, DEFAULT_SUPPLY, &TOKEN_PROGRAM_ID);
+    let mut mint = spl_token::state::Mint::unpack(&account.data).unwrap();
+    mint.decimals = decimals;
+    mint.pack_into_slice(&mut account.data);
+    account
+}
+
+// a pool of two fresh mints in the test amm, nothing seeded but the mints
+fn create_pool_ix(
+    test: &mut TestPool,
+    decimals_b: u8,
+    curve_type: CurveType,
+    amp: u64,
+) -> (PoolKeys, Instruction) {
+    let admin = test.admin;
+    let (mint_a, mint_b) = ordered_keys();
+    test.svm
+        .set_account(mint_a, mint_with_decimals(&admin, DECIMALS));
+    test.svm
+        .set_account(mint_b, mint_with_decimals(&admin, decimals_b));
+    let keys = PoolKeys::new(test.keys.amm, mint_a, mint_b, TOKEN_PROGRAM_ID);
+    let create = instructions::create_pool(&keys, &admin, curve_type, amp);
+    (keys, create)
+}
+
+#[test]
+fn pools_are_created_deposited_into_and_traded() {
+    let mut svm = Svm::new();
+    let (admin, holder) = (Pubkey::new_unique(), Pubkey::new_unique());
+    svm.airdrop(&admin, 10_000_000_000);
+    svm.airdrop(&holder, 10_000_000_000);
+
+    let id = Pubkey::new_unique();
+    let (mint_a, mint_b) = ordered_keys();
+    let keys = PoolKeys::new(amm_address(&id), mint_a, mint_b, TOKEN_PROGRAM_ID);
+    for mint in [mint_a, mint_b] {
+        svm.set_account(
+            mint,
+            mint_account(&admin, DEFAULT_SUPPLY, &TOKEN_PROGRAM_ID),
+        );
+        svm.set_account(
+            keys.user_account(&holder, &mint),
+            token_account(&mint, &holder, DEFAULT_SUPPLY, &TOKEN_PROGRAM_ID),
+        );
+    }
+
+    svm.send(&[
+        instructions::create_amm(&admin, &admin, id, FEE, PROTOCOL_FEE),
+        instructions::create_pool(&keys, &admin, CurveType::ConstantProduct, 0),
+    ])
+    .unwrap();
+    let pool = svm.get::<Pool>(&keys.pool);
+    assert_eq!((pool.mint_a, pool.mint_b), (mint_a, mint_b));
+    assert_eq!(svm.balance(&keys.pool_account_a), 0);
+    assert_eq!(svm.balance(&keys.locked_liquidity), 0);
+
+    let deposit = instructions::deposit_liquidity(
+        &keys, &holder, &holder, DEPOSIT_A, DEPOSIT_B, LIQUIDITY, None,
+    );
+    svm.send(&[deposit]).unwrap();
+    let lp_account = keys.user_account(&holder, &keys.mint_liquidity);
+    assert_eq!(svm.balance(&lp_account), LIQUIDITY);
+    assert_eq!(svm.balance(&keys.locked_liquidity), MINIMUM_LIQUIDITY);
+    assert_eq!(svm.balance(&keys.pool_account_a), DEPOSIT_A);
+    assert_eq!(svm.balance(&keys.pool_account_b), DEPOSIT_B);
+
+    let swap = instructions::swap_tokens(&keys, &holder, &holder, true, 10_000, 2_369, None);
+    svm.send(&[swap]).unwrap();
+    assert_eq!(svm.balance(&keys.pool_account_a), DEPOSIT_A + 10_000);
+    assert_eq!(svm.balance(&keys.pool_account_b), DEPOSIT_B - 2_369);
+    assert_eq!(
+        svm.balance(&keys.user_account(&holder, &mint_b)),
+        DEFAULT_SUPPLY - DEPOSIT_B + 2_369
+    );
+}
+
+#[test]
+fn pools_need_two_ordered_mints() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    let (keys, _) = create_pool_ix(&mut test, DECIMALS, CurveType::ConstantProduct, 0);
+
+    let swapped = PoolKeys::new(keys.amm, keys.mint_b, keys.mint_a, TOKEN_PROGRAM_ID);
+    let create = instructions::create_pool(&swapped, &admin, CurveType::ConstantProduct, 0);
+    assert_error(test.send(create), ErrorType::UnorderedMints);
+
+    let same = PoolKeys::new(keys.amm, keys.mint_a, keys.mint_a, TOKEN_PROGRAM_ID);
+    let create = instructions::create_pool(&same, &admin, CurveType::ConstantProduct, 0);
+    assert_error(test.send(create), ErrorType::IdenticalMints);
+
+    let create = instructions::create_pool(&keys, &admin, CurveType::ConstantProduct, 0);
+    test.send(create).unwrap();
+}
+
+#[test]
+fn stable_pools_need_mints_of_the_same_decimals() {
+    let mut test = TestPool::new();
+    let (_, create) = create_pool_ix(&mut test, DECIMALS + 3, CurveType::StableSwap, 100);
+    assert_error(test.send(create), ErrorType::MismatchedDecimals);
+
+    // constant product pools price in raw units either way
+    let (_, create) = create_pool_ix(&mut test, DECIMALS + 3, CurveType::ConstantProduct, 0);
+    test.send(create).unwrap();
+    let (_, create) = create_pool_ix(&mut test, DECIMALS, CurveType::StableSwap, 100);
+    test.send(create).unwrap();
+}
+
+#[test]
+fn pools_are_found_in_either_mint_order() {
+    let mut test = TestPool::new();
+    let (amm, mint_a, mint_b) = (test.keys.amm, test.keys.mint_a, test.keys.mint_b);
+    let keys = PoolKeys::for_pair(amm, mint_b, mint_a, test.keys.token_program);
+    assert_eq!(keys, test.keys);
+    assert_eq!(test.svm.get::<Pool>(&keys.pool).mint_a, mint_a);
+
+    // selling b is swap_a = false whatever order the pair came in
+    assert_eq!(keys.swap_a(&mint_a), Some(true));
+    assert_eq!(keys.swap_a(&mint_b), Some(false));
+    assert_eq!(keys.swap_a(&keys.mint_liquidity), None);
+
+    let swap_a = keys.swap_a(&mint_b).unwrap();
+    let swap = instructions::swap_tokens(&keys, &test.holder, &test.holder, swap_a, 1000, 0, None);
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(swap).unwrap();
+    assert_eq!(test.holder_balances().1, holder_b - 1000);
+    assert!(test.holder_balances().0 > holder_a);
+}
@@ -0,0 +1,180 @@
+use amm::{constants::MINIMUM_LIQUIDITY, errors::ErrorType};
+use amm_client::quote::quote_deposit;
+use anchor_lang::{error::ErrorCode, prelude::Pubkey};
+use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
+
+use crate::fixtures::{
+    assert_anchor_error, assert_error, mint_account, token_account, with_data, TestPool,
+    DEFAULT_SUPPLY, DEPOSIT_A, DEPOSIT_B, LIQUIDITY,
+};
+
+#[test]
+fn deposit_mints_the_quoted_liquidity() {
+    let mut test = TestPool::new();
+    let supply = LIQUIDITY + MINIMUM_LIQUIDITY;
+    // a deposit the size of the pool doubles the supply, b is cut down to the pool ratio
+    let quote = quote_deposit(
+        &test.pool(),
+        DEPOSIT_A,
+        DEPOSIT_B,
+        supply,
+        DEPOSIT_A,
+        2 * DEPOSIT_B,
+        test.svm.now(),
+    )
+    .unwrap();
+    assert_eq!(
+        (quote.amount_a, quote.amount_b, quote.liquidity),
+        (DEPOSIT_A, DEPOSIT_B, supply)
+    );
+
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(test.deposit_ix(DEPOSIT_A, 2 * DEPOSIT_B))
+        .unwrap();
+
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a - DEPOSIT_A, holder_b - DEPOSIT_B)
+    );
+    assert_eq!(test.vault_balances(), (2 * DEPOSIT_A, 2 * DEPOSIT_B));
+    assert_eq!(test.lp_balance(), LIQUIDITY + supply);
+    let pool = test.pool();
+    assert_eq!(
+        (pool.reserve_a, pool.reserve_b),
+        (2 * DEPOSIT_A, 2 * DEPOSIT_B)
+    );
+}
+
+#[test]
+fn deposit_honors_minimum_liquidity() {
+    let mut test = TestPool::new();
+    let supply = LIQUIDITY + MINIMUM_LIQUIDITY;
+    let deposit = |min_liquidity_out| {
+        with_data(
+            test.deposit_ix(DEPOSIT_A, DEPOSIT_B),
+            amm::instruction::DepositLiquidity {
+                amount_a: DEPOSIT_A,
+                amount_b: DEPOSIT_B,
+                min_liquidity_out,
+            },
+        )
+    };
+    let (too_much, quoted) = (deposit(supply + 1), deposit(supply));
+
+    assert_error(test.send(too_much), ErrorType::OutputTooSmall);
+    test.send(quoted).unwrap();
+}
+
+#[test]
+fn deposit_rejects_zero_amounts() {
+    let mut test = TestPool::new();
+    assert_error(
+        test.send(test.deposit_ix(0, DEPOSIT_B)),
+        ErrorType::DepositTooSmall,
+    );
+    assert_error(
+        test.send(test.deposit_ix(DEPOSIT_A, 0)),
+        ErrorType::DepositTooSmall,
+    );
+}
+
+#[test]
+fn deposit_rejects_expired_deadline() {
+    let mut test = TestPool::new();
+    let deposit = with_data(
+        test.deposit_ix(DEPOSIT_A, DEPOSIT_B),
+        amm::instruction::DepositLiquidityWithDeadline {
+            amount_a: DEPOSIT_A,
+            amount_b: DEPOSIT_B,
+            min_liquidity_out: 0,
+            deadline: test.svm.now() - 1,
+        },
+    );
+    assert_error(test.send(deposit), ErrorType::DeadlineExceeded);
+}
+
+#[test]
+fn deposit_rejects_locked_pool() {
+    let mut test = TestPool::new();
+    test.update_pool(|pool| pool.locked = true);
+    assert_error(
+        test.send(test.deposit_ix(DEPOSIT_A, DEPOSIT_B)),
+        ErrorType::PoolLocked,
+    );
+}
+
+#[test]
+fn deposit_rejects_mints_of_another_pool() {
+    let mut test = TestPool::new();
+    let other_mint = Pubkey::new_unique();
+    let admin = test.admin;
+    test.svm.set_account(
+        other_mint,
+        mint_account(&admin, DEFAULT_SUPPLY, &test.keys.token_program),
+    );
+
+    let mut deposit = test.deposit_ix(DEPOSIT_A, DEPOSIT_B);
+    let mint_a = test.keys.mint_a;
+    let meta = deposit
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == mint_a)
+        .unwrap();
+    meta.pubkey = other_mint;
+    // the pool address is derived from its mints
+    assert_anchor_error(test.send(deposit), ErrorCode::ConstraintSeeds);
+}
+
+#[test]
+fn deposit_needs_the_locked_liquidity_account() {
+    let mut test = TestPool::new();
+    test.remove_locked_liquidity();
+    assert_anchor_error(
+        test.send(test.deposit_ix(DEPOSIT_A, DEPOSIT_B)),
+        ErrorCode::AccountNotInitialized,
+    );
+}
+
+#[test]
+fn deposit_rejects_a_locked_account_the_pool_does_not_own() {
+    let mut test = TestPool::new();
+    let (locked, mint_liquidity, holder) = (
+        test.keys.locked_liquidity,
+        test.keys.mint_liquidity,
+        test.holder,
+    );
+    test.svm.set_account(
+        locked,
+        token_account(
+            &mint_liquidity,
+            &holder,
+            MINIMUM_LIQUIDITY,
+            &TOKEN_PROGRAM_ID,
+        ),
+    );
+    assert_anchor_error(
+        test.send(test.deposit_ix(DEPOSIT_A, DEPOSIT_B)),
+        ErrorCode::ConstraintTokenOwner,
+    );
+}
+
+#[test]
+fn deposit_handles_large_reserves() {
+    let mut test = TestPool::new();
+    // billions of tokens with 9 decimals on both sides
+    test.set_vaults(4_000_000_000_000_000_000, 1_000_000_000_000_000_000);
+    test.set_liquidity(2_000_000_000_000_000_000, 2_000_000_000_000_000_000);
+    test.send(test.deposit_ix(DEPOSIT_A, DEPOSIT_B)).unwrap();
+    assert_eq!(test.lp_balance(), 2_000_000_000_002_000_000);
+}
+
+#[test]
+fn deposit_fails_when_the_math_overflows() {
+    let mut test = TestPool::new();
+    // matching the pool ratio needs more than a u64 of token b
+    test.set_vaults(1, u64::MAX);
+    assert_error(
+        test.send(test.deposit_ix(DEPOSIT_A, DEPOSIT_B)),
+        ErrorType::MathOverflow,
+    );
+}
@@ -0,0 +1,435 @@
+use amm::{
+    constants::MINIMUM_LIQUIDITY,
+    errors::ErrorType,
+    state::{Amm, CurveType, Observation, Pool},
+};
+use amm_client::{
+    instructions,
+    pda::{amm_address, sort_mints},
+    PoolKeys,
+};
+use anchor_lang::{
+    prelude::{AccountMeta, ProgramError, Pubkey},
+    solana_program::{instruction::Instruction, program_option::COption, program_pack::Pack},
+    AccountSerialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    token::{spl_token, ID as TOKEN_PROGRAM_ID},
+    token_2022::{
+        spl_token_2022::{
+            self,
+            extension::{
+                transfer_fee::TransferFeeAmount, BaseStateWithExtensionsMut, ExtensionType,
+                StateWithExtensionsMut,
+            },
+        },
+        ID as TOKEN_2022_PROGRAM_ID,
+    },
+};
+
+use crate::svm::{Account, Svm, TxError, TxResult};
+
+// same defaults as the typescript tests
+pub const FEE: u16 = 500;
+pub const PROTOCOL_FEE: u16 = 1667;
+pub const DECIMALS: u8 = 6;
+pub const DEFAULT_SUPPLY: u64 = 100_000_000;
+pub const DEPOSIT_A: u64 = 4_000_000;
+pub const DEPOSIT_B: u64 = 1_000_000;
+// sqrt(DEPOSIT_A * DEPOSIT_B) minus the locked minimum liquidity
+pub const LIQUIDITY: u64 = 2_000_000 - MINIMUM_LIQUIDITY;
+
+pub fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// same accounts, other instruction, e.g. the deadline variant of a builder
+pub fn with_data(mut instruction: Instruction, data: impl InstructionData) -> Instruction {
+    instruction.data = data.data();
+    instruction
+}
+
+// extra accounts for remaining_accounts
+pub fn with_remaining(mut instruction: Instruction, accounts: &[AccountMeta]) -> Instruction {
+    instruction.accounts.extend_from_slice(accounts);
+    instruction
+}
+
+#[track_caller]
+pub fn assert_error(result: TxResult, error: ErrorType) {
+    assert_eq!(
+        result,
+        Err(TxError::Program(ProgramError::Custom(error.into()))),
+        "expected {error:?}"
+    );
+}
+
+// anchor's own constraint errors, like a failed has_one
+#[track_caller]
+pub fn assert_anchor_error(result: TxResult, error: anchor_lang::error::ErrorCode) {
+    assert_eq!(
+        result,
+        Err(TxError::Program(ProgramError::Custom(error.into()))),
+        "expected {error:?}"
+    );
+}
+
+pub fn program_account<T: AccountSerialize>(value: &T, len: usize) -> Account {
+    let mut data = Vec::with_capacity(len);
+    value.try_serialize(&mut data).unwrap();
+    data.resize(len, 0);
+    Account::new(data, amm::ID)
+}
+
+pub fn mint_account(authority: &Pubkey, supply: u64, token_program: &Pubkey) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::Some(*authority),
+        supply,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Account::new(data, *token_program)
+}
+
+// token-2022 accounts carry the transfer fee amount extension, transfers of
+// a fee mint withhold into it
+pub fn token_account(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+    token_program: &Pubkey,
+) -> Account {
+    let base = spl_token_2022::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token_2022::state::AccountState::Initialized,
+        ..Default::default()
+    };
+    if *token_program != TOKEN_2022_PROGRAM_ID {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        base.pack_into_slice(&mut data);
+        return Account::new(data, *token_program);
+    }
+
+    let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&[
+        ExtensionType::TransferFeeAmount,
+    ])
+    .unwrap();
+    let mut data = vec![0; len];
+    let mut account =
+        StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(&mut data)
+            .unwrap();
+    account.init_extension::<TransferFeeAmount>(true).unwrap();
+    account.base = base;
+    account.pack_base();
+    account.init_account_type().unwrap();
+    Account::new(data, *token_program)
+}
+
+// two fresh keys, sorted the way pools expect their mints
+pub fn ordered_keys() -> (Pubkey, Pubkey) {
+    sort_mints(Pubkey::new_unique(), Pubkey::new_unique())
+}
+
+// an amm with the default fees and a constant product pool holding the
+// default deposit, seeded the way the program leaves them after
+// create_amm, create_pool and deposit_liquidity
+pub struct TestPool {
+    pub svm: Svm,
+    pub admin: Pubkey,
+    pub keys: PoolKeys,
+    pub holder: Pubkey,
+}
+
+impl TestPool {
+    pub fn new() -> Self {
+        Self::with_curve(CurveType::ConstantProduct, 0)
+    }
+
+    pub fn with_curve(curve_type: CurveType, amp: u64) -> Self {
+        Self::setup(curve_type, amp, |authority| {
+            mint_account(authority, DEFAULT_SUPPLY, &TOKEN_PROGRAM_ID)
+        })
+    }
+
+    // pool whose mints are made by `new_mint`, the token program is their owner
+    pub fn setup(curve_type: CurveType, amp: u64, new_mint: impl Fn(&Pubkey) -> Account) -> Self {
+        Self::with_mints(curve_type, amp, &new_mint, &new_mint)
+    }
+
+    // pool whose mints can belong to different token programs, the lp mint
+    // goes with mint a
+    pub fn with_mints(
+        curve_type: CurveType,
+        amp: u64,
+        new_mint_a: impl Fn(&Pubkey) -> Account,
+        new_mint_b: impl Fn(&Pubkey) -> Account,
+    ) -> Self {
+        let mut svm = Svm::new();
+        let admin = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        svm.airdrop(&admin, 10_000_000_000);
+        svm.airdrop(&holder, 10_000_000_000);
+
+        let id = Pubkey::new_unique();
+        let (mint_a, mint_b) = ordered_keys();
+        let (mint_a_account, mint_b_account) = (new_mint_a(&admin), new_mint_b(&admin));
+        let token_program = mint_a_account.owner;
+        let keys = PoolKeys::with_token_programs(
+            amm_address(&id),
+            mint_a,
+            mint_b,
+            token_program,
+            mint_b_account.owner,
+            token_program,
+        );
+
+        let amm = Amm {
+            id,
+            admin,
+            fee: FEE,
+            protocol_fee: PROTOCOL_FEE,
+            ..Amm::default()
+        };
+        svm.set_account(keys.amm, program_account(&amm, Amm::LEN));
+
+        let now = svm.now();
+        let mut pool = Pool {
+            amm: keys.amm,
+            mint_a,
+            mint_b,
+            last_update_ts: now,
+            curve_type,
+            amp_initial: amp,
+            amp_target: amp,
+            reserve_a: DEPOSIT_A,
+            reserve_b: DEPOSIT_B,
+            ..Pool::default()
+        };
+        pool.observations[0] = Observation {
+            timestamp: now,
+            ..Observation::default()
+        };
+        svm.set_account(keys.pool, program_account(&pool, Pool::LEN));
+
+        for (mint, mint_account, deposit) in [
+            (mint_a, mint_a_account, DEPOSIT_A),
+            (mint_b, mint_b_account, DEPOSIT_B),
+        ] {
+            let token_program = mint_account.owner;
+            svm.set_account(mint, mint_account);
+            svm.set_account(
+                keys.user_account(&keys.pool_authority, &mint),
+                token_account(&mint, &keys.pool_authority, deposit, &token_program),
+            );
+            svm.set_account(
+                keys.user_account(&holder, &mint),
+                token_account(&mint, &holder, DEFAULT_SUPPLY - deposit, &token_program),
+            );
+        }
+        svm.set_account(
+            keys.mint_liquidity,
+            mint_account(
+                &keys.pool_authority,
+                LIQUIDITY + MINIMUM_LIQUIDITY,
+                &token_program,
+            ),
+        );
+        svm.set_account(
+            keys.locked_liquidity,
+            token_account(
+                &keys.mint_liquidity,
+                &keys.pool,
+                MINIMUM_LIQUIDITY,
+                &token_program,
+            ),
+        );
+        svm.set_account(
+            keys.user_account(&holder, &keys.mint_liquidity),
+            token_account(&keys.mint_liquidity, &holder, LIQUIDITY, &token_program),
+        );
+
+        Self {
+            svm,
+            admin,
+            keys,
+            holder,
+        }
+    }
+
+    pub fn amm(&self) -> Amm {
+        self.svm.get(&self.keys.amm)
+    }
+
+    pub fn pool(&self) -> Pool {
+        self.svm.get(&self.keys.pool)
+    }
+
+    pub fn update_amm(&mut self, update: impl FnOnce(&mut Amm)) {
+        let mut amm = self.amm();
+        update(&mut amm);
+        self.svm
+            .set_account(self.keys.amm, program_account(&amm, Amm::LEN));
+    }
+
+    pub fn update_pool(&mut self, update: impl FnOnce(&mut Pool)) {
+        let mut pool = self.pool();
+        update(&mut pool);
+        self.svm
+            .set_account(self.keys.pool, program_account(&pool, Pool::LEN));
+    }
+
+    // replaces the vault balances, the reserves are these minus the protocol fees
+    pub fn set_vaults(&mut self, amount_a: u64, amount_b: u64) {
+        for (vault, mint, amount) in [
+            (self.keys.pool_account_a, self.keys.mint_a, amount_a),
+            (self.keys.pool_account_b, self.keys.mint_b, amount_b),
+        ] {
+            let token_program = self.keys.mint_token_program(&mint);
+            self.svm.set_account(
+                vault,
+                token_account(&mint, &self.keys.pool_authority, amount, &token_program),
+            );
+        }
+    }
+
+    // replaces the lp supply, the locked minimum liquidity included, and
+    // `holder_balance` of it is held by the holder
+    pub fn set_liquidity(&mut self, supply: u64, holder_balance: u64) {
+        let token_program = self.keys.token_program;
+        self.svm.set_account(
+            self.keys.mint_liquidity,
+            mint_account(&self.keys.pool_authority, supply, &token_program),
+        );
+        self.svm.set_account(
+            self.keys
+                .user_account(&self.holder, &self.keys.mint_liquidity),
+            token_account(
+                &self.keys.mint_liquidity,
+                &self.holder,
+                holder_balance,
+                &token_program,
+            ),
+        );
+    }
+
+    // turns the pool into one created before the minimum liquidity was locked
+    pub fn remove_locked_liquidity(&mut self) {
+        self.svm
+            .set_account(self.keys.locked_liquidity, Account::default());
+    }
+
+    // empties the pool, the way create_pool leaves it
+    pub fn empty_pool(&mut self) {
+        self.set_vaults(0, 0);
+        self.set_liquidity(0, 0);
+        let token_program = self.keys.token_program;
+        self.svm.set_account(
+            self.keys.locked_liquidity,
+            token_account(
+                &self.keys.mint_liquidity,
+                &self.keys.pool,
+                0,
+                &token_program,
+            ),
+        );
+        self.update_pool(|pool| {
+            pool.reserve_a = 0;
+            pool.reserve_b = 0;
+        });
+    }
+
+    // replaces what the holder has of a and b
+    pub fn set_holder_balances(&mut self, amount_a: u64, amount_b: u64) {
+        for (mint, amount) in [(self.keys.mint_a, amount_a), (self.keys.mint_b, amount_b)] {
+            let token_program = self.keys.mint_token_program(&mint);
+            self.svm.set_account(
+                self.keys.user_account(&self.holder, &mint),
+                token_account(&mint, &self.holder, amount, &token_program),
+            );
+        }
+    }
+
+    pub fn vault_balances(&self) -> (u64, u64) {
+        (
+            self.svm.balance(&self.keys.pool_account_a),
+            self.svm.balance(&self.keys.pool_account_b),
+        )
+    }
+
+    pub fn holder_balances(&self) -> (u64, u64) {
+        (
+            self.svm
+                .balance(&self.keys.user_account(&self.holder, &self.keys.mint_a)),
+            self.svm
+                .balance(&self.keys.user_account(&self.holder, &self.keys.mint_b)),
+        )
+    }
+
+    pub fn lp_balance(&self) -> u64 {
+        self.svm.balance(
+            &self
+                .keys
+                .user_account(&self.holder, &self.keys.mint_liquidity),
+        )
+    }
+
+    pub fn send(&mut self, instruction: Instruction) -> TxResult {
+        self.svm.send(&[instruction])
+    }
+
+    // the holder trades, deposits and withdraws
+
+    pub fn swap_ix(&self, swap_a: bool, input: u64, min_output: u64) -> Instruction {
+        instructions::swap_tokens(
+            &self.keys,
+            &self.holder,
+            &self.holder,
+            swap_a,
+            input,
+            min_output,
+            None,
+        )
+    }
+
+    pub fn deposit_ix(&self, amount_a: u64, amount_b: u64) -> Instruction {
+        instructions::deposit_liquidity(
+            &self.keys,
+            &self.holder,
+            &self.holder,
+            amount_a,
+            amount_b,
+            0,
+            None,
+        )
+    }
+
+    pub fn withdraw_ix(&self, amount: u64) -> Instruction {
+        self.withdraw_min_ix(amount, 0, 0)
+    }
+
+    pub fn withdraw_min_ix(
+        &self,
+        amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Instruction {
+        instructions::withdraw_liquidity(
+            &self.keys,
+            &self.holder,
+            &self.holder,
+            amount,
+            min_amount_a,
+            min_amount_b,
+            None,
+        )
+    }
+}
@@ -0,0 +1,144 @@
+use amm::errors::ErrorType;
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, system_program},
+};
+use anchor_spl::token::spl_token;
+
+use crate::fixtures::{
+    assert_error, ix, with_remaining, TestPool, DECIMALS, DEPOSIT_A, DEPOSIT_B, PROTOCOL_FEE,
+};
+
+fn flash_loan(
+    test: &TestPool,
+    receiver_program: Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+) -> Instruction {
+    flash_loan_with_data(test, receiver_program, amount_a, amount_b, Vec::new())
+}
+
+fn flash_loan_with_data(
+    test: &TestPool,
+    receiver_program: Pubkey,
+    amount_a: u64,
+    amount_b: u64,
+    data: Vec<u8>,
+) -> Instruction {
+    let keys = &test.keys;
+    ix(
+        amm::accounts::FlashLoan {
+            amm: keys.amm,
+            pool: keys.pool,
+            pool_authority: keys.pool_authority,
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            pool_account_a: keys.pool_account_a,
+            pool_account_b: keys.pool_account_b,
+            borrower_account_a: keys.user_account(&test.holder, &keys.mint_a),
+            borrower_account_b: keys.user_account(&test.holder, &keys.mint_b),
+            borrower: test.holder,
+            receiver_program,
+            token_program_a: keys.token_program_a,
+            token_program_b: keys.token_program_b,
+        },
+        amm::instruction::FlashLoan {
+            amount_a,
+            amount_b,
+            data,
+        },
+    )
+}
+
+// a loan of a whose receiver is the token program itself, sending `repaid`
+// back to the vault on the borrower's signature
+fn loan_repaid_with(test: &TestPool, amount: u64, repaid: u64) -> Instruction {
+    let keys = &test.keys;
+    let repay = spl_token::instruction::transfer_checked(
+        &keys.token_program_a,
+        &keys.user_account(&test.holder, &keys.mint_a),
+        &keys.mint_a,
+        &keys.pool_account_a,
+        &test.holder,
+        &[],
+        repaid,
+        DECIMALS,
+    )
+    .unwrap();
+    let loan = flash_loan_with_data(test, keys.token_program_a, amount, 0, repay.data);
+    with_remaining(loan, &repay.accounts)
+}
+
+#[test]
+fn loan_is_repaid_with_a_fee() {
+    let mut test = TestPool::new();
+    let (holder_a, _) = test.holder_balances();
+    // 9 basis points of the loan, a sixth of it is the protocol's
+    let (amount, fee, protocol_fee) = (DEPOSIT_A / 2, 1_800, 300);
+    assert_eq!(protocol_fee, fee * PROTOCOL_FEE as u64 / 10_000);
+    test.send(loan_repaid_with(&test, amount, amount + fee))
+        .unwrap();
+
+    assert_eq!(test.holder_balances().0, holder_a - fee);
+    assert_eq!(test.vault_balances(), (DEPOSIT_A + fee, DEPOSIT_B));
+    let pool = test.pool();
+    assert!(!pool.locked);
+    assert_eq!(pool.protocol_fees_a, protocol_fee);
+    assert_eq!(pool.reserve_a, DEPOSIT_A + fee - protocol_fee);
+}
+
+#[test]
+fn loan_has_to_be_repaid_in_full() {
+    let mut test = TestPool::new();
+    let amount = DEPOSIT_A / 2;
+    assert_error(
+        test.send(loan_repaid_with(&test, amount, amount)),
+        ErrorType::FlashLoanNotRepaid,
+    );
+    assert_error(
+        test.send(loan_repaid_with(&test, amount, amount + 1_799)),
+        ErrorType::FlashLoanNotRepaid,
+    );
+    assert_eq!(test.vault_balances(), (DEPOSIT_A, DEPOSIT_B));
+    assert!(!test.pool().locked);
+}
+
+#[test]
+fn loan_is_limited_to_the_reserves() {
+    let mut test = TestPool::new();
+    test.update_pool(|pool| pool.protocol_fees_a = 1);
+
+    // accrued protocol fees can't be lent out
+    let loan = flash_loan(&test, system_program::ID, DEPOSIT_A, 0);
+    assert_error(test.send(loan), ErrorType::InsufficientReserves);
+    let loan = flash_loan(&test, system_program::ID, 0, DEPOSIT_B + 1);
+    assert_error(test.send(loan), ErrorType::InsufficientReserves);
+}
+
+#[test]
+fn loan_cannot_call_back_into_the_amm() {
+    let mut test = TestPool::new();
+    let loan = flash_loan(&test, amm::ID, 1000, 0);
+    assert_error(test.send(loan), ErrorType::PoolLocked);
+}
+
+#[test]
+fn locked_pool_lends_nothing() {
+    let mut test = TestPool::new();
+    test.update_pool(|pool| pool.locked = true);
+    let loan = flash_loan(&test, system_program::ID, 1000, 0);
+    assert_error(test.send(loan), ErrorType::PoolLocked);
+}
+
+#[test]
+fn paused_pool_lends_nothing() {
+    let mut test = TestPool::new();
+    test.update_pool(|pool| pool.paused = true);
+    let loan = flash_loan(&test, system_program::ID, 1000, 0);
+    assert_error(test.send(loan), ErrorType::PoolPaused);
+
+    test.update_pool(|pool| pool.paused = false);
+    test.update_amm(|amm| amm.paused = true);
+    let loan = flash_loan(&test, system_program::ID, 1000, 0);
+    assert_error(test.send(loan), ErrorType::AmmPaused);
+}
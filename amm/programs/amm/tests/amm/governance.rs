@@ -0,0 +1,139 @@
+use amm::errors::ErrorType;
+use anchor_lang::prelude::Pubkey;
+
+use crate::{
+    fixtures::{assert_error, ix, TestPool, FEE, PROTOCOL_FEE},
+    svm::TxResult,
+};
+
+fn set_fee(test: &mut TestPool, admin: Pubkey, fee: u16, protocol_fee: u16) -> TxResult {
+    let instruction = ix(
+        amm::accounts::SetFee {
+            amm: test.keys.amm,
+            admin,
+        },
+        amm::instruction::SetFee { fee, protocol_fee },
+    );
+    test.send(instruction)
+}
+
+fn propose_admin(test: &mut TestPool, admin: Pubkey, new_admin: Pubkey) -> TxResult {
+    let instruction = ix(
+        amm::accounts::ProposeAdmin {
+            amm: test.keys.amm,
+            admin,
+        },
+        amm::instruction::ProposeAdmin { new_admin },
+    );
+    test.send(instruction)
+}
+
+fn accept_admin(test: &mut TestPool, pending_admin: Pubkey) -> TxResult {
+    let instruction = ix(
+        amm::accounts::AcceptAdmin {
+            amm: test.keys.amm,
+            pending_admin,
+        },
+        amm::instruction::AcceptAdmin {},
+    );
+    test.send(instruction)
+}
+
+#[test]
+fn admin_sets_fees() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    set_fee(&mut test, admin, 30, 2000).unwrap();
+
+    let amm = test.amm();
+    assert_eq!(amm.fee, 30);
+    assert_eq!(amm.protocol_fee, 2000);
+}
+
+#[test]
+fn set_fee_rejects_other_signers() {
+    let mut test = TestPool::new();
+    let result = set_fee(&mut test, Pubkey::new_unique(), 30, 2000);
+    assert_error(result, ErrorType::UnauthorizedAdmin);
+    assert_eq!(test.amm().fee, FEE);
+}
+
+#[test]
+fn set_fee_rejects_fee_of_100_percent() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    assert_error(
+        set_fee(&mut test, admin, 10000, PROTOCOL_FEE),
+        ErrorType::InvalidFee,
+    );
+}
+
+#[test]
+fn set_fee_rejects_protocol_fee_above_100_percent() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    assert_error(
+        set_fee(&mut test, admin, FEE, 10001),
+        ErrorType::InvalidProtocolFee,
+    );
+    set_fee(&mut test, admin, FEE, 10000).unwrap();
+}
+
+#[test]
+fn admin_hands_over_in_two_steps() {
+    let mut test = TestPool::new();
+    let (old_admin, new_admin) = (test.admin, Pubkey::new_unique());
+
+    propose_admin(&mut test, old_admin, new_admin).unwrap();
+    // nothing changes until the new admin accepts
+    assert_eq!(test.amm().admin, old_admin);
+    assert_eq!(test.amm().pending_admin, new_admin);
+
+    accept_admin(&mut test, new_admin).unwrap();
+    let amm = test.amm();
+    assert_eq!(amm.admin, new_admin);
+    assert_eq!(amm.pending_admin, Pubkey::default());
+
+    assert_error(
+        set_fee(&mut test, old_admin, 30, 0),
+        ErrorType::UnauthorizedAdmin,
+    );
+    set_fee(&mut test, new_admin, 30, 0).unwrap();
+}
+
+#[test]
+fn only_the_admin_proposes() {
+    let mut test = TestPool::new();
+    let outsider = Pubkey::new_unique();
+    assert_error(
+        propose_admin(&mut test, outsider, outsider),
+        ErrorType::UnauthorizedAdmin,
+    );
+}
+
+#[test]
+fn only_the_pending_admin_accepts() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    propose_admin(&mut test, admin, Pubkey::new_unique()).unwrap();
+
+    assert_error(
+        accept_admin(&mut test, Pubkey::new_unique()),
+        ErrorType::NotPendingAdmin,
+    );
+    // the current admin can't accept on the pending admin's behalf either
+    assert_error(accept_admin(&mut test, admin), ErrorType::NotPendingAdmin);
+}
+
+#[test]
+fn proposing_the_default_key_cancels() {
+    let mut test = TestPool::new();
+    let (admin, new_admin) = (test.admin, Pubkey::new_unique());
+    propose_admin(&mut test, admin, new_admin).unwrap();
+    propose_admin(&mut test, admin, Pubkey::default()).unwrap();
+
+    assert_error(
+        accept_admin(&mut test, new_admin),
+        ErrorType::NotPendingAdmin,
+    );
+}
@@ -0,0 +1,496 @@
+// random sequences of deposits, swaps and withdrawals sent to the program,
+// next to a model of the pool that runs the same amm-math steps. the program
+// has to accept exactly what the model does, and after every step its pool
+// account, vaults and lp balances have to match the model and keep the pool
+// invariants. runs are seeded, a failure names the seed and the steps that
+// led to it
+use amm::{constants::MINIMUM_LIQUIDITY, errors::ErrorType, state::CurveType};
+use amm_client::instructions;
+use amm_math::{
+    liquidity::{balanced_amounts, is_empty},
+    quote_deposit, quote_exact_input, quote_exact_output, quote_withdraw,
+    stable_swap::compute_d,
+    Curve, U256,
+};
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_interface::Mint;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    fixtures::{assert_error, token_account, with_data, TestPool},
+    svm::{TxError, TxResult},
+};
+
+// INVARIANT_RUNS=<n> runs longer sessions
+const RUNS: u64 = 32;
+const STEPS: usize = 40;
+const USERS: usize = 3;
+// 100M tokens with 9 decimals, STEPS of them still fit in a vault
+const MAX_AMOUNT: u64 = 100_000_000_000_000_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Step {
+    Deposit {
+        user: usize,
+        amount_a: u64,
+        amount_b: u64,
+    },
+    Swap {
+        swap_a: bool,
+        input: u64,
+    },
+    SwapExactOutput {
+        swap_a: bool,
+        output: u64,
+    },
+    Withdraw {
+        user: usize,
+        amount: u64,
+    },
+}
+
+// the pool as the program sees it, vaults are the reserves plus the protocol fees
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Model {
+    curve: Curve,
+    fee: u16,
+    protocol_fee: u16,
+    reserve_a: u64,
+    reserve_b: u64,
+    protocol_fees_a: u64,
+    protocol_fees_b: u64,
+    lp_supply: u64,
+    balances: [u64; USERS],
+}
+
+impl Model {
+    fn reserves_in_out(&self, swap_a: bool) -> (u64, u64) {
+        if swap_a {
+            (self.reserve_a, self.reserve_b)
+        } else {
+            (self.reserve_b, self.reserve_a)
+        }
+    }
+
+    // false when the instruction would fail, in the program or in its transfers
+    fn apply(&mut self, step: Step) -> bool {
+        match step {
+            Step::Deposit {
+                user,
+                amount_a,
+                amount_b,
+            } => {
+                let Some(quote) = quote_deposit(
+                    self.curve,
+                    self.reserve_a,
+                    self.reserve_b,
+                    self.lp_supply,
+                    amount_a,
+                    amount_b,
+                ) else {
+                    return false;
+                };
+                self.reserve_a = quote.reserve_a;
+                self.reserve_b = quote.reserve_b;
+                self.lp_supply = quote.lp_supply;
+                self.balances[user] += quote.liquidity;
+            }
+            Step::Swap { swap_a, input } => {
+                let (reserve_in, reserve_out) = self.reserves_in_out(swap_a);
+                let Some(quote) = quote_exact_input(
+                    self.curve,
+                    self.fee,
+                    self.protocol_fee,
+                    reserve_in,
+                    reserve_out,
+                    input,
+                ) else {
+                    return false;
+                };
+                self.swapped(swap_a, quote);
+            }
+            Step::SwapExactOutput { swap_a, output } => {
+                let (reserve_in, reserve_out) = self.reserves_in_out(swap_a);
+                let Some(quote) = quote_exact_output(
+                    self.curve,
+                    self.fee,
+                    self.protocol_fee,
+                    reserve_in,
+                    reserve_out,
+                    output,
+                ) else {
+                    return false;
+                };
+                self.swapped(swap_a, quote);
+            }
+            Step::Withdraw { user, amount } => {
+                if amount > self.balances[user] {
+                    return false;
+                }
+                let Some(quote) =
+                    quote_withdraw(self.reserve_a, self.reserve_b, self.lp_supply, amount)
+                else {
+                    return false;
+                };
+                self.reserve_a = quote.reserve_a;
+                self.reserve_b = quote.reserve_b;
+                self.lp_supply = quote.lp_supply;
+                self.balances[user] -= amount;
+            }
+        }
+
+        // the token program can't credit a vault past a u64
+        self.reserve_a.checked_add(self.protocol_fees_a).is_some()
+            && self.reserve_b.checked_add(self.protocol_fees_b).is_some()
+    }
+
+    fn swapped(&mut self, swap_a: bool, quote: amm_math::SwapQuote) {
+        if swap_a {
+            self.reserve_a = quote.reserve_in;
+            self.reserve_b = quote.reserve_out;
+            self.protocol_fees_a += quote.protocol_fee;
+        } else {
+            self.reserve_b = quote.reserve_in;
+            self.reserve_a = quote.reserve_out;
+            self.protocol_fees_b += quote.protocol_fee;
+        }
+    }
+
+    // lp tokens nobody holds, locked in the pool by the first deposit
+    fn locked(&self) -> u64 {
+        if self.lp_supply > 0 {
+            MINIMUM_LIQUIDITY
+        } else {
+            0
+        }
+    }
+}
+
+fn random_step(rng: &mut StdRng, model: &Model) -> Step {
+    let user = rng.gen_range(0..USERS);
+    let swap_a = rng.gen_bool(0.5);
+    let (reserve_in, reserve_out) = model.reserves_in_out(swap_a);
+    match rng.gen_range(0..4) {
+        0 => Step::Deposit {
+            user,
+            amount_a: rng.gen_range(1..=MAX_AMOUNT),
+            amount_b: rng.gen_range(1..=MAX_AMOUNT),
+        },
+        1 => Step::Swap {
+            swap_a,
+            input: rng.gen_range(1..=reserve_in.clamp(1, MAX_AMOUNT)),
+        },
+        2 => Step::SwapExactOutput {
+            swap_a,
+            output: rng.gen_range(1..=reserve_out.clamp(1, MAX_AMOUNT)),
+        },
+        _ => {
+            let balance = model.balances[user];
+            Step::Withdraw {
+                user,
+                // whole balances are common, they're where rounding hurts
+                amount: if rng.gen_bool(0.25) {
+                    balance
+                } else {
+                    rng.gen_range(0..=balance)
+                },
+            }
+        }
+    }
+}
+
+// what one lp token is worth can only go up: k / supply^2 for constant
+// product pools and D / supply for stableswap pools
+fn share_value_held(before: &Model, after: &Model) -> bool {
+    let (supply_before, supply_after) = (before.lp_supply, after.lp_supply);
+    match before.curve {
+        Curve::ConstantProduct => {
+            let k = |model: &Model| U256::from(model.reserve_a) * U256::from(model.reserve_b);
+            k(after) * U256::from(supply_before) * U256::from(supply_before)
+                >= k(before) * U256::from(supply_after) * U256::from(supply_after)
+        }
+        Curve::StableSwap { amp } => {
+            let d = |model: &Model| {
+                U256::from(compute_d(amp, model.reserve_a, model.reserve_b).unwrap())
+            };
+            // D is only solved to the unit
+            (d(after) + 1) * U256::from(supply_before) >= d(before) * U256::from(supply_after)
+        }
+    }
+}
+
+fn check_invariants(before: &Model, after: &Model, step: Step) -> Result<(), String> {
+    if after.balances.iter().sum::<u64>() + after.locked() != after.lp_supply {
+        return Err("lp balances and the locked liquidity don't add up to the lp supply".into());
+    }
+    // the locked minimum liquidity keeps a share of both reserves in the pool
+    if after.reserve_a == 0 || after.reserve_b == 0 {
+        return Err("pool was drained".into());
+    }
+    if after.protocol_fees_a < before.protocol_fees_a
+        || after.protocol_fees_b < before.protocol_fees_b
+    {
+        return Err("protocol fees went down".into());
+    }
+
+    match step {
+        Step::Swap { .. } | Step::SwapExactOutput { .. } => {
+            if after.lp_supply != before.lp_supply {
+                return Err("swap changed the lp supply".into());
+            }
+            let held = before.curve.invariant_held(
+                (before.reserve_a, before.reserve_b),
+                (after.reserve_a, after.reserve_b),
+            );
+            if held != Ok(true) {
+                return Err("swap lowered the invariant".into());
+            }
+        }
+        Step::Deposit { .. } | Step::Withdraw { .. } => {
+            if !share_value_held(before, after) {
+                return Err("lp tokens lost value".into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// the pool as the program left it. the stored reserves have to be the vaults
+// minus the protocol fees, and the lp balances plus the locked liquidity the
+// lp supply
+fn on_chain(test: &TestPool, users: &[Pubkey; USERS], model: &Model) -> Result<Model, String> {
+    let keys = &test.keys;
+    let pool = test.pool();
+    let (vault_a, vault_b) = test.vault_balances();
+    if vault_a.checked_sub(pool.protocol_fees_a) != Some(pool.reserve_a)
+        || vault_b.checked_sub(pool.protocol_fees_b) != Some(pool.reserve_b)
+    {
+        return Err("stored reserves aren't the vaults minus the protocol fees".into());
+    }
+
+    let lp_supply = test.svm.get::<Mint>(&keys.mint_liquidity).supply;
+    let balances = users.map(|user| {
+        test.svm
+            .balance(&keys.user_account(&user, &keys.mint_liquidity))
+    });
+    let locked = test.svm.balance(&keys.locked_liquidity);
+    if balances.iter().sum::<u64>() + locked != lp_supply {
+        return Err("lp accounts don't add up to the lp supply".into());
+    }
+
+    Ok(Model {
+        reserve_a: pool.reserve_a,
+        reserve_b: pool.reserve_b,
+        protocol_fees_a: pool.protocol_fees_a,
+        protocol_fees_b: pool.protocol_fees_b,
+        lp_supply,
+        balances,
+        ..*model
+    })
+}
+
+// sends the step as `user`, the program has to accept exactly what the
+// model does, the quoted amounts being the limit
+fn send_step(test: &mut TestPool, user: Pubkey, model: &Model, step: Step) {
+    let accepted = { *model }.apply(step);
+    let accept = |result: TxResult| match result {
+        Ok(()) => assert!(accepted, "program accepted {step:?}"),
+        Err(error) => assert!(!accepted, "program rejected {step:?} with {error:?}"),
+    };
+    let keys = test.keys;
+
+    match step {
+        Step::Deposit {
+            amount_a, amount_b, ..
+        } => {
+            let deposit =
+                instructions::deposit_liquidity(&keys, &user, &user, amount_a, amount_b, 0, None);
+            // matching the pool ratio is the first thing a deposit does
+            let (reserve_a, reserve_b) = (model.reserve_a, model.reserve_b);
+            let balanced = if is_empty(reserve_a, reserve_b, model.lp_supply) {
+                Ok((amount_a, amount_b))
+            } else {
+                balanced_amounts(amount_a, amount_b, reserve_a, reserve_b)
+            };
+            match balanced {
+                Ok(_) => accept(test.send(deposit)),
+                Err(error) => assert_error(test.send(deposit), error.into()),
+            }
+        }
+        Step::Swap { swap_a, input } => {
+            let swap = instructions::swap_tokens(&keys, &user, &user, swap_a, input, 0, None);
+            accept(test.send(swap));
+        }
+        Step::SwapExactOutput { swap_a, output } => {
+            let (reserve_in, reserve_out) = model.reserves_in_out(swap_a);
+            let quote = quote_exact_output(
+                model.curve,
+                model.fee,
+                model.protocol_fee,
+                reserve_in,
+                reserve_out,
+                output,
+            );
+            let swap = |max_input_amount| {
+                with_data(
+                    instructions::swap_tokens(&keys, &user, &user, swap_a, 0, 0, None),
+                    amm::instruction::SwapTokensForExactTokens {
+                        swap_a,
+                        output_amount: output,
+                        max_input_amount,
+                        deadline: None,
+                    },
+                )
+            };
+            match quote {
+                Some(quote) => {
+                    let (too_little, quoted) = (swap(quote.input - 1), swap(quote.input));
+                    assert_error(test.send(too_little), ErrorType::InputTooLarge);
+                    accept(test.send(quoted));
+                }
+                None => {
+                    let any_input = swap(u64::MAX);
+                    assert!(matches!(test.send(any_input), Err(TxError::Program(_))));
+                }
+            }
+        }
+        Step::Withdraw {
+            user: index,
+            amount,
+        } => {
+            let quote = quote_withdraw(model.reserve_a, model.reserve_b, model.lp_supply, amount);
+            let withdraw = |min_amount_a, min_amount_b| {
+                instructions::withdraw_liquidity(
+                    &keys,
+                    &user,
+                    &user,
+                    amount,
+                    min_amount_a,
+                    min_amount_b,
+                    None,
+                )
+            };
+            match quote {
+                Some(quote) if amount <= model.balances[index] => {
+                    let (too_much_a, too_much_b, quoted) = (
+                        withdraw(quote.amount_a + 1, quote.amount_b),
+                        withdraw(quote.amount_a, quote.amount_b + 1),
+                        withdraw(quote.amount_a, quote.amount_b),
+                    );
+                    assert_error(test.send(too_much_a), ErrorType::OutputTooSmall);
+                    assert_error(test.send(too_much_b), ErrorType::OutputTooSmall);
+                    accept(test.send(quoted));
+                }
+                _ => {
+                    let anything = withdraw(0, 0);
+                    assert!(matches!(test.send(anything), Err(TxError::Program(_))));
+                }
+            }
+        }
+    }
+}
+
+fn run(seed: u64, curve_type: CurveType) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let amp = rng.gen_range(1..=1000);
+    let mut test = TestPool::with_curve(curve_type, amp);
+    test.empty_pool();
+    let (fee, protocol_fee) = (rng.gen_range(0..1000), rng.gen_range(0..=10000));
+    test.update_amm(|amm| {
+        amm.fee = fee;
+        amm.protocol_fee = protocol_fee;
+    });
+
+    // deposits and swaps of any size stay within each user's means, and
+    // everything they hold still fits in a vault
+    let keys = test.keys;
+    let users = [(); USERS].map(|_| Pubkey::new_unique());
+    for user in users {
+        test.svm.airdrop(&user, 10_000_000_000);
+        for (mint, amount) in [
+            (keys.mint_a, u64::MAX / 4),
+            (keys.mint_b, u64::MAX / 4),
+            (keys.mint_liquidity, 0),
+        ] {
+            let token_program = keys.mint_token_program(&mint);
+            test.svm.set_account(
+                keys.user_account(&user, &mint),
+                token_account(&mint, &user, amount, &token_program),
+            );
+        }
+    }
+
+    let mut model = Model {
+        curve: test.pool().curve(test.svm.now()),
+        fee,
+        protocol_fee,
+        reserve_a: 0,
+        reserve_b: 0,
+        protocol_fees_a: 0,
+        protocol_fees_b: 0,
+        lp_supply: 0,
+        balances: [0; USERS],
+    };
+    let mut pool = model;
+    let mut steps = Vec::with_capacity(STEPS);
+    for i in 0..STEPS {
+        let step = if i == 0 {
+            Step::Deposit {
+                user: 0,
+                amount_a: rng.gen_range(1_000..=MAX_AMOUNT),
+                amount_b: rng.gen_range(1_000..=MAX_AMOUNT),
+            }
+        } else {
+            random_step(&mut rng, &model)
+        };
+        steps.push(step);
+
+        // any user can swap, swaps leave the lp balances alone
+        let user = match step {
+            Step::Deposit { user, .. } | Step::Withdraw { user, .. } => user,
+            Step::Swap { .. } | Step::SwapExactOutput { .. } => rng.gen_range(0..USERS),
+        };
+        send_step(&mut test, users[user], &model, step);
+        let (model_before, pool_before) = (model, pool);
+        let applied = model.apply(step);
+        if !applied {
+            model = model_before;
+        }
+
+        let checked = on_chain(&test, &users, &model).and_then(|after| {
+            pool = after;
+            if pool != model {
+                return Err("program and model disagree".into());
+            }
+            if applied {
+                check_invariants(&pool_before, &pool, step)
+            } else {
+                Ok(())
+            }
+        });
+        if let Err(broken) = checked {
+            panic!("{broken} with seed {seed} after {steps:#?}\n{model:#?}\n{pool:#?}");
+        }
+    }
+}
+
+fn runs() -> u64 {
+    std::env::var("INVARIANT_RUNS")
+        .map(|runs| runs.parse().expect("INVARIANT_RUNS is a number"))
+        .unwrap_or(RUNS)
+}
+
+#[test]
+fn constant_product_pools_keep_their_invariants() {
+    for seed in 0..runs() {
+        run(seed, CurveType::ConstantProduct);
+    }
+}
+
+#[test]
+fn stableswap_pools_keep_their_invariants() {
+    for seed in 0..runs() {
+        run(seed, CurveType::StableSwap);
+    }
+}
@@ -0,0 +1,80 @@
+use amm::{
+    constants::MINIMUM_LIQUIDITY,
+    state::{CurveType, Pool},
+};
+use amm_client::instructions;
+use anchor_lang::{error::ErrorCode, prelude::Pubkey, AnchorSerialize, Discriminator};
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    fixtures::{assert_anchor_error, TestPool, DEPOSIT_A, DEPOSIT_B, LIQUIDITY},
+    svm::Account,
+};
+
+// a pool from before the lock, its supply doesn't count the minimum liquidity
+fn legacy_pool() -> TestPool {
+    let mut test = TestPool::new();
+    test.remove_locked_liquidity();
+    test.set_liquidity(LIQUIDITY, LIQUIDITY);
+    test
+}
+
+#[test]
+fn migration_locks_the_minimum_liquidity() {
+    let mut test = legacy_pool();
+    let lock = instructions::lock_minimum_liquidity(&test.keys, &test.admin);
+    test.send(lock.clone()).unwrap();
+
+    let keys = test.keys;
+    assert_eq!(test.svm.balance(&keys.locked_liquidity), MINIMUM_LIQUIDITY);
+    assert_eq!(
+        test.svm.get::<Mint>(&keys.mint_liquidity).supply,
+        LIQUIDITY + MINIMUM_LIQUIDITY
+    );
+
+    // the locked account exists now, so it only happens once
+    assert!(test.send(lock).is_err());
+}
+
+#[test]
+fn migration_grows_legacy_pool_accounts() {
+    let mut test = legacy_pool();
+    let keys = test.keys;
+    let mut data = Pool::DISCRIMINATOR.to_vec();
+    (keys.amm, keys.mint_a, keys.mint_b)
+        .serialize(&mut data)
+        .unwrap();
+    assert_eq!(data.len(), Pool::LEGACY_LEN);
+    test.svm.set_account(keys.pool, Account::new(data, amm::ID));
+
+    let lock = instructions::lock_minimum_liquidity(&keys, &test.admin);
+    test.send(lock).unwrap();
+
+    let account = test.svm.account(&keys.pool).unwrap();
+    assert_eq!(account.data.len(), Pool::LEN);
+    // topped up to stay rent exempt at the new size
+    assert_eq!(account, Account::new(account.data.clone(), amm::ID));
+    let pool = test.pool();
+    assert_eq!(
+        (pool.amm, pool.mint_a, pool.mint_b),
+        (keys.amm, keys.mint_a, keys.mint_b)
+    );
+    assert!(pool.curve_type == CurveType::ConstantProduct);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (DEPOSIT_A, DEPOSIT_B));
+    assert_eq!(pool.last_update_ts, test.svm.now());
+    assert_eq!(test.svm.balance(&keys.locked_liquidity), MINIMUM_LIQUIDITY);
+
+    // and it trades like any other pool
+    let (holder_a, _) = test.holder_balances();
+    test.send(test.swap_ix(true, 1000, 0)).unwrap();
+    assert_eq!(test.holder_balances().0, holder_a - 1000);
+}
+
+#[test]
+fn migration_only_creates_the_pool_locked_account() {
+    let mut test = legacy_pool();
+    let mut keys = test.keys;
+    keys.locked_liquidity = Pubkey::new_unique();
+    let lock = instructions::lock_minimum_liquidity(&keys, &test.admin);
+    assert_anchor_error(test.send(lock), ErrorCode::ConstraintSeeds);
+}
@@ -0,0 +1,21 @@
+// rust integration tests, the built program runs in litesvm on seeded
+// accounts, see svm.rs. they run with every `cargo test` and fail if
+// `anchor build` hasn't been run first
+
+mod fixtures;
+mod svm;
+
+mod concentrated_liquidity;
+mod create_pool;
+mod deposit_liquidity;
+mod flash_loan;
+mod governance;
+mod invariants;
+mod lock_minimum_liquidity;
+mod pause_resume;
+mod stable_swap;
+mod swap_route;
+mod swap_tokens;
+mod token_2022;
+mod twap_oracle;
+mod withdraw_liquidity;
@@ -0,0 +1,170 @@
+use amm::errors::ErrorType;
+use anchor_lang::prelude::Pubkey;
+
+use crate::{
+    fixtures::{assert_error, ix, TestPool, DEPOSIT_A, DEPOSIT_B},
+    svm::TxResult,
+};
+
+fn pause_amm(test: &mut TestPool, admin: Pubkey, allow_withdrawals: bool) -> TxResult {
+    let instruction = ix(
+        amm::accounts::PauseAmm {
+            amm: test.keys.amm,
+            admin,
+        },
+        amm::instruction::PauseAmm { allow_withdrawals },
+    );
+    test.send(instruction)
+}
+
+fn resume_amm(test: &mut TestPool, admin: Pubkey) -> TxResult {
+    let instruction = ix(
+        amm::accounts::ResumeAmm {
+            amm: test.keys.amm,
+            admin,
+        },
+        amm::instruction::ResumeAmm {},
+    );
+    test.send(instruction)
+}
+
+fn pause_pool(test: &mut TestPool, admin: Pubkey, allow_withdrawals: bool) -> TxResult {
+    let instruction = ix(
+        amm::accounts::PausePool {
+            amm: test.keys.amm,
+            pool: test.keys.pool,
+            admin,
+        },
+        amm::instruction::PausePool { allow_withdrawals },
+    );
+    test.send(instruction)
+}
+
+fn resume_pool(test: &mut TestPool, admin: Pubkey) -> TxResult {
+    let instruction = ix(
+        amm::accounts::ResumePool {
+            amm: test.keys.amm,
+            pool: test.keys.pool,
+            admin,
+        },
+        amm::instruction::ResumePool {},
+    );
+    test.send(instruction)
+}
+
+#[test]
+fn paused_amm_stops_swaps_and_deposits() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    pause_amm(&mut test, admin, true).unwrap();
+    assert!(test.amm().paused);
+    assert!(!test.amm().withdrawals_paused);
+
+    assert_error(test.send(test.swap_ix(true, 1000, 0)), ErrorType::AmmPaused);
+    assert_error(
+        test.send(test.deposit_ix(DEPOSIT_A, DEPOSIT_B)),
+        ErrorType::AmmPaused,
+    );
+    // lps can still get out
+    test.send(test.withdraw_ix(1000)).unwrap();
+}
+
+#[test]
+fn paused_amm_can_stop_withdrawals() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    pause_amm(&mut test, admin, false).unwrap();
+
+    assert_error(test.send(test.withdraw_ix(1000)), ErrorType::AmmPaused);
+}
+
+#[test]
+fn resumed_amm_trades_again() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    pause_amm(&mut test, admin, false).unwrap();
+    resume_amm(&mut test, admin).unwrap();
+
+    let amm = test.amm();
+    assert!(!amm.paused);
+    assert!(!amm.withdrawals_paused);
+    test.send(test.swap_ix(true, 1000, 0)).unwrap();
+    test.send(test.withdraw_ix(1000)).unwrap();
+}
+
+#[test]
+fn amm_pause_is_not_repeated() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    assert_error(resume_amm(&mut test, admin), ErrorType::NotPaused);
+
+    pause_amm(&mut test, admin, true).unwrap();
+    assert_error(pause_amm(&mut test, admin, false), ErrorType::AlreadyPaused);
+}
+
+#[test]
+fn only_the_admin_pauses_the_amm() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    let outsider = Pubkey::new_unique();
+    assert_error(
+        pause_amm(&mut test, outsider, true),
+        ErrorType::UnauthorizedAdmin,
+    );
+
+    pause_amm(&mut test, admin, true).unwrap();
+    assert_error(
+        resume_amm(&mut test, outsider),
+        ErrorType::UnauthorizedAdmin,
+    );
+}
+
+#[test]
+fn paused_pool_stops_swaps_and_deposits() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    pause_pool(&mut test, admin, true).unwrap();
+    assert!(test.pool().paused);
+
+    assert_error(
+        test.send(test.swap_ix(false, 1000, 0)),
+        ErrorType::PoolPaused,
+    );
+    assert_error(
+        test.send(test.deposit_ix(DEPOSIT_A, DEPOSIT_B)),
+        ErrorType::PoolPaused,
+    );
+    test.send(test.withdraw_ix(1000)).unwrap();
+}
+
+#[test]
+fn paused_pool_can_stop_withdrawals() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    pause_pool(&mut test, admin, false).unwrap();
+
+    assert_error(test.send(test.withdraw_ix(1000)), ErrorType::PoolPaused);
+
+    resume_pool(&mut test, admin).unwrap();
+    assert!(!test.pool().withdrawals_paused);
+    test.send(test.withdraw_ix(1000)).unwrap();
+}
+
+#[test]
+fn pool_pause_is_not_repeated() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    assert_error(resume_pool(&mut test, admin), ErrorType::NotPaused);
+
+    pause_pool(&mut test, admin, true).unwrap();
+    assert_error(pause_pool(&mut test, admin, true), ErrorType::AlreadyPaused);
+}
+
+#[test]
+fn only_the_admin_pauses_a_pool() {
+    let mut test = TestPool::new();
+    assert_error(
+        pause_pool(&mut test, Pubkey::new_unique(), true),
+        ErrorType::UnauthorizedAdmin,
+    );
+}
@@ -0,0 +1,213 @@
+use amm::{
+    constants::{MAX_AMP, MIN_AMP_RAMP_DURATION},
+    errors::ErrorType,
+    state::CurveType,
+};
+use amm_client::quote::{quote_swap, quote_swap_exact_output};
+use anchor_lang::prelude::Pubkey;
+
+use crate::{
+    fixtures::{assert_error, ix, with_data, TestPool, DEPOSIT_A, DEPOSIT_B},
+    svm::TxResult,
+};
+
+const AMP: u64 = 100;
+
+fn ramp_amp(test: &mut TestPool, admin: Pubkey, target_amp: u64, ramp_end_ts: i64) -> TxResult {
+    let instruction = ix(
+        amm::accounts::RampAmp {
+            amm: test.keys.amm,
+            pool: test.keys.pool,
+            admin,
+        },
+        amm::instruction::RampAmp {
+            target_amp,
+            ramp_end_ts,
+        },
+    );
+    test.send(instruction)
+}
+
+fn stop_ramp_amp(test: &mut TestPool, admin: Pubkey) -> TxResult {
+    let instruction = ix(
+        amm::accounts::StopRampAmp {
+            amm: test.keys.amm,
+            pool: test.keys.pool,
+            admin,
+        },
+        amm::instruction::StopRampAmp {},
+    );
+    test.send(instruction)
+}
+
+#[test]
+fn amp_ramps_linearly_and_stops() {
+    let mut test = TestPool::with_curve(CurveType::StableSwap, AMP);
+    let admin = test.admin;
+    let start = test.svm.now();
+    ramp_amp(&mut test, admin, 2 * AMP, start + 2 * MIN_AMP_RAMP_DURATION).unwrap();
+
+    let pool = test.pool();
+    assert_eq!(pool.amp_initial, AMP);
+    assert_eq!(pool.amp_target, 2 * AMP);
+    assert_eq!(pool.amp_ramp_start_ts, start);
+
+    test.svm.warp(MIN_AMP_RAMP_DURATION);
+    assert_eq!(test.pool().current_amp(test.svm.now()), AMP + AMP / 2);
+
+    stop_ramp_amp(&mut test, admin).unwrap();
+    test.svm.warp(MIN_AMP_RAMP_DURATION);
+    assert_eq!(test.pool().current_amp(test.svm.now()), AMP + AMP / 2);
+}
+
+#[test]
+fn ramp_is_stableswap_only() {
+    let mut test = TestPool::new();
+    let admin = test.admin;
+    let end = test.svm.now() + MIN_AMP_RAMP_DURATION;
+
+    assert_error(
+        ramp_amp(&mut test, admin, AMP, end),
+        ErrorType::InvalidCurveType,
+    );
+    assert_error(stop_ramp_amp(&mut test, admin), ErrorType::InvalidCurveType);
+}
+
+#[test]
+fn ramp_target_stays_in_bounds() {
+    let mut test = TestPool::with_curve(CurveType::StableSwap, AMP);
+    let admin = test.admin;
+    let end = test.svm.now() + MIN_AMP_RAMP_DURATION;
+
+    assert_error(ramp_amp(&mut test, admin, 0, end), ErrorType::InvalidAmp);
+    assert_error(
+        ramp_amp(&mut test, admin, MAX_AMP + 1, end),
+        ErrorType::InvalidAmp,
+    );
+    // at most a tenfold change either way
+    assert_error(
+        ramp_amp(&mut test, admin, AMP * 10 + 1, end),
+        ErrorType::InvalidAmp,
+    );
+    assert_error(
+        ramp_amp(&mut test, admin, AMP / 10 - 1, end),
+        ErrorType::InvalidAmp,
+    );
+    ramp_amp(&mut test, admin, AMP * 10, end).unwrap();
+}
+
+#[test]
+fn ramps_are_slow_and_spaced_out() {
+    let mut test = TestPool::with_curve(CurveType::StableSwap, AMP);
+    let admin = test.admin;
+    let now = test.svm.now();
+
+    assert_error(
+        ramp_amp(&mut test, admin, 2 * AMP, now + MIN_AMP_RAMP_DURATION - 1),
+        ErrorType::InvalidAmpRamp,
+    );
+    ramp_amp(&mut test, admin, 2 * AMP, now + MIN_AMP_RAMP_DURATION).unwrap();
+
+    // the next ramp can only start a full ramp duration after this one
+    test.svm.warp(MIN_AMP_RAMP_DURATION - 1);
+    let now = test.svm.now();
+    assert_error(
+        ramp_amp(&mut test, admin, AMP, now + MIN_AMP_RAMP_DURATION),
+        ErrorType::InvalidAmpRamp,
+    );
+    test.svm.warp(1);
+    ramp_amp(&mut test, admin, AMP, now + 1 + MIN_AMP_RAMP_DURATION).unwrap();
+}
+
+#[test]
+fn only_the_admin_ramps() {
+    let mut test = TestPool::with_curve(CurveType::StableSwap, AMP);
+    let outsider = Pubkey::new_unique();
+    let end = test.svm.now() + MIN_AMP_RAMP_DURATION;
+
+    assert_error(
+        ramp_amp(&mut test, outsider, 2 * AMP, end),
+        ErrorType::UnauthorizedAdmin,
+    );
+    assert_error(
+        stop_ramp_amp(&mut test, outsider),
+        ErrorType::UnauthorizedAdmin,
+    );
+}
+
+#[test]
+fn exact_output_charges_the_quoted_input() {
+    let mut test = TestPool::with_curve(CurveType::StableSwap, AMP);
+    let output = DEPOSIT_B / 2;
+    let quote = quote_swap_exact_output(
+        &test.amm(),
+        &test.pool(),
+        DEPOSIT_A,
+        DEPOSIT_B,
+        true,
+        output,
+        test.svm.now(),
+    )
+    .unwrap();
+    let swap = |max_input_amount| {
+        with_data(
+            test.swap_ix(true, 0, 0),
+            amm::instruction::SwapTokensForExactTokens {
+                swap_a: true,
+                output_amount: output,
+                max_input_amount,
+                deadline: None,
+            },
+        )
+    };
+    let (too_little, quoted) = (swap(quote.input - 1), swap(quote.input));
+
+    assert_error(test.send(too_little), ErrorType::InputTooLarge);
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(quoted).unwrap();
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a - quote.input, holder_b + output)
+    );
+}
+
+#[test]
+fn swaps_follow_the_stableswap_curve() {
+    let mut test = TestPool::with_curve(CurveType::StableSwap, AMP);
+    let quote = quote_swap(
+        &test.amm(),
+        &test.pool(),
+        DEPOSIT_A,
+        DEPOSIT_B,
+        true,
+        10_000,
+        test.svm.now(),
+    )
+    .unwrap();
+    // a is the plentiful side, still a near one to one trade minus the fee
+    assert!(quote.output > 9_000 && quote.output < 9_500);
+
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(test.swap_ix(true, 10_000, quote.output)).unwrap();
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a - 10_000, holder_b + quote.output)
+    );
+}
+
+#[test]
+fn swap_fails_when_the_curve_math_does() {
+    let mut test = TestPool::with_curve(CurveType::StableSwap, AMP);
+    // one unit of a against a million of b is too lopsided for newton's method
+    test.set_vaults(1, DEPOSIT_B);
+    let exact_output = with_data(
+        test.swap_ix(true, 0, 0),
+        amm::instruction::SwapTokensForExactTokens {
+            swap_a: true,
+            output_amount: 1,
+            max_input_amount: u64::MAX,
+            deadline: None,
+        },
+    );
+    assert_error(test.send(exact_output), ErrorType::CurveMathFailed);
+}
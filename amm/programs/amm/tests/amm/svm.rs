@@ -0,0 +1,161 @@
+// the integration tests run the built program in litesvm, next to the spl
+// token, token-2022 and associated token programs it ships with. build it
+// first, `anchor build` puts it in target/deploy.
+//
+// accounts are seeded directly rather than made through the token programs,
+// so a test can start from any state. signatures aren't verified, a meta
+// marked as signer is trusted and a funded payer covers the fees
+
+use anchor_lang::{
+    prelude::{Clock, ProgramError, Pubkey, Rent},
+    solana_program::instruction::Instruction,
+    AccountDeserialize, AnchorDeserialize,
+};
+use anchor_spl::token_interface::TokenAccount;
+use litesvm::LiteSVM;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+const PROGRAM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy/amm.so");
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Account {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+impl Account {
+    // rent exempt account owned by `owner`
+    pub fn new(data: Vec<u8>, owner: Pubkey) -> Self {
+        Self {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TxError {
+    Program(ProgramError),
+    // the runtime turned the transaction down, e.g. an account left below
+    // rent exemption
+    Transaction(TransactionError),
+}
+
+pub type TxResult = Result<(), TxError>;
+
+impl From<TransactionError> for TxError {
+    fn from(error: TransactionError) -> Self {
+        match error {
+            TransactionError::InstructionError(index, error) => match ProgramError::try_from(error)
+            {
+                Ok(error) => TxError::Program(error),
+                Err(error) => {
+                    TxError::Transaction(TransactionError::InstructionError(index, error))
+                }
+            },
+            error => TxError::Transaction(error),
+        }
+    }
+}
+
+pub struct Svm {
+    svm: LiteSVM,
+    payer: Pubkey,
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        let mut svm = LiteSVM::new()
+            .with_sigverify(false)
+            .with_blockhash_check(false)
+            // tests send the same transaction more than once
+            .with_transaction_history(0);
+        svm.set_sysvar(&Clock {
+            slot: 1,
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        });
+        svm.add_program_from_file(amm::ID, PROGRAM_PATH)
+            .unwrap_or_else(|error| {
+                panic!("can't load {PROGRAM_PATH}, run `anchor build` first: {error}")
+            });
+
+        let payer = Pubkey::new_unique();
+        svm.airdrop(&payer, 1_000_000_000_000).unwrap();
+        Self { svm, payer }
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: Account) {
+        self.svm
+            .set_account(
+                key,
+                solana_account::Account {
+                    lamports: account.lamports,
+                    data: account.data,
+                    owner: account.owner,
+                    executable: account.executable,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<Account> {
+        self.svm.get_account(key).map(|account| Account {
+            lamports: account.lamports,
+            data: account.data,
+            owner: account.owner,
+            executable: account.executable,
+        })
+    }
+
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.account(key).expect("account does not exist");
+        T::try_deserialize(&mut account.data.as_slice()).expect("invalid account data")
+    }
+
+    // token balance of either token program's accounts
+    pub fn balance(&self, key: &Pubkey) -> u64 {
+        self.get::<TokenAccount>(key).amount
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        self.svm.airdrop(key, lamports).unwrap();
+    }
+
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    // moves the clock forward, about two and a half slots a second
+    pub fn warp(&mut self, seconds: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        clock.slot += seconds as u64 * 5 / 2;
+        self.svm.set_sysvar(&clock);
+    }
+
+    // runs the instructions as one transaction, nothing is kept if one fails
+    pub fn send(&mut self, instructions: &[Instruction]) -> TxResult {
+        let transaction = Transaction::new_with_payer(instructions, Some(&self.payer));
+        self.svm
+            .send_transaction(transaction)
+            .map(|_| ())
+            .map_err(|failed| failed.err.into())
+    }
+
+    // simulates the instruction and decodes what it returned
+    pub fn view<T: AnchorDeserialize>(&self, instruction: Instruction) -> Result<T, TxError> {
+        let transaction = Transaction::new_with_payer(&[instruction], Some(&self.payer));
+        let info = self
+            .svm
+            .simulate_transaction(transaction)
+            .map_err(|failed| TxError::from(failed.err))?;
+        Ok(T::try_from_slice(&info.meta.return_data.data).expect("invalid return data"))
+    }
+}
@@ -0,0 +1,142 @@
+use amm::errors::ErrorType;
+use amm_client::quote::quote_swap;
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::instruction::Instruction,
+};
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+use crate::fixtures::{
+    assert_error, ix, mint_account, token_account, with_remaining, TestPool, DEFAULT_SUPPLY,
+    DEPOSIT_A, DEPOSIT_B,
+};
+
+fn swap_route(test: &TestPool, deadline: Option<i64>) -> Instruction {
+    let keys = &test.keys;
+    ix(
+        amm::accounts::SwapRoute {
+            amm: keys.amm,
+            source_mint: keys.mint_a,
+            trader_source: keys.user_account(&test.holder, &keys.mint_a),
+            trader_destination: keys.user_account(&test.holder, &keys.mint_b),
+            trader: test.holder,
+            token_program: keys.token_program,
+        },
+        amm::instruction::SwapRoute {
+            input_amount: 1000,
+            min_output_amount: 0,
+            deadline,
+        },
+    )
+}
+
+// a -> b through the test pool
+fn hop(test: &TestPool) -> Vec<AccountMeta> {
+    let keys = &test.keys;
+    vec![
+        AccountMeta::new(keys.pool, false),
+        AccountMeta::new_readonly(keys.pool_authority, false),
+        AccountMeta::new(keys.pool_account_a, false),
+        AccountMeta::new(keys.pool_account_b, false),
+        AccountMeta::new_readonly(keys.mint_b, false),
+        AccountMeta::new_readonly(keys.token_program_b, false),
+    ]
+}
+
+#[test]
+fn single_hop_route_prices_like_a_swap() {
+    let mut test = TestPool::new();
+    let quote = quote_swap(
+        &test.amm(),
+        &test.pool(),
+        DEPOSIT_A,
+        DEPOSIT_B,
+        true,
+        1000,
+        test.svm.now(),
+    )
+    .unwrap();
+    let (holder_a, holder_b) = test.holder_balances();
+    let route = with_remaining(swap_route(&test, None), &hop(&test));
+    test.send(route).unwrap();
+
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a - 1000, holder_b + quote.output)
+    );
+    assert_eq!(
+        test.vault_balances(),
+        (DEPOSIT_A + 1000, DEPOSIT_B - quote.output)
+    );
+    assert_eq!(test.pool().protocol_fees_a, quote.protocol_fee);
+}
+
+#[test]
+fn route_needs_whole_hops() {
+    let mut test = TestPool::new();
+    assert_error(test.send(swap_route(&test, None)), ErrorType::InvalidRoute);
+
+    let partial_hop = &hop(&test)[..5];
+    let route = with_remaining(swap_route(&test, None), partial_hop);
+    assert_error(test.send(route), ErrorType::InvalidRoute);
+}
+
+#[test]
+fn route_rejects_expired_deadline() {
+    let mut test = TestPool::new();
+    let deadline = test.svm.now() - 1;
+    let route = with_remaining(swap_route(&test, Some(deadline)), &hop(&test));
+    assert_error(test.send(route), ErrorType::DeadlineExceeded);
+}
+
+#[test]
+fn route_rejects_paused_amm() {
+    let mut test = TestPool::new();
+    test.update_amm(|amm| amm.paused = true);
+    let route = with_remaining(swap_route(&test, None), &hop(&test));
+    assert_error(test.send(route), ErrorType::AmmPaused);
+}
+
+#[test]
+fn route_checks_the_hop_token_program() {
+    let mut test = TestPool::new();
+    let mut hop = hop(&test);
+    hop[5].pubkey = TOKEN_2022_PROGRAM_ID;
+    let route = with_remaining(swap_route(&test, None), &hop);
+    assert_error(test.send(route), ErrorType::InvalidRoute);
+}
+
+#[test]
+fn route_rejects_a_pool_that_does_not_trade_the_input() {
+    let mut test = TestPool::new();
+    let keys = test.keys;
+    let (admin, holder) = (test.admin, test.holder);
+    // the holder sends some other mint into an account of the pool authority
+    let other_mint = Pubkey::new_unique();
+    let (holder_account, authority_account) = (
+        keys.user_account(&holder, &other_mint),
+        keys.user_account(&keys.pool_authority, &other_mint),
+    );
+    test.svm.set_account(
+        other_mint,
+        mint_account(&admin, DEFAULT_SUPPLY, &keys.token_program),
+    );
+    test.svm.set_account(
+        holder_account,
+        token_account(&other_mint, &holder, DEFAULT_SUPPLY, &keys.token_program),
+    );
+    test.svm.set_account(
+        authority_account,
+        token_account(&other_mint, &keys.pool_authority, 0, &keys.token_program),
+    );
+
+    let mut route = swap_route(&test, None);
+    route.accounts[1].pubkey = other_mint;
+    route.accounts[2].pubkey = holder_account;
+    let mut hop = hop(&test);
+    hop[2].pubkey = authority_account;
+    assert_error(
+        test.send(with_remaining(route, &hop)),
+        ErrorType::InvalidMint,
+    );
+}
@@ -0,0 +1,257 @@
+use amm::errors::ErrorType;
+use amm_client::quote::{quote_swap, quote_swap_exact_output};
+use anchor_lang::{error::ErrorCode, solana_program::instruction::Instruction};
+
+use crate::fixtures::{
+    assert_anchor_error, assert_error, with_data, TestPool, DEPOSIT_A, DEPOSIT_B,
+};
+
+fn exact_output_ix(
+    test: &TestPool,
+    swap_a: bool,
+    output_amount: u64,
+    max_input_amount: u64,
+    deadline: Option<i64>,
+) -> Instruction {
+    with_data(
+        test.swap_ix(swap_a, 0, 0),
+        amm::instruction::SwapTokensForExactTokens {
+            swap_a,
+            output_amount,
+            max_input_amount,
+            deadline,
+        },
+    )
+}
+
+#[test]
+fn swap_pays_out_at_the_curve_price() {
+    let mut test = TestPool::new();
+    let (holder_a, holder_b) = test.holder_balances();
+    // 5% of 10_000 is 500, 9_500 * 1_000_000 / (4_000_000 + 9_500) is 2_369
+    // and the protocol keeps 500 * 16.67% of the fee
+    let (output, protocol_fee) = (2_369, 83);
+    test.send(test.swap_ix(true, 10_000, output)).unwrap();
+
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a - 10_000, holder_b + output)
+    );
+    assert_eq!(
+        test.vault_balances(),
+        (DEPOSIT_A + 10_000, DEPOSIT_B - output)
+    );
+    let pool = test.pool();
+    assert_eq!(
+        (pool.protocol_fees_a, pool.protocol_fees_b),
+        (protocol_fee, 0)
+    );
+    assert_eq!(
+        (pool.reserve_a, pool.reserve_b),
+        (DEPOSIT_A + 10_000 - protocol_fee, DEPOSIT_B - output)
+    );
+}
+
+#[test]
+fn swaps_go_both_ways() {
+    let mut test = TestPool::new();
+    let (holder_a, holder_b) = test.holder_balances();
+    let quote = quote_swap(
+        &test.amm(),
+        &test.pool(),
+        DEPOSIT_A,
+        DEPOSIT_B,
+        false,
+        10_000,
+        test.svm.now(),
+    )
+    .unwrap();
+    test.send(test.swap_ix(false, 10_000, 0)).unwrap();
+
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a + quote.output, holder_b - 10_000)
+    );
+    assert_eq!(test.pool().protocol_fees_b, quote.protocol_fee);
+}
+
+#[test]
+fn swap_honors_minimum_output() {
+    let mut test = TestPool::new();
+    let balances = test.holder_balances();
+    assert_error(
+        test.send(test.swap_ix(true, 10_000, 2_370)),
+        ErrorType::OutputTooSmall,
+    );
+    // the input had already been taken, it's returned with the failed transaction
+    assert_eq!(test.holder_balances(), balances);
+    assert_eq!(test.vault_balances(), (DEPOSIT_A, DEPOSIT_B));
+}
+
+#[test]
+fn swap_input_is_capped_at_the_balance() {
+    let mut test = TestPool::new();
+    test.set_holder_balances(10_000, 0);
+    test.send(test.swap_ix(true, u64::MAX, 0)).unwrap();
+    assert_eq!(test.holder_balances(), (0, 2_369));
+}
+
+#[test]
+fn swap_fails_when_the_invariant_breaks() {
+    let mut test = TestPool::new();
+    // set_fee caps the protocol cut at the whole swap fee, past that it comes
+    // out of the reserves and the pool loses value
+    test.update_amm(|amm| amm.protocol_fee = 30_000);
+    assert_error(
+        test.send(test.swap_ix(true, 10_000, 0)),
+        ErrorType::InvariantViolated,
+    );
+}
+
+#[test]
+fn swap_needs_the_trader_signature() {
+    let mut test = TestPool::new();
+    let mut instruction = test.swap_ix(true, 1000, 0);
+    let holder = test.holder;
+    for meta in &mut instruction.accounts {
+        if meta.pubkey == holder {
+            meta.is_signer = false;
+        }
+    }
+    assert_anchor_error(test.send(instruction), ErrorCode::AccountNotSigner);
+}
+
+#[test]
+fn swap_rejects_expired_deadline() {
+    let mut test = TestPool::new();
+    let now = test.svm.now();
+    let swap = |deadline| {
+        with_data(
+            test.swap_ix(true, 1000, 0),
+            amm::instruction::SwapTokensWithDeadline {
+                swap_a: true,
+                input_amount: 1000,
+                min_output_amount: 0,
+                deadline,
+            },
+        )
+    };
+    let (expired, current) = (swap(now - 1), swap(now));
+
+    assert_error(test.send(expired), ErrorType::DeadlineExceeded);
+    test.send(current).unwrap();
+}
+
+#[test]
+fn swap_rejects_locked_pool() {
+    let mut test = TestPool::new();
+    test.update_pool(|pool| pool.locked = true);
+
+    assert_error(
+        test.send(test.swap_ix(true, 1000, 0)),
+        ErrorType::PoolLocked,
+    );
+    assert_error(
+        test.send(exact_output_ix(&test, true, 1000, u64::MAX, None)),
+        ErrorType::PoolLocked,
+    );
+}
+
+#[test]
+fn exact_output_charges_the_quoted_input() {
+    let mut test = TestPool::new();
+    let quote = quote_swap_exact_output(
+        &test.amm(),
+        &test.pool(),
+        DEPOSIT_A,
+        DEPOSIT_B,
+        true,
+        10_000,
+        test.svm.now(),
+    )
+    .unwrap();
+
+    assert_error(
+        test.send(exact_output_ix(&test, true, 10_000, quote.input - 1, None)),
+        ErrorType::InputTooLarge,
+    );
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(exact_output_ix(&test, true, 10_000, quote.input, None))
+        .unwrap();
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a - quote.input, holder_b + 10_000)
+    );
+    assert_eq!(test.pool().protocol_fees_a, quote.protocol_fee);
+}
+
+#[test]
+fn exact_output_rejects_expired_deadline() {
+    let mut test = TestPool::new();
+    let now = test.svm.now();
+    assert_error(
+        test.send(exact_output_ix(&test, true, 1000, u64::MAX, Some(now - 1))),
+        ErrorType::DeadlineExceeded,
+    );
+}
+
+#[test]
+fn exact_output_is_bounded_by_the_reserves() {
+    let mut test = TestPool::new();
+    assert_error(
+        test.send(exact_output_ix(&test, true, 0, u64::MAX, None)),
+        ErrorType::InsufficientReserves,
+    );
+    assert_error(
+        test.send(exact_output_ix(&test, true, DEPOSIT_B, u64::MAX, None)),
+        ErrorType::InsufficientReserves,
+    );
+    assert_error(
+        test.send(exact_output_ix(&test, false, DEPOSIT_A, u64::MAX, None)),
+        ErrorType::InsufficientReserves,
+    );
+}
+
+#[test]
+fn protocol_fees_are_not_reserves() {
+    let mut test = TestPool::new();
+    test.update_pool(|pool| pool.protocol_fees_b = DEPOSIT_B / 2);
+
+    assert_error(
+        test.send(exact_output_ix(&test, true, DEPOSIT_B / 2, u64::MAX, None)),
+        ErrorType::InsufficientReserves,
+    );
+    test.send(exact_output_ix(&test, true, DEPOSIT_B / 4, u64::MAX, None))
+        .unwrap();
+    assert_eq!(test.vault_balances().1, DEPOSIT_B * 3 / 4);
+}
+
+#[test]
+fn swaps_handle_large_reserves() {
+    let mut test = TestPool::new();
+    // the product of the reserves is far past a u64
+    test.set_vaults(u64::MAX / 2, u64::MAX / 2);
+    test.set_holder_balances(u64::MAX / 4, 0);
+    let amount = 1_000_000_000_000_000_000;
+
+    test.send(test.swap_ix(true, amount, 0)).unwrap();
+    test.send(exact_output_ix(&test, true, amount, u64::MAX, None))
+        .unwrap();
+    // everything that left vault b went to the holder
+    assert_eq!(
+        test.holder_balances().1,
+        u64::MAX / 2 - test.vault_balances().1
+    );
+}
+
+#[test]
+fn exact_output_fails_when_the_math_overflows() {
+    let mut test = TestPool::new();
+    // almost all of b costs more than a u64 of a
+    test.set_vaults(u64::MAX, DEPOSIT_B);
+
+    assert_error(
+        test.send(exact_output_ix(&test, true, DEPOSIT_B - 1, u64::MAX, None)),
+        ErrorType::MathOverflow,
+    );
+}
@@ -0,0 +1,244 @@
+use amm::{errors::ErrorType, state::CurveType};
+use amm_client::{
+    instructions,
+    quote::{quote_swap, quote_swap_exact_output},
+    PoolKeys,
+};
+use anchor_lang::{
+    error::ErrorCode,
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, program_option::COption},
+};
+use anchor_spl::{
+    token::ID as TOKEN_PROGRAM_ID,
+    token_2022::{
+        spl_token_2022::{
+            extension::{
+                permanent_delegate::PermanentDelegate,
+                transfer_fee::{TransferFee, TransferFeeConfig},
+                BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+            },
+            state::Mint,
+        },
+        ID as TOKEN_2022_PROGRAM_ID,
+    },
+};
+
+use crate::{
+    fixtures::{
+        assert_anchor_error, assert_error, mint_account, ordered_keys, with_data, TestPool,
+        DECIMALS, DEFAULT_SUPPLY, DEPOSIT_A, DEPOSIT_B,
+    },
+    svm::Account,
+};
+
+// token-2022 mint charging `basis_points` of every transfer, up to `maximum_fee`
+fn fee_mint(authority: &Pubkey, basis_points: u16, maximum_fee: u64) -> Account {
+    let len = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+        .unwrap();
+    let mut data = vec![0; len];
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+
+    let fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: maximum_fee.into(),
+        transfer_fee_basis_points: basis_points.into(),
+    };
+    let config = mint.init_extension::<TransferFeeConfig>(true).unwrap();
+    config.older_transfer_fee = fee;
+    config.newer_transfer_fee = fee;
+
+    mint.base = Mint {
+        mint_authority: COption::Some(*authority),
+        supply: DEFAULT_SUPPLY,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    mint.pack_base();
+    mint.init_account_type().unwrap();
+
+    Account::new(data, TOKEN_2022_PROGRAM_ID)
+}
+
+// token-2022 mint whose tokens `delegate` can move out of any account
+fn delegated_mint(authority: &Pubkey, delegate: &Pubkey) -> Account {
+    let len = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::PermanentDelegate])
+        .unwrap();
+    let mut data = vec![0; len];
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    mint.init_extension::<PermanentDelegate>(true)
+        .unwrap()
+        .delegate = Some(*delegate).try_into().unwrap();
+
+    mint.base = Mint {
+        mint_authority: COption::Some(*authority),
+        supply: DEFAULT_SUPPLY,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    mint.pack_base();
+    mint.init_account_type().unwrap();
+
+    Account::new(data, TOKEN_2022_PROGRAM_ID)
+}
+
+fn exact_output_swap(test: &TestPool, output_amount: u64, max_input_amount: u64) -> Instruction {
+    with_data(
+        test.swap_ix(true, 0, 0),
+        amm::instruction::SwapTokensForExactTokens {
+            swap_a: true,
+            output_amount,
+            max_input_amount,
+            deadline: None,
+        },
+    )
+}
+
+#[test]
+fn swaps_work_with_token_2022_mints() {
+    let mut test = TestPool::setup(CurveType::ConstantProduct, 0, |authority| {
+        fee_mint(authority, 0, 0)
+    });
+    assert_eq!(test.keys.token_program, TOKEN_2022_PROGRAM_ID);
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(test.swap_ix(true, 1000, 0)).unwrap();
+    test.send(exact_output_swap(&test, 1000, u64::MAX)).unwrap();
+
+    let (vault_a, vault_b) = test.vault_balances();
+    assert_eq!(
+        test.holder_balances(),
+        (
+            holder_a - (vault_a - DEPOSIT_A),
+            holder_b + (DEPOSIT_B - vault_b)
+        )
+    );
+}
+
+#[test]
+fn swaps_work_with_mixed_token_programs() {
+    let mut test = TestPool::with_mints(
+        CurveType::ConstantProduct,
+        0,
+        |authority| mint_account(authority, DEFAULT_SUPPLY, &TOKEN_PROGRAM_ID),
+        |authority| fee_mint(authority, 0, 0),
+    );
+    assert_eq!(test.keys.token_program_a, TOKEN_PROGRAM_ID);
+    assert_eq!(test.keys.token_program_b, TOKEN_2022_PROGRAM_ID);
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(test.swap_ix(true, 1000, 0)).unwrap();
+    test.send(test.swap_ix(false, 1000, 0)).unwrap();
+    test.send(test.deposit_ix(DEPOSIT_A, DEPOSIT_B)).unwrap();
+    let (vault_a, vault_b) = test.vault_balances();
+    assert_eq!(
+        (holder_a + DEPOSIT_A, holder_b + DEPOSIT_B),
+        (
+            test.holder_balances().0 + vault_a,
+            test.holder_balances().1 + vault_b
+        )
+    );
+
+    // each vault has to belong to its own mint's token program
+    let mut swap = test.swap_ix(true, 1000, 0);
+    swap.accounts
+        .iter_mut()
+        .filter(|meta| meta.pubkey == TOKEN_2022_PROGRAM_ID)
+        .for_each(|meta| meta.pubkey = TOKEN_PROGRAM_ID);
+    assert_anchor_error(
+        test.send(swap),
+        ErrorCode::ConstraintAssociatedTokenTokenProgram,
+    );
+}
+
+#[test]
+fn exact_output_pays_the_transfer_fee_on_top() {
+    // 1%, capped at 10 tokens, so swaps of a few thousand pay exactly the cap
+    let mut test = TestPool::setup(CurveType::ConstantProduct, 0, |authority| {
+        fee_mint(authority, 100, 10)
+    });
+    let output = 1000;
+    let quote = quote_swap_exact_output(
+        &test.amm(),
+        &test.pool(),
+        DEPOSIT_A,
+        DEPOSIT_B,
+        true,
+        output,
+        test.svm.now(),
+    )
+    .unwrap();
+
+    assert_error(
+        test.send(exact_output_swap(&test, output, quote.input + 9)),
+        ErrorType::InputTooLarge,
+    );
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(exact_output_swap(&test, output, quote.input + 10))
+        .unwrap();
+
+    // the pool gets the quoted input and pays out the output, the fee on
+    // b comes off what the holder receives
+    assert_eq!(
+        test.vault_balances(),
+        (DEPOSIT_A + quote.input, DEPOSIT_B - output)
+    );
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a - quote.input - 10, holder_b + output - 10)
+    );
+}
+
+#[test]
+fn exact_input_is_priced_on_what_arrives() {
+    let mut test = TestPool::setup(CurveType::ConstantProduct, 0, |authority| {
+        fee_mint(authority, 100, 10)
+    });
+    let quote = quote_swap(
+        &test.amm(),
+        &test.pool(),
+        DEPOSIT_A,
+        DEPOSIT_B,
+        true,
+        990,
+        test.svm.now(),
+    )
+    .unwrap();
+    test.send(test.swap_ix(true, 1000, 0)).unwrap();
+    assert_eq!(
+        test.vault_balances(),
+        (DEPOSIT_A + 990, DEPOSIT_B - quote.output)
+    );
+}
+
+#[test]
+fn pools_reject_unsupported_mint_extensions() {
+    let mut test = TestPool::new();
+    let (admin, amm) = (test.admin, test.keys.amm);
+    let (mint_a, mint_b) = ordered_keys();
+    test.svm
+        .set_account(mint_a, delegated_mint(&admin, &Pubkey::new_unique()));
+    test.svm.set_account(mint_b, fee_mint(&admin, 0, 0));
+
+    // a permanent delegate could move the vault balances at will
+    let keys = PoolKeys::new(amm, mint_a, mint_b, TOKEN_2022_PROGRAM_ID);
+    let create = instructions::create_pool(&keys, &admin, CurveType::ConstantProduct, 0);
+    assert_error(test.send(create), ErrorType::UnsupportedMintExtension);
+
+    // transfer fees are fine
+    test.svm.set_account(mint_a, fee_mint(&admin, 0, 0));
+    let create = instructions::create_pool(&keys, &admin, CurveType::ConstantProduct, 0);
+    test.send(create).unwrap();
+}
+
+#[test]
+fn exact_output_rejects_unpayable_transfer_fees() {
+    // a 100% fee without a cap can't be grossed up
+    let mut test = TestPool::setup(CurveType::ConstantProduct, 0, |authority| {
+        fee_mint(authority, 10_000, u64::MAX)
+    });
+    assert_error(
+        test.send(exact_output_swap(&test, 1000, u64::MAX)),
+        ErrorType::InvalidTransferFee,
+    );
+}
@@ -0,0 +1,80 @@
+use amm::{constants::OBSERVATION_PERIOD, errors::ErrorType, instructions::TwapPrices};
+use anchor_lang::solana_program::instruction::Instruction;
+
+use crate::fixtures::{assert_error, ix, TestPool, DEPOSIT_A, DEPOSIT_B};
+
+fn observe(test: &TestPool, seconds_ago: u32) -> Instruction {
+    let keys = &test.keys;
+    ix(
+        amm::accounts::Observe { pool: keys.pool },
+        amm::instruction::Observe { seconds_ago },
+    )
+}
+
+#[test]
+fn observe_needs_history() {
+    let mut test = TestPool::new();
+    // nothing has been observed over any window yet
+    assert_error(test.send(observe(&test, 0)), ErrorType::ObservationTooOld);
+
+    test.svm.warp(OBSERVATION_PERIOD);
+    test.send(observe(&test, 0)).unwrap();
+    test.send(observe(&test, OBSERVATION_PERIOD as u32))
+        .unwrap();
+    assert_error(
+        test.send(observe(&test, OBSERVATION_PERIOD as u32 + 1)),
+        ErrorType::ObservationTooOld,
+    );
+}
+
+#[test]
+fn observe_returns_the_average_prices() {
+    let mut test = TestPool::new();
+    test.svm.warp(OBSERVATION_PERIOD);
+    let prices: TwapPrices = test.svm.view(observe(&test, 0)).unwrap();
+    // b is 4 times scarcer than a
+    assert_eq!(prices.price_a, (1u128 << 64) / 4);
+    assert_eq!(prices.price_b, 4u128 << 64);
+
+    // a swap moves the price from here on, the window only sees part of it
+    test.send(test.swap_ix(true, DEPOSIT_A, 0)).unwrap();
+    test.svm.warp(OBSERVATION_PERIOD);
+    let pool = test.pool();
+    let spot_a = ((pool.reserve_b as u128) << 64) / pool.reserve_a as u128;
+    let window = OBSERVATION_PERIOD as u32 * 2;
+    let prices: TwapPrices = test.svm.view(observe(&test, window)).unwrap();
+    assert!(prices.price_a > spot_a && prices.price_a < (1u128 << 64) / 4);
+}
+
+#[test]
+fn observe_changes_nothing() {
+    let mut test = TestPool::new();
+    test.svm.warp(OBSERVATION_PERIOD);
+    let pool = test.pool();
+    test.send(observe(&test, 0)).unwrap();
+
+    let after = test.pool();
+    assert_eq!(after.last_update_ts, pool.last_update_ts);
+    assert_eq!(after.price_a_cumulative, pool.price_a_cumulative);
+    assert_eq!(after.observation_index, pool.observation_index);
+}
+
+#[test]
+fn observe_ignores_vault_balances() {
+    let mut test = TestPool::new();
+    test.svm.warp(OBSERVATION_PERIOD);
+    let now = test.svm.now();
+    let before = test.pool().observe(now, 0).unwrap();
+    // b is 4 times scarcer than a
+    assert_eq!(before.0, (1u128 << 64) / 4);
+    assert_eq!(before.1, 4u128 << 64);
+
+    // a donation moves the vaults but not the price of the idle period
+    test.set_vaults(DEPOSIT_A, DEPOSIT_B * 100);
+    assert_eq!(test.pool().observe(now, 0).unwrap(), before);
+
+    // neither does a flash loan that is still out
+    test.update_pool(|pool| pool.locked = true);
+    let prices: TwapPrices = test.svm.view(observe(&test, 0)).unwrap();
+    assert_eq!((prices.price_a, prices.price_b), before);
+}
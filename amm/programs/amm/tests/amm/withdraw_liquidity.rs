@@ -0,0 +1,170 @@
+use amm::{constants::MINIMUM_LIQUIDITY, errors::ErrorType};
+use amm_client::quote::quote_withdraw;
+use anchor_lang::{error::ErrorCode, solana_program::instruction::Instruction};
+use anchor_spl::token_interface::Mint;
+
+use crate::fixtures::{
+    assert_anchor_error, assert_error, with_data, TestPool, DEPOSIT_A, DEPOSIT_B, LIQUIDITY,
+};
+
+fn withdraw_with_deadline(
+    test: &TestPool,
+    amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+    deadline: i64,
+) -> Instruction {
+    with_data(
+        test.withdraw_ix(amount),
+        amm::instruction::WithdrawLiquidityWithDeadline {
+            amount,
+            min_amount_a,
+            min_amount_b,
+            deadline,
+        },
+    )
+}
+
+#[test]
+fn withdraw_burns_and_pays_out_a_share() {
+    let mut test = TestPool::new();
+    let (holder_a, holder_b) = test.holder_balances();
+    // a quarter of the supply
+    let amount = (LIQUIDITY + MINIMUM_LIQUIDITY) / 4;
+    test.send(test.withdraw_ix(amount)).unwrap();
+
+    assert_eq!(test.lp_balance(), LIQUIDITY - amount);
+    assert_eq!(
+        test.svm.get::<Mint>(&test.keys.mint_liquidity).supply,
+        LIQUIDITY + MINIMUM_LIQUIDITY - amount
+    );
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a + DEPOSIT_A / 4, holder_b + DEPOSIT_B / 4)
+    );
+    assert_eq!(
+        test.vault_balances(),
+        (DEPOSIT_A * 3 / 4, DEPOSIT_B * 3 / 4)
+    );
+    let pool = test.pool();
+    assert_eq!(
+        (pool.reserve_a, pool.reserve_b),
+        (DEPOSIT_A * 3 / 4, DEPOSIT_B * 3 / 4)
+    );
+}
+
+#[test]
+fn withdraw_is_limited_to_the_lp_balance() {
+    let mut test = TestPool::new();
+    assert_error(
+        test.send(test.withdraw_ix(LIQUIDITY + 1)),
+        ErrorType::InsufficientLiquidity,
+    );
+}
+
+#[test]
+fn withdraw_needs_the_locked_liquidity_account() {
+    let mut test = TestPool::new();
+    test.remove_locked_liquidity();
+    assert_anchor_error(
+        test.send(test.withdraw_ix(LIQUIDITY)),
+        ErrorCode::AccountNotInitialized,
+    );
+}
+
+#[test]
+fn withdrawing_everything_leaves_the_locked_share() {
+    let mut test = TestPool::new();
+    let deadline = test.svm.now();
+    // LIQUIDITY out of LIQUIDITY + MINIMUM_LIQUIDITY lp tokens
+    let (amount_a, amount_b) = (3_999_800, 999_950);
+
+    assert_error(
+        test.send(withdraw_with_deadline(
+            &test,
+            LIQUIDITY,
+            amount_a + 1,
+            0,
+            deadline,
+        )),
+        ErrorType::OutputTooSmall,
+    );
+    assert_error(
+        test.send(withdraw_with_deadline(
+            &test,
+            LIQUIDITY,
+            0,
+            amount_b + 1,
+            deadline,
+        )),
+        ErrorType::OutputTooSmall,
+    );
+    test.send(withdraw_with_deadline(
+        &test, LIQUIDITY, amount_a, amount_b, deadline,
+    ))
+    .unwrap();
+
+    // the locked share keeps the pool priced
+    assert_eq!(test.lp_balance(), 0);
+    assert_eq!(
+        test.vault_balances(),
+        (DEPOSIT_A - amount_a, DEPOSIT_B - amount_b)
+    );
+    assert_eq!(
+        test.svm.balance(&test.keys.locked_liquidity),
+        MINIMUM_LIQUIDITY
+    );
+}
+
+#[test]
+fn withdraw_rejects_dust() {
+    let mut test = TestPool::new();
+    // one lp token is worth 2 of a and half of b, which rounds to nothing
+    assert_error(test.send(test.withdraw_ix(1)), ErrorType::ZeroWithdrawal);
+    test.send(test.withdraw_ix(2)).unwrap();
+    assert_eq!(test.vault_balances(), (DEPOSIT_A - 4, DEPOSIT_B - 1));
+}
+
+#[test]
+fn withdraw_honors_minimum_amounts() {
+    let mut test = TestPool::new();
+    let amount = LIQUIDITY / 2;
+    let supply = LIQUIDITY + MINIMUM_LIQUIDITY;
+    let quote = quote_withdraw(&test.pool(), DEPOSIT_A, DEPOSIT_B, supply, amount).unwrap();
+
+    assert_error(
+        test.send(test.withdraw_min_ix(amount, quote.amount_a + 1, quote.amount_b)),
+        ErrorType::OutputTooSmall,
+    );
+    assert_error(
+        test.send(test.withdraw_min_ix(amount, quote.amount_a, quote.amount_b + 1)),
+        ErrorType::OutputTooSmall,
+    );
+    let (holder_a, holder_b) = test.holder_balances();
+    test.send(test.withdraw_min_ix(amount, quote.amount_a, quote.amount_b))
+        .unwrap();
+    assert_eq!(
+        test.holder_balances(),
+        (holder_a + quote.amount_a, holder_b + quote.amount_b)
+    );
+}
+
+#[test]
+fn withdraw_rejects_expired_deadline() {
+    let mut test = TestPool::new();
+    let deadline = test.svm.now() - 1;
+    assert_error(
+        test.send(withdraw_with_deadline(&test, LIQUIDITY, 0, 0, deadline)),
+        ErrorType::DeadlineExceeded,
+    );
+}
+
+#[test]
+fn withdraw_rejects_locked_pool() {
+    let mut test = TestPool::new();
+    test.update_pool(|pool| pool.locked = true);
+    assert_error(
+        test.send(test.withdraw_ix(LIQUIDITY)),
+        ErrorType::PoolLocked,
+    );
+}
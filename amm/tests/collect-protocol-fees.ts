@@ -0,0 +1,198 @@
+import type { Program } from "@coral-xyz/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import { BN } from "bn.js";
+import { expect } from "chai";
+import type { SwapExample } from "../target/types/swap_example";
+import {
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import {
+  createValues,
+  expectRevert,
+  mintingTokens,
+  type TestValues,
+} from "./utils";
+
+describe("Collect protocol fees", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SwapExample as Program<SwapExample>;
+
+  let values: TestValues;
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods
+      .createAmm(values.id, values.fee, values.protocolFee)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool({ constantProduct: {} }, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  it("Collects the admin share of swap fees", async () => {
+    const input = new BN(10 ** 6);
+    await program.methods
+      .swapTokens(true, input, new BN(100))
+      .accounts({
+        amm: values.ammKey.toBase58(),
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        trader: values.admin.publicKey,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const feeAmount = input.muln(values.fee).divn(10000);
+    const protocolFeeAmount = feeAmount.muln(values.protocolFee).divn(10000);
+
+    const poolBefore = await program.account.pool.fetch(values.poolKey);
+    expect(poolBefore.protocolFeesA.toString()).to.equal(
+      protocolFeeAmount.toString()
+    );
+    expect(poolBefore.protocolFeesB.toString()).to.equal("0");
+
+    const holderABefore = await connection.getTokenAccountBalance(
+      values.holderAccountA
+    );
+
+    let event: any;
+    const listener = program.addEventListener(
+      "protocolFeesCollected",
+      (e) => {
+        event = e;
+      }
+    );
+
+    await program.methods
+      .collectProtocolFees()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        adminAccountA: values.holderAccountA,
+        adminAccountB: values.holderAccountB,
+        admin: values.admin.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([values.admin])
+      .rpc();
+
+    const holderAAfter = await connection.getTokenAccountBalance(
+      values.holderAccountA
+    );
+    expect(
+      new BN(holderAAfter.value.amount)
+        .sub(new BN(holderABefore.value.amount))
+        .toString()
+    ).to.equal(protocolFeeAmount.toString());
+
+    const poolAfter = await program.account.pool.fetch(values.poolKey);
+    expect(poolAfter.protocolFeesA.toString()).to.equal("0");
+    expect(poolAfter.protocolFeesB.toString()).to.equal("0");
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    expect(event.pool.toString()).to.equal(values.poolKey.toString());
+    expect(event.amountA.toString()).to.equal(protocolFeeAmount.toString());
+    expect(event.amountB.toString()).to.equal("0");
+  });
+
+  it("Only the admin can collect", async () => {
+    const intruder = Keypair.generate();
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(intruder.publicKey, 10 ** 9)
+    );
+
+    await expectRevert(
+      program.methods
+        .collectProtocolFees()
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          adminAccountA: getAssociatedTokenAddressSync(
+            values.mintAKeypair.publicKey,
+            intruder.publicKey
+          ),
+          adminAccountB: getAssociatedTokenAddressSync(
+            values.mintBKeypair.publicKey,
+            intruder.publicKey
+          ),
+          admin: intruder.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([intruder])
+        .rpc()
+    );
+  });
+});
@@ -0,0 +1,305 @@
+import type { Program } from "@coral-xyz/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import {
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+import type { SwapExample } from "../target/types/swap_example";
+import {
+  createValues,
+  expectRevert,
+  mintingTokens,
+  type TestValues,
+} from "./utils";
+
+describe("Concentrated liquidity", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SwapExample as Program<SwapExample>;
+
+  const tickSpacing = 10;
+  // must match TICK_ARRAY_SIZE on chain
+  const ticksInArray = tickSpacing * 16;
+  const tickLower = -100;
+  const tickUpper = 100;
+  const liquidity = new BN(10 ** 9);
+  // sqrt price of 1 in Q64.64
+  const sqrtPriceOne = new BN(1).shln(64);
+
+  let values: TestValues;
+  let pool: PublicKey;
+  let poolAuthority: PublicKey;
+  let poolAccountA: PublicKey;
+  let poolAccountB: PublicKey;
+  let position: PublicKey;
+
+  const tickArrayKey = (startTickIndex: number) => {
+    const start = Buffer.alloc(4);
+    start.writeInt32LE(startTickIndex);
+    return PublicKey.findProgramAddressSync(
+      [pool.toBuffer(), Buffer.from("tick_array"), start],
+      program.programId
+    )[0];
+  };
+
+  const positionKey = (lower: number, upper: number) => {
+    const lowerBuffer = Buffer.alloc(4);
+    lowerBuffer.writeInt32LE(lower);
+    const upperBuffer = Buffer.alloc(4);
+    upperBuffer.writeInt32LE(upper);
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("position"),
+        pool.toBuffer(),
+        values.admin.publicKey.toBuffer(),
+        lowerBuffer,
+        upperBuffer,
+      ],
+      program.programId
+    )[0];
+  };
+
+  // Q64.64 sqrt price of a tick, close enough for a price limit
+  const sqrtPriceAtTick = (tick: number) =>
+    new BN(
+      BigInt(Math.floor(Math.sqrt(1.0001 ** tick) * 2 ** 32)).toString()
+    ).shln(32);
+
+  const modifyLiquidityAccounts = () => ({
+    amm: values.ammKey,
+    pool,
+    poolAuthority,
+    position,
+    tickArrayLower: tickArrayKey(-ticksInArray),
+    tickArrayUpper: tickArrayKey(0),
+    mintA: values.mintAKeypair.publicKey,
+    mintB: values.mintBKeypair.publicKey,
+    poolAccountA,
+    poolAccountB,
+    owner: values.admin.publicKey,
+    ownerAccountA: values.holderAccountA,
+    ownerAccountB: values.holderAccountB,
+    payer: values.admin.publicKey,
+    tokenProgramA: TOKEN_PROGRAM_ID,
+    tokenProgramB: TOKEN_PROGRAM_ID,
+  });
+
+  const swap = (input: BN, sqrtPriceLimit: BN | null) =>
+    program.methods
+      .swapConcentrated(true, input, new BN(1), sqrtPriceLimit, null)
+      .accounts({
+        amm: values.ammKey,
+        pool,
+        poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA,
+        poolAccountB,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        trader: values.admin.publicKey,
+        payer: values.admin.publicKey,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      } as any)
+      .remainingAccounts(
+        [tickArrayKey(0), tickArrayKey(-ticksInArray)].map((pubkey) => ({
+          pubkey,
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .signers([values.admin])
+      .rpc();
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods
+      .createAmm(values.id, values.fee, values.protocolFee)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    pool = PublicKey.findProgramAddressSync(
+      [
+        values.ammKey.toBuffer(),
+        values.mintAKeypair.publicKey.toBuffer(),
+        values.mintBKeypair.publicKey.toBuffer(),
+        Buffer.from("concentrated"),
+      ],
+      program.programId
+    )[0];
+    poolAuthority = PublicKey.findProgramAddressSync(
+      [pool.toBuffer(), Buffer.from("authority")],
+      program.programId
+    )[0];
+    poolAccountA = getAssociatedTokenAddressSync(
+      values.mintAKeypair.publicKey,
+      poolAuthority,
+      true
+    );
+    poolAccountB = getAssociatedTokenAddressSync(
+      values.mintBKeypair.publicKey,
+      poolAuthority,
+      true
+    );
+    position = positionKey(tickLower, tickUpper);
+
+    await program.methods
+      .createConcentratedPool(tickSpacing, sqrtPriceOne)
+      .accounts({
+        amm: values.ammKey,
+        pool,
+        poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA,
+        poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      } as any)
+      .rpc();
+
+    for (const startTickIndex of [-ticksInArray, 0]) {
+      await program.methods
+        .initializeTickArray(startTickIndex)
+        .accounts({ pool, tickArray: tickArrayKey(startTickIndex) } as any)
+        .rpc();
+    }
+
+    await program.methods
+      .openPosition(tickLower, tickUpper)
+      .accounts({
+        pool,
+        position,
+        owner: values.admin.publicKey,
+        payer: values.admin.publicKey,
+      } as any)
+      .signers([values.admin])
+      .rpc();
+
+    await program.methods
+      .increaseLiquidity(liquidity, new BN(10 ** 7), new BN(10 ** 7), null)
+      .accounts(modifyLiquidityAccounts() as any)
+      .signers([values.admin])
+      .rpc();
+  });
+
+  it("Deposits both tokens for a range around the price", async () => {
+    const positionAccount = await program.account.position.fetch(position);
+    expect(positionAccount.liquidity.toString()).to.equal(liquidity.toString());
+
+    const poolAccount = await program.account.concentratedPool.fetch(pool);
+    expect(poolAccount.liquidity.toString()).to.equal(liquidity.toString());
+    expect(poolAccount.tickCurrent).to.equal(0);
+
+    // L * (1 - 1.0001^-50) of each token
+    const vaultA = await connection.getTokenAccountBalance(poolAccountA);
+    const vaultB = await connection.getTokenAccountBalance(poolAccountB);
+    expect(Number(vaultA.value.amount)).to.be.closeTo(4987273, 1);
+    expect(Number(vaultB.value.amount)).to.be.closeTo(4987273, 1);
+  });
+
+  it("Rejects invalid ranges", async () => {
+    const openPosition = (lower: number, upper: number) =>
+      program.methods
+        .openPosition(lower, upper)
+        .accounts({
+          pool,
+          position: positionKey(lower, upper),
+          owner: values.admin.publicKey,
+          payer: values.admin.publicKey,
+        } as any)
+        .signers([values.admin])
+        .rpc();
+
+    await expectRevert(openPosition(100, -100));
+    // not on the tick spacing
+    await expectRevert(openPosition(-105, 100));
+  });
+
+  it("Swaps within the range and pays fees to the position", async () => {
+    const input = new BN(10 ** 5);
+    const balanceBefore = await connection.getTokenAccountBalance(
+      values.holderAccountB
+    );
+
+    await swap(input, null);
+
+    const balanceAfter = await connection.getTokenAccountBalance(
+      values.holderAccountB
+    );
+    const output =
+      Number(balanceAfter.value.amount) - Number(balanceBefore.value.amount);
+    const taxedInput = (input.toNumber() * (10000 - values.fee)) / 10000;
+    expect(output).to.be.lessThan(taxedInput);
+    expect(output).to.be.greaterThan(taxedInput * 0.99);
+
+    const poolAccount = await program.account.concentratedPool.fetch(pool);
+    expect(poolAccount.sqrtPrice.lt(sqrtPriceOne)).to.be.true;
+
+    const feesBefore = await connection.getTokenAccountBalance(
+      values.holderAccountA
+    );
+    await program.methods
+      .collectFees()
+      .accounts(modifyLiquidityAccounts() as any)
+      .signers([values.admin])
+      .rpc();
+    const feesAfter = await connection.getTokenAccountBalance(
+      values.holderAccountA
+    );
+
+    // the only position gets the whole fee, minus rounding
+    const fees =
+      Number(feesAfter.value.amount) - Number(feesBefore.value.amount);
+    expect(fees).to.be.closeTo(input.toNumber() - taxedInput, 1);
+  });
+
+  it("Crosses the lower tick of the position", async () => {
+    // stops between the two arrays' ticks, below the position
+    await swap(new BN(10 ** 7), sqrtPriceAtTick(-120));
+
+    const poolAccount = await program.account.concentratedPool.fetch(pool);
+    expect(poolAccount.tickCurrent).to.be.lessThan(tickLower);
+    expect(poolAccount.liquidity.toNumber()).to.equal(0);
+  });
+
+  it("Withdraws the liquidity", async () => {
+    await program.methods
+      .decreaseLiquidity(liquidity, new BN(1), new BN(1), null)
+      .accounts(modifyLiquidityAccounts() as any)
+      .signers([values.admin])
+      .rpc();
+
+    const positionAccount = await program.account.position.fetch(position);
+    expect(positionAccount.liquidity.toNumber()).to.equal(0);
+
+    const poolAccount = await program.account.concentratedPool.fetch(pool);
+    expect(poolAccount.liquidity.toNumber()).to.equal(0);
+
+    // rounding keeps at most a unit of each token in the pool
+    const vaultA = await connection.getTokenAccountBalance(poolAccountA);
+    expect(Number(vaultA.value.amount)).to.be.at.most(1);
+
+    await expectRevert(
+      program.methods
+        .decreaseLiquidity(new BN(1), new BN(0), new BN(0), null)
+        .accounts(modifyLiquidityAccounts() as any)
+        .signers([values.admin])
+        .rpc()
+    );
+  });
+});
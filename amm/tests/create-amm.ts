@@ -18,7 +18,7 @@ describe("Create AMM", () => {
 
   it("Creation", async () => {
     await program.methods
-      .createAmm(values.id, values.fee)
+      .createAmm(values.id, values.fee, values.protocolFee)
       .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
       .rpc();
 
@@ -28,6 +28,9 @@ describe("Create AMM", () => {
       values.admin.publicKey.toString()
     );
     expect(ammAccount.fee.toString()).to.equal(values.fee.toString());
+    expect(ammAccount.protocolFee.toString()).to.equal(
+      values.protocolFee.toString()
+    );
   });
 
   it("Invalid fee", async () => {
@@ -35,7 +38,18 @@ describe("Create AMM", () => {
 
     await expectRevert(
       program.methods
-        .createAmm(values.id, values.fee)
+        .createAmm(values.id, values.fee, values.protocolFee)
+        .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+        .rpc()
+    );
+  });
+
+  it("Invalid protocol fee", async () => {
+    values.protocolFee = 10001;
+
+    await expectRevert(
+      program.methods
+        .createAmm(values.id, values.fee, values.protocolFee)
         .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
         .rpc()
     );
@@ -9,6 +9,8 @@ import {
   type TestValues,
 } from "./utils";
 import { PublicKey } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, getAccount, getMint } from "@solana/spl-token";
+import { BN } from "bn.js";
 
 describe("Create a pool", () => {
   const provider = anchor.AnchorProvider.local();
@@ -23,7 +25,7 @@ describe("Create a pool", () => {
     values = createValues();
 
     await program.methods
-      .createAmm(values.id, values.fee)
+      .createAmm(values.id, values.fee, values.protocolFee)
       .accounts({
         amm: values.ammKey,
         admin: values.admin.publicKey,
@@ -40,20 +42,73 @@ describe("Create a pool", () => {
 
   it("Creation", async () => {
     program.methods
-      .createPool()
+      .createPool({ constantProduct: {} }, new BN(0))
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
         mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
   });
 
+  it("Lp mint and locked liquidity account", async () => {
+    await program.methods
+      .createPool({ constantProduct: {} }, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // both test mints have 6 decimals
+    const mintLiquidity = await getMint(connection, values.mintLiquidity);
+    expect(mintLiquidity.decimals).to.equal(6);
+
+    const locked = await getAccount(connection, values.lockedLiquidity);
+    expect(locked.owner.toBase58()).to.equal(values.poolKey.toBase58());
+    expect(locked.amount.toString()).to.equal("0");
+
+    // a pool is only migrated once
+    await expectRevert(
+      program.methods
+        .lockMinimumLiquidity()
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc()
+    );
+  });
+
   it("invalid mints", async () => {
     values = createValues({
       mintBKeypair: values.mintAKeypair,
@@ -78,16 +133,50 @@ describe("Create a pool", () => {
 
     await expectRevert(
       program.methods
-        .createPool()
+        .createPool({ constantProduct: {} }, new BN(0))
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc()
+    );
+  });
+
+  it("reversed mints", async () => {
+    // same pair, mint_a above mint_b
+    values = createValues({
+      id: values.id,
+      admin: values.admin,
+      mintAKeypair: values.mintBKeypair,
+      mintBKeypair: values.mintAKeypair,
+    });
+
+    await expectRevert(
+      program.methods
+        .createPool({ constantProduct: {} }, new BN(0))
         .accounts({
           amm: values.ammKey,
           pool: values.poolKey,
           poolAuthority: values.poolAuthority,
           mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
           mintA: values.mintAKeypair.publicKey,
           mintB: values.mintBKeypair.publicKey,
           poolAccountA: values.poolAccountA,
           poolAccountB: values.poolAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc()
     );
@@ -8,6 +8,7 @@ import {
   mintingTokens,
   type TestValues,
 } from "./utils";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { BN } from "bn.js";
 
 describe("Deposit liquidity", () => {
@@ -23,7 +24,7 @@ describe("Deposit liquidity", () => {
     values = createValues();
 
     await program.methods
-      .createAmm(values.id, values.fee)
+      .createAmm(values.id, values.fee, values.protocolFee)
       .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
       .rpc();
 
@@ -35,28 +36,34 @@ describe("Deposit liquidity", () => {
     });
 
     await program.methods
-      .createPool()
+      .createPool({ constantProduct: {} }, new BN(0))
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
         mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
   });
 
   it("Deposit equal amounts", async () => {
     await program.methods
-      .depositLiquidity(values.depositAmountA, values.depositAmountA)
+      .depositLiquidity(values.depositAmountA, values.depositAmountA, new BN(0))
       .accounts({
+        amm: values.ammKey,
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
         depositor: values.admin.publicKey,
         mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
         poolAccountA: values.poolAccountA,
@@ -64,6 +71,9 @@ describe("Deposit liquidity", () => {
         depositorAccountLiquidity: values.liquidityAccount,
         depositorAccountA: values.holderAccountA,
         depositorAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([values.admin])
       .rpc({ skipPreflight: true });
@@ -73,6 +83,12 @@ describe("Deposit liquidity", () => {
     expect(depositTokenAccountLiquditiy.value.amount).to.equal(
       values.depositAmountA.sub(values.minimumLiquidity).toString()
     );
+    const lockedLiquidity = await connection.getTokenAccountBalance(
+      values.lockedLiquidity
+    );
+    expect(lockedLiquidity.value.amount).to.equal(
+      values.minimumLiquidity.toString()
+    );
     const depositTokenAccountA = await connection.getTokenAccountBalance(
       values.holderAccountA
     );
@@ -96,12 +112,14 @@ describe("Deposit liquidity", () => {
     );
     await expectRevert(
       program.methods
-        .depositLiquidity(new BN(0), values.depositAmountA)
+        .depositLiquidity(new BN(0), values.depositAmountA, new BN(0))
         .accounts({
+          amm: values.ammKey,
           pool: values.poolKey,
           poolAuthority: values.poolAuthority,
           depositor: values.admin.publicKey,
           mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
           mintA: values.mintAKeypair.publicKey,
           mintB: values.mintBKeypair.publicKey,
           poolAccountA: values.poolAccountA,
@@ -109,6 +127,9 @@ describe("Deposit liquidity", () => {
           depositorAccountLiquidity: values.liquidityAccount,
           depositorAccountA: values.holderAccountA,
           depositorAccountB: values.holderAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
         })
         .signers([values.admin])
         .rpc({ skipPreflight: true })
@@ -129,4 +150,37 @@ describe("Deposit liquidity", () => {
     );
     expect(liquidityAccountInfo).to.be.null;
   });
+
+  it("Deposit below the minimum liquidity", async () => {
+    const deadline = new BN(Math.floor(Date.now() / 1000) + 60);
+    await expectRevert(
+      program.methods
+        .depositLiquidityWithDeadline(
+          values.depositAmountA,
+          values.depositAmountA,
+          values.depositAmountA,
+          deadline
+        )
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          depositor: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          depositorAccountLiquidity: values.liquidityAccount,
+          depositorAccountA: values.holderAccountA,
+          depositorAccountB: values.holderAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([values.admin])
+        .rpc()
+    );
+  });
 });
@@ -0,0 +1,140 @@
+import type { Program } from "@coral-xyz/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import {
+  createTransferInstruction,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { BN } from "bn.js";
+import { expect } from "chai";
+import type { SwapExample } from "../target/types/swap_example";
+import {
+  createValues,
+  expectRevert,
+  mintingTokens,
+  type TestValues,
+} from "./utils";
+
+describe("Flash loans", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SwapExample as Program<SwapExample>;
+
+  // must match FLASH_LOAN_FEE on chain
+  const flashLoanFee = 9;
+  const amount = new BN(10 ** 6);
+  const fee = amount.muln(flashLoanFee).addn(9999).divn(10000);
+
+  let values: TestValues;
+
+  // the token program itself is the receiver, its transfer pays the loan back
+  const flashLoan = (repayment: BN, receiverProgram = TOKEN_PROGRAM_ID) => {
+    const repay = createTransferInstruction(
+      values.holderAccountA,
+      values.poolAccountA,
+      values.admin.publicKey,
+      BigInt(repayment.toString())
+    );
+
+    return program.methods
+      .flashLoan(amount, new BN(0), repay.data)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        borrowerAccountA: values.holderAccountA,
+        borrowerAccountB: values.holderAccountB,
+        borrower: values.admin.publicKey,
+        receiverProgram,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      } as any)
+      .remainingAccounts(repay.keys)
+      .signers([values.admin])
+      .rpc();
+  };
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods
+      .createAmm(values.id, values.fee, values.protocolFee)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool({ constantProduct: {} }, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  it("Lends and gets the loan back with the fee", async () => {
+    await flashLoan(amount.add(fee));
+
+    const poolAccountA = await connection.getTokenAccountBalance(
+      values.poolAccountA
+    );
+    expect(poolAccountA.value.amount).to.equal(
+      values.depositAmountA.add(fee).toString()
+    );
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.locked).to.be.false;
+    expect(pool.protocolFeesA.toString()).to.equal(
+      fee.muln(values.protocolFee).divn(10000).toString()
+    );
+  });
+
+  it("Fails when the fee isn't paid", async () => {
+    await expectRevert(flashLoan(amount));
+  });
+
+  it("Can't call back into the amm", async () => {
+    await expectRevert(flashLoan(amount.add(fee), program.programId));
+  });
+});
@@ -0,0 +1,127 @@
+import type { Program } from "@coral-xyz/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+import type { SwapExample } from "../target/types/swap_example";
+import { createValues, expectRevert, type TestValues } from "./utils";
+
+describe("AMM governance", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SwapExample as Program<SwapExample>;
+
+  let values: TestValues;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods
+      .createAmm(values.id, values.fee, values.protocolFee)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .rpc();
+
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(values.admin.publicKey, 10 ** 9)
+    );
+  });
+
+  it("Admin updates the fee", async () => {
+    await program.methods
+      .setFee(300, 2000)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey } as any)
+      .signers([values.admin])
+      .rpc();
+
+    const ammAccount = await program.account.amm.fetch(values.ammKey);
+    expect(ammAccount.fee).to.equal(300);
+    expect(ammAccount.protocolFee).to.equal(2000);
+  });
+
+  it("Invalid fee update", async () => {
+    await expectRevert(
+      program.methods
+        .setFee(10000, values.protocolFee)
+        .accounts({ amm: values.ammKey, admin: values.admin.publicKey } as any)
+        .signers([values.admin])
+        .rpc()
+    );
+  });
+
+  it("Non admin cannot update the fee", async () => {
+    const intruder = Keypair.generate();
+
+    await expectRevert(
+      program.methods
+        .setFee(0, 0)
+        .accounts({ amm: values.ammKey, admin: intruder.publicKey } as any)
+        .signers([intruder])
+        .rpc()
+    );
+  });
+
+  it("Two step admin handover", async () => {
+    const newAdmin = Keypair.generate();
+
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey } as any)
+      .signers([values.admin])
+      .rpc();
+
+    let ammAccount = await program.account.amm.fetch(values.ammKey);
+    expect(ammAccount.admin.toString()).to.equal(
+      values.admin.publicKey.toString()
+    );
+    expect(ammAccount.pendingAdmin.toString()).to.equal(
+      newAdmin.publicKey.toString()
+    );
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        amm: values.ammKey,
+        pendingAdmin: newAdmin.publicKey,
+      } as any)
+      .signers([newAdmin])
+      .rpc();
+
+    ammAccount = await program.account.amm.fetch(values.ammKey);
+    expect(ammAccount.admin.toString()).to.equal(newAdmin.publicKey.toString());
+    expect(ammAccount.pendingAdmin.toString()).to.equal(
+      anchor.web3.PublicKey.default.toString()
+    );
+
+    // old admin lost its rights
+    await expectRevert(
+      program.methods
+        .setFee(0, 0)
+        .accounts({ amm: values.ammKey, admin: values.admin.publicKey } as any)
+        .signers([values.admin])
+        .rpc()
+    );
+  });
+
+  it("Only the pending admin can accept", async () => {
+    const newAdmin = Keypair.generate();
+    const intruder = Keypair.generate();
+
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey } as any)
+      .signers([values.admin])
+      .rpc();
+
+    await expectRevert(
+      program.methods
+        .acceptAdmin()
+        .accounts({
+          amm: values.ammKey,
+          pendingAdmin: intruder.publicKey,
+        } as any)
+        .signers([intruder])
+        .rpc()
+    );
+  });
+});
@@ -0,0 +1,210 @@
+import type { Program } from "@coral-xyz/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from "bn.js";
+import { expect } from "chai";
+import type { SwapExample } from "../target/types/swap_example";
+import {
+  createValues,
+  expectRevert,
+  mintingTokens,
+  type TestValues,
+} from "./utils";
+
+describe("Pause and resume", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SwapExample as Program<SwapExample>;
+
+  let values: TestValues;
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods
+      .createAmm(values.id, values.fee, values.protocolFee)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+
+    await program.methods
+      .createPool({ constantProduct: {} }, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  });
+
+  const swap = () =>
+    program.methods
+      .swapTokens(true, new BN(10 ** 6), new BN(100))
+      .accounts({
+        amm: values.ammKey.toBase58(),
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        trader: values.admin.publicKey,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+  const withdraw = () =>
+    program.methods
+      .withdrawLiquidity(new BN(1000), new BN(0), new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositer: values.admin.publicKey,
+        payer: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolTokenAccountA: values.poolAccountA,
+        poolTokenAccountB: values.poolAccountB,
+        depositerAccountTokenLp: values.liquidityAccount,
+        depositerAccountTokenA: values.holderAccountA,
+        depositerAccountTokenB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([values.admin])
+      .rpc();
+
+  it("Paused pool rejects swaps but can allow withdrawals", async () => {
+    let paused: any;
+    let resumed: any;
+    const listeners = [
+      program.addEventListener("poolPaused", (e) => {
+        paused = e;
+      }),
+      program.addEventListener("poolResumed", (e) => {
+        resumed = e;
+      }),
+    ];
+
+    await program.methods
+      .pausePool(true)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        admin: values.admin.publicKey,
+      } as any)
+      .signers([values.admin])
+      .rpc();
+
+    const pool = await program.account.pool.fetch(values.poolKey);
+    expect(pool.paused).to.be.true;
+    expect(pool.withdrawalsPaused).to.be.false;
+
+    await expectRevert(swap());
+    await withdraw();
+
+    await program.methods
+      .resumePool()
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        admin: values.admin.publicKey,
+      } as any)
+      .signers([values.admin])
+      .rpc();
+
+    await swap();
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    for (const listener of listeners) {
+      await program.removeEventListener(listener);
+    }
+
+    expect(paused.pool.toString()).to.equal(values.poolKey.toString());
+    expect(paused.allowWithdrawals).to.be.true;
+    expect(resumed.pool.toString()).to.equal(values.poolKey.toString());
+  });
+
+  it("AMM circuit breaker freezes everything", async () => {
+    await program.methods
+      .pauseAmm(false)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey } as any)
+      .signers([values.admin])
+      .rpc();
+
+    const amm = await program.account.amm.fetch(values.ammKey);
+    expect(amm.paused).to.be.true;
+    expect(amm.withdrawalsPaused).to.be.true;
+
+    await expectRevert(swap());
+    await expectRevert(withdraw());
+
+    await program.methods
+      .resumeAmm()
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey } as any)
+      .signers([values.admin])
+      .rpc();
+
+    await swap();
+    await withdraw();
+  });
+
+  it("Only the admin can pause", async () => {
+    const intruder = anchor.web3.Keypair.generate();
+
+    await expectRevert(
+      program.methods
+        .pauseAmm(false)
+        .accounts({ amm: values.ammKey, admin: intruder.publicKey } as any)
+        .signers([intruder])
+        .rpc()
+    );
+  });
+});
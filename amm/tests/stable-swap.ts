@@ -0,0 +1,210 @@
+import type { Program } from "@coral-xyz/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+import type { SwapExample } from "../target/types/swap_example";
+import {
+  createValues,
+  expectRevert,
+  mintingTokens,
+  type TestValues,
+} from "./utils";
+
+describe("Stable swap", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SwapExample as Program<SwapExample>;
+
+  const amp = new BN(100);
+  const day = 24 * 60 * 60;
+
+  let values: TestValues;
+
+  const createPool = (curveType: any, poolAmp: BN) =>
+    program.methods
+      .createPool(curveType, poolAmp)
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .rpc();
+
+  const rampAmp = (targetAmp: BN, rampEndTs: number, admin = values.admin) =>
+    program.methods
+      .rampAmp(targetAmp, new BN(rampEndTs))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        admin: admin.publicKey,
+      } as any)
+      .signers([admin])
+      .rpc();
+
+  const now = async () =>
+    (await connection.getBlockTime(await connection.getSlot())) as number;
+
+  beforeEach(async () => {
+    values = createValues();
+
+    await program.methods
+      .createAmm(values.id, values.fee, values.protocolFee)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .rpc();
+
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+    });
+  });
+
+  it("Rejects an amp that doesn't fit the curve", async () => {
+    await expectRevert(createPool({ stableSwap: {} }, new BN(0)));
+    await expectRevert(createPool({ constantProduct: {} }, amp));
+  });
+
+  it("Rejects a stable pool of mints with different decimals", async () => {
+    values = createValues({ id: values.id, admin: values.admin });
+    await mintingTokens({
+      connection,
+      creator: values.admin,
+      mintAKeypair: values.mintAKeypair,
+      mintBKeypair: values.mintBKeypair,
+      decimalsB: 9,
+    });
+
+    await expectRevert(createPool({ stableSwap: {} }, amp));
+    await createPool({ constantProduct: {} }, new BN(0));
+  });
+
+  describe("With a stable pool", () => {
+    beforeEach(async () => {
+      await createPool({ stableSwap: {} }, amp);
+
+      await program.methods
+        .depositLiquidity(
+          values.depositAmountA,
+          values.depositAmountA,
+          new BN(0)
+        )
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          depositor: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([values.admin])
+        .rpc({ skipPreflight: true });
+    });
+
+    it("Mints D worth of lp tokens on the first deposit", async () => {
+      // D of a balanced pool is the sum of its reserves
+      const liquidity = await connection.getTokenAccountBalance(
+        values.liquidityAccount
+      );
+      expect(liquidity.value.amount).to.equal(
+        values.depositAmountA.muln(2).sub(values.minimumLiquidity).toString()
+      );
+
+      const pool = await program.account.pool.fetch(values.poolKey);
+      expect(pool.curveType).to.deep.equal({ stableSwap: {} });
+      expect(pool.ampTarget.toNumber()).to.equal(amp.toNumber());
+    });
+
+    it("Swaps close to the peg", async () => {
+      const input = new BN(10 ** 5);
+      const balanceBefore = await connection.getTokenAccountBalance(
+        values.holderAccountB
+      );
+
+      await program.methods
+        .swapTokens(true, input, new BN(1))
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          trader: values.admin.publicKey,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([values.admin])
+        .rpc();
+
+      const balanceAfter = await connection.getTokenAccountBalance(
+        values.holderAccountB
+      );
+      const output =
+        Number(balanceAfter.value.amount) - Number(balanceBefore.value.amount);
+      const taxedInput = (input.toNumber() * (10000 - values.fee)) / 10000;
+
+      // constant product would give ~97.7% of the taxed input here
+      expect(output).to.be.greaterThan(taxedInput * 0.999);
+      expect(output).to.be.lessThan(taxedInput);
+    });
+
+    it("Ramps the amp", async () => {
+      const rampEndTs = (await now()) + 2 * day;
+      await rampAmp(amp.muln(2), rampEndTs);
+
+      let pool = await program.account.pool.fetch(values.poolKey);
+      expect(pool.ampInitial.toNumber()).to.equal(amp.toNumber());
+      expect(pool.ampTarget.toNumber()).to.equal(amp.muln(2).toNumber());
+      expect(pool.ampRampEndTs.toNumber()).to.equal(rampEndTs);
+
+      await program.methods
+        .stopRampAmp()
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          admin: values.admin.publicKey,
+        } as any)
+        .signers([values.admin])
+        .rpc();
+
+      pool = await program.account.pool.fetch(values.poolKey);
+      expect(pool.ampTarget.toNumber()).to.equal(pool.ampInitial.toNumber());
+
+      // a new ramp has to wait a day after the last one started
+      await expectRevert(rampAmp(amp, (await now()) + 2 * day));
+    });
+
+    it("Rejects bad ramps", async () => {
+      // too short
+      await expectRevert(rampAmp(amp.muln(2), (await now()) + 60));
+      // too big of a jump
+      await expectRevert(rampAmp(amp.muln(11), (await now()) + 2 * day));
+      // not the admin
+      await expectRevert(
+        rampAmp(amp.muln(2), (await now()) + 2 * day, Keypair.generate())
+      );
+    });
+  });
+});
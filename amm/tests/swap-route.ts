@@ -0,0 +1,217 @@
+import type { Program } from "@coral-xyz/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { BN } from "bn.js";
+import { expect } from "chai";
+import type { SwapExample } from "../target/types/swap_example";
+import {
+  createValues,
+  expectRevert,
+  mintingTokens,
+  type TestValues,
+} from "./utils";
+
+describe("Swap route", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SwapExample as Program<SwapExample>;
+
+  // first pool trades X/Y, second pool trades Y/Z
+  let first: TestValues;
+  let second: TestValues;
+
+  const createAndFundPool = async (values: TestValues) => {
+    await program.methods
+      .createPool({ constantProduct: {} }, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    await program.methods
+      .depositLiquidity(values.depositAmountA, values.depositAmountA, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        depositor: values.admin.publicKey,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        depositorAccountLiquidity: values.liquidityAccount,
+        depositorAccountA: values.holderAccountA,
+        depositorAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc({ skipPreflight: true });
+  };
+
+  const hop = (values: TestValues, swapA: boolean) => [
+    { pubkey: values.poolKey, isSigner: false, isWritable: true },
+    { pubkey: values.poolAuthority, isSigner: false, isWritable: false },
+    {
+      pubkey: swapA ? values.poolAccountA : values.poolAccountB,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: swapA ? values.poolAccountB : values.poolAccountA,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: swapA
+        ? values.mintBKeypair.publicKey
+        : values.mintAKeypair.publicKey,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+  ];
+
+  beforeEach(async () => {
+    first = createValues();
+
+    await program.methods
+      .createAmm(first.id, first.fee, first.protocolFee)
+      .accounts({ amm: first.ammKey, admin: first.admin.publicKey })
+      .rpc();
+
+    await mintingTokens({
+      connection,
+      creator: first.admin,
+      mintAKeypair: first.mintAKeypair,
+      mintBKeypair: first.mintBKeypair,
+    });
+
+    second = createValues({
+      id: first.id,
+      admin: first.admin,
+      mintAKeypair: first.mintBKeypair,
+    });
+
+    await createMint(
+      connection,
+      first.admin,
+      first.admin.publicKey,
+      first.admin.publicKey,
+      6,
+      second.mintBKeypair
+    );
+    const holderAccountZ = await getOrCreateAssociatedTokenAccount(
+      connection,
+      first.admin,
+      second.mintBKeypair.publicKey,
+      first.admin.publicKey
+    );
+    await mintTo(
+      connection,
+      first.admin,
+      second.mintBKeypair.publicKey,
+      holderAccountZ.address,
+      first.admin.publicKey,
+      first.defaultSupply.toNumber()
+    );
+
+    await createAndFundPool(first);
+    await createAndFundPool(second);
+  });
+
+  it("Swap x to z through y", async () => {
+    const input = new BN(10 ** 5);
+    const balanceZBefore = await connection.getTokenAccountBalance(
+      second.holderAccountB
+    );
+
+    await program.methods
+      .swapRoute(input, new BN(1), null)
+      .accounts({
+        amm: first.ammKey,
+        sourceMint: first.mintAKeypair.publicKey,
+        traderSource: first.holderAccountA,
+        traderDestination: second.holderAccountB,
+        trader: first.admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .remainingAccounts([...hop(first, true), ...hop(second, true)])
+      .signers([first.admin])
+      .rpc();
+
+    const balanceX = await connection.getTokenAccountBalance(
+      first.holderAccountA
+    );
+    const balanceZAfter = await connection.getTokenAccountBalance(
+      second.holderAccountB
+    );
+    expect(balanceX.value.amount).to.equal(
+      first.defaultSupply.sub(first.depositAmountA).sub(input).toString()
+    );
+    expect(Number(balanceZAfter.value.amount)).to.be.greaterThan(
+      Number(balanceZBefore.value.amount)
+    );
+    expect(Number(balanceZAfter.value.amount)).to.be.lessThan(
+      Number(balanceZBefore.value.amount) + input.toNumber()
+    );
+  });
+
+  it("End to end slippage check", async () => {
+    await expectRevert(
+      program.methods
+        .swapRoute(new BN(10 ** 5), new BN(10 ** 5), null)
+        .accounts({
+          amm: first.ammKey,
+          sourceMint: first.mintAKeypair.publicKey,
+          traderSource: first.holderAccountA,
+          traderDestination: second.holderAccountB,
+          trader: first.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .remainingAccounts([...hop(first, true), ...hop(second, true)])
+        .signers([first.admin])
+        .rpc()
+    );
+  });
+
+  it("Broken route", async () => {
+    // second hop starts from the wrong side of the pool
+    await expectRevert(
+      program.methods
+        .swapRoute(new BN(10 ** 5), new BN(1), null)
+        .accounts({
+          amm: first.ammKey,
+          sourceMint: first.mintAKeypair.publicKey,
+          traderSource: first.holderAccountA,
+          traderDestination: second.holderAccountB,
+          trader: first.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .remainingAccounts([...hop(first, true), ...hop(second, false)])
+        .signers([first.admin])
+        .rpc()
+    );
+  });
+});
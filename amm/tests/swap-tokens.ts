@@ -1,9 +1,15 @@
 import type { Program } from "@coral-xyz/anchor";
 import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { BN } from "bn.js";
 import { expect } from "chai";
 import type { SwapExample } from "../target/types/swap_example";
-import { createValues, mintingTokens, TestValues } from "./utils";
+import {
+  createValues,
+  expectRevert,
+  mintingTokens,
+  type TestValues,
+} from "./utils";
 
 describe("Swapping tokens test", () => {
   const provider = anchor.AnchorProvider.env();
@@ -17,7 +23,7 @@ describe("Swapping tokens test", () => {
     values = createValues();
 
     await program.methods
-      .createAmm(values.id, values.fee)
+      .createAmm(values.id, values.fee, values.protocolFee)
       .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
       .rpc();
 
@@ -29,26 +35,32 @@ describe("Swapping tokens test", () => {
     });
 
     await program.methods
-      .createPool()
+      .createPool({ constantProduct: {} }, new BN(0))
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
         mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
     await program.methods
-      .depositLiquidity(values.depositAmountA, values.depositAmountB)
+      .depositLiquidity(values.depositAmountA, values.depositAmountB, new BN(0))
       .accounts({
+        amm: values.ammKey,
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
         depositor: values.admin.publicKey,
         mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
         poolAccountA: values.poolAccountA,
@@ -56,6 +68,9 @@ describe("Swapping tokens test", () => {
         depositorAccountLiquidity: values.liquidityAccount,
         depositorAccountA: values.holderAccountA,
         depositorAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([values.admin])
       .rpc({ skipPreflight: true });
@@ -77,6 +92,8 @@ describe("Swapping tokens test", () => {
         trader: values.admin.publicKey,
         traderAccountA: values.holderAccountA,
         traderAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
       })
       .signers([values.admin])
       .rpc();
@@ -97,4 +114,153 @@ describe("Swapping tokens test", () => {
       values.defaultSupply.sub(values.depositAmountB).add(input).toNumber()
     );
   });
+
+  it("Swap a for an exact amount of b", async () => {
+    const output = new BN(10 ** 5);
+    await program.methods
+      .swapTokensForExactTokens(true, output, new BN(10 ** 6), null)
+      .accounts({
+        amm: values.ammKey.toBase58(),
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        trader: values.admin.publicKey,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    const traderAccountA = await connection.getTokenAccountBalance(
+      values.holderAccountA
+    );
+    const traderAccountB = await connection.getTokenAccountBalance(
+      values.holderAccountB
+    );
+    expect(traderAccountB.value.amount).to.equal(
+      values.defaultSupply.sub(values.depositAmountB).add(output).toString()
+    );
+    expect(Number(traderAccountA.value.amount)).to.be.lessThan(
+      values.defaultSupply.sub(values.depositAmountA).toNumber()
+    );
+    expect(Number(traderAccountA.value.amount)).to.be.greaterThan(
+      values.defaultSupply
+        .sub(values.depositAmountA)
+        .sub(new BN(10 ** 6))
+        .toNumber()
+    );
+  });
+
+  it("Exact output swap above the max input", async () => {
+    await expectRevert(
+      program.methods
+        .swapTokensForExactTokens(true, new BN(10 ** 5), new BN(100), null)
+        .accounts({
+          amm: values.ammKey.toBase58(),
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          trader: values.admin.publicKey,
+          traderAccountA: values.holderAccountA,
+          traderAccountB: values.holderAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        })
+        .signers([values.admin])
+        .rpc()
+    );
+  });
+
+  it("Swap before the deadline", async () => {
+    const deadline = new BN(Math.floor(Date.now() / 1000) + 60);
+    await program.methods
+      .swapTokensWithDeadline(true, new BN(10 ** 6), new BN(100), deadline)
+      .accounts({
+        amm: values.ammKey.toBase58(),
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        trader: values.admin.publicKey,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+  });
+
+  it("Swap after the deadline", async () => {
+    const deadline = new BN(Math.floor(Date.now() / 1000) - 60);
+    await expectRevert(
+      program.methods
+        .swapTokensWithDeadline(true, new BN(10 ** 6), new BN(100), deadline)
+        .accounts({
+          amm: values.ammKey.toBase58(),
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          trader: values.admin.publicKey,
+          traderAccountA: values.holderAccountA,
+          traderAccountB: values.holderAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+        })
+        .signers([values.admin])
+        .rpc()
+    );
+  });
+
+  it("Emits a swap event", async () => {
+    const input = new BN(10 ** 6);
+    let event: any;
+    const listener = program.addEventListener("swap", (e) => {
+      event = e;
+    });
+
+    await program.methods
+      .swapTokens(true, input, new BN(100))
+      .accounts({
+        amm: values.ammKey.toBase58(),
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        trader: values.admin.publicKey,
+        traderAccountA: values.holderAccountA,
+        traderAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+      })
+      .signers([values.admin])
+      .rpc();
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    expect(event.pool.toString()).to.equal(values.poolKey.toString());
+    expect(event.mintIn.toString()).to.equal(
+      values.mintAKeypair.publicKey.toString()
+    );
+    expect(event.inputAmount.toString()).to.equal(input.toString());
+    expect(event.feeAmount.toString()).to.equal(
+      input.muln(values.fee).divn(10000).toString()
+    );
+  });
 });
@@ -0,0 +1,260 @@
+import type { Program } from "@coral-xyz/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import {
+  createInitializeMintInstruction,
+  createInitializeNonTransferableMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  ExtensionType,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import {
+  type Keypair,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import { BN } from "bn.js";
+import { expect } from "chai";
+import type { SwapExample } from "../target/types/swap_example";
+import { createValues, expectRevert, type TestValues } from "./utils";
+
+describe("Token-2022", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.SwapExample as Program<SwapExample>;
+
+  // 1% on every transfer
+  const transferFeeBps = 100;
+
+  let values: TestValues;
+
+  const createMint2022 = async (
+    mintKeypair: Keypair,
+    extension: ExtensionType
+  ) => {
+    const admin = values.admin;
+    const space = getMintLen([extension]);
+    const lamports = await connection.getMinimumBalanceForRentExemption(space);
+
+    const initializeExtension =
+      extension === ExtensionType.TransferFeeConfig
+        ? createInitializeTransferFeeConfigInstruction(
+            mintKeypair.publicKey,
+            admin.publicKey,
+            admin.publicKey,
+            transferFeeBps,
+            BigInt(10 ** 9),
+            TOKEN_2022_PROGRAM_ID
+          )
+        : createInitializeNonTransferableMintInstruction(
+            mintKeypair.publicKey,
+            TOKEN_2022_PROGRAM_ID
+          );
+
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: admin.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        initializeExtension,
+        createInitializeMintInstruction(
+          mintKeypair.publicKey,
+          6,
+          admin.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [admin, mintKeypair]
+    );
+
+    const holder = await getOrCreateAssociatedTokenAccount(
+      connection,
+      admin,
+      mintKeypair.publicKey,
+      admin.publicKey,
+      true,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      connection,
+      admin,
+      mintKeypair.publicKey,
+      holder.address,
+      admin.publicKey,
+      values.defaultSupply.toNumber(),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+  };
+
+  const createPool = () =>
+    program.methods
+      .createPool({ constantProduct: {} }, new BN(0))
+      .accounts({
+        amm: values.ammKey,
+        pool: values.poolKey,
+        poolAuthority: values.poolAuthority,
+        mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
+        mintA: values.mintAKeypair.publicKey,
+        mintB: values.mintBKeypair.publicKey,
+        poolAccountA: values.poolAccountA,
+        poolAccountB: values.poolAccountB,
+        tokenProgramA: values.tokenProgram,
+        tokenProgramB: values.tokenProgram,
+        tokenProgram: values.tokenProgram,
+      } as any)
+      .rpc();
+
+  beforeEach(async () => {
+    values = createValues({ tokenProgram: TOKEN_2022_PROGRAM_ID });
+
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(values.admin.publicKey, 10 ** 10)
+    );
+
+    await program.methods
+      .createAmm(values.id, values.fee, values.protocolFee)
+      .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
+      .rpc();
+  });
+
+  describe("Transfer fee mints", () => {
+    beforeEach(async () => {
+      await createMint2022(
+        values.mintAKeypair,
+        ExtensionType.TransferFeeConfig
+      );
+      await createMint2022(
+        values.mintBKeypair,
+        ExtensionType.TransferFeeConfig
+      );
+      await createPool();
+
+      await program.methods
+        .depositLiquidity(
+          values.depositAmountA,
+          values.depositAmountA,
+          new BN(0)
+        )
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          depositor: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          tokenProgramA: values.tokenProgram,
+          tokenProgramB: values.tokenProgram,
+          tokenProgram: values.tokenProgram,
+        } as any)
+        .signers([values.admin])
+        .rpc({ skipPreflight: true });
+    });
+
+    it("Mints lp tokens for the amounts the pool received", async () => {
+      const received = values.depositAmountA
+        .muln(10000 - transferFeeBps)
+        .divn(10000);
+
+      const poolAccountA = await connection.getTokenAccountBalance(
+        values.poolAccountA
+      );
+      expect(poolAccountA.value.amount).to.equal(received.toString());
+
+      const liquidity = await connection.getTokenAccountBalance(
+        values.liquidityAccount
+      );
+      expect(liquidity.value.amount).to.equal(
+        received.sub(values.minimumLiquidity).toString()
+      );
+    });
+
+    it("Prices swaps on the amount the pool received", async () => {
+      const input = new BN(10 ** 5);
+      const poolBefore = await connection.getTokenAccountBalance(
+        values.poolAccountA
+      );
+
+      await program.methods
+        .swapTokens(true, input, new BN(1))
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          trader: values.admin.publicKey,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolAccountA: values.poolAccountA,
+          poolAccountB: values.poolAccountB,
+          tokenProgramA: values.tokenProgram,
+          tokenProgramB: values.tokenProgram,
+        } as any)
+        .signers([values.admin])
+        .rpc({ skipPreflight: true });
+
+      const poolAfter = await connection.getTokenAccountBalance(
+        values.poolAccountA
+      );
+      expect(
+        new BN(poolAfter.value.amount)
+          .sub(new BN(poolBefore.value.amount))
+          .toString()
+      ).to.equal(input.muln(10000 - transferFeeBps).divn(10000).toString());
+    });
+
+    it("Withdraws through the token-2022 program", async () => {
+      await program.methods
+        .withdrawLiquidity(values.depositAmountA.divn(2), new BN(0), new BN(0))
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          depositer: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolTokenAccountA: values.poolAccountA,
+          poolTokenAccountB: values.poolAccountB,
+          tokenProgramA: values.tokenProgram,
+          tokenProgramB: values.tokenProgram,
+          tokenProgram: values.tokenProgram,
+        } as any)
+        .signers([values.admin])
+        .rpc({ skipPreflight: true });
+
+      const liquidity = await connection.getTokenAccountBalance(
+        values.liquidityAccount
+      );
+      expect(Number(liquidity.value.amount)).to.be.lessThan(
+        values.depositAmountA.toNumber()
+      );
+    });
+  });
+
+  it("Rejects mints with unsupported extensions", async () => {
+    await createMint2022(values.mintAKeypair, ExtensionType.NonTransferable);
+    await createMint2022(values.mintBKeypair, ExtensionType.TransferFeeConfig);
+
+    await expectRevert(createPool());
+  });
+});
@@ -4,6 +4,7 @@ import {
   getAssociatedTokenAddressSync,
   getOrCreateAssociatedTokenAccount,
   mintTo,
+  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import {
   type Connection,
@@ -40,6 +41,7 @@ export const mintingTokens = async ({
   mintBKeypair,
   mintedAmount = 100,
   decimals = 6,
+  decimalsB = decimals,
 }: {
   connection: Connection;
   creator: Signer;
@@ -48,6 +50,7 @@ export const mintingTokens = async ({
   mintBKeypair: Keypair;
   mintedAmount?: number;
   decimals?: number;
+  decimalsB?: number;
 }) => {
   // Mint tokens
   await connection.confirmTransaction(
@@ -66,7 +69,7 @@ export const mintingTokens = async ({
     creator,
     creator.publicKey,
     creator.publicKey,
-    decimals,
+    decimalsB,
     mintBKeypair
   );
   await getOrCreateAssociatedTokenAccount(
@@ -105,13 +108,14 @@ export const mintingTokens = async ({
       true
     ),
     creator.publicKey,
-    mintedAmount * 10 ** decimals
+    mintedAmount * 10 ** decimalsB
   );
 };
 
 export interface TestValues {
   id: PublicKey;
   fee: number;
+  protocolFee: number;
   admin: Keypair;
   mintAKeypair: Keypair;
   mintBKeypair: Keypair;
@@ -121,6 +125,7 @@ export interface TestValues {
   poolKey: PublicKey;
   poolAuthority: PublicKey;
   mintLiquidity: PublicKey;
+  lockedLiquidity: PublicKey;
   depositAmountA: anchor.BN;
   depositAmountB: anchor.BN;
   liquidityAccount: PublicKey;
@@ -128,6 +133,7 @@ export interface TestValues {
   poolAccountB: PublicKey;
   holderAccountA: PublicKey;
   holderAccountB: PublicKey;
+  tokenProgram: PublicKey;
 }
 
 type TestValuesDefaults = {
@@ -135,16 +141,18 @@ type TestValuesDefaults = {
 };
 export function createValues(defaults?: TestValuesDefaults): TestValues {
   const id = defaults?.id || Keypair.generate().publicKey;
-  const admin = Keypair.generate();
+  const admin = defaults?.admin || Keypair.generate();
+  const tokenProgram = defaults?.tokenProgram || TOKEN_PROGRAM_ID;
   const ammKey = PublicKey.findProgramAddressSync(
     [id.toBuffer()],
     anchor.workspace.SwapExample.programId
   )[0];
 
   // Making sure tokens are in the right order
-  const mintAKeypair = Keypair.generate();
-  let mintBKeypair = Keypair.generate();
+  const mintAKeypair = defaults?.mintAKeypair || Keypair.generate();
+  let mintBKeypair = defaults?.mintBKeypair || Keypair.generate();
   while (
+    !defaults?.mintBKeypair &&
     new BN(mintBKeypair.publicKey.toBytes()).lt(
       new BN(mintAKeypair.publicKey.toBytes())
     )
@@ -170,6 +178,15 @@ export function createValues(defaults?: TestValuesDefaults): TestValues {
     ],
     anchor.workspace.SwapExample.programId
   )[0];
+  const lockedLiquidity = PublicKey.findProgramAddressSync(
+    [
+      ammKey.toBuffer(),
+      mintAKeypair.publicKey.toBuffer(),
+      mintBKeypair.publicKey.toBuffer(),
+      Buffer.from("locked_liquidity"),
+    ],
+    anchor.workspace.SwapExample.programId
+  )[0];
   const poolKey = PublicKey.findProgramAddressSync(
     [
       ammKey.toBuffer(),
@@ -181,41 +198,49 @@ export function createValues(defaults?: TestValuesDefaults): TestValues {
   return {
     id,
     fee: 500,
+    protocolFee: 1667,
     admin,
     ammKey,
     mintAKeypair,
     mintBKeypair,
     mintLiquidity,
+    lockedLiquidity,
     poolKey,
     poolAuthority,
     poolAccountA: getAssociatedTokenAddressSync(
       mintAKeypair.publicKey,
       poolAuthority,
-      true
+      true,
+      tokenProgram
     ),
     poolAccountB: getAssociatedTokenAddressSync(
       mintBKeypair.publicKey,
       poolAuthority,
-      true
+      true,
+      tokenProgram
     ),
     liquidityAccount: getAssociatedTokenAddressSync(
       mintLiquidity,
       admin.publicKey,
-      true
+      true,
+      tokenProgram
     ),
     holderAccountA: getAssociatedTokenAddressSync(
       mintAKeypair.publicKey,
       admin.publicKey,
-      true
+      true,
+      tokenProgram
     ),
     holderAccountB: getAssociatedTokenAddressSync(
       mintBKeypair.publicKey,
       admin.publicKey,
-      true
+      true,
+      tokenProgram
     ),
     depositAmountA: new BN(4 * 10 ** 6),
     depositAmountB: new BN(1 * 10 ** 6),
     minimumLiquidity: new BN(100),
     defaultSupply: new BN(100 * 10 ** 6),
+    tokenProgram,
   };
 }
@@ -2,7 +2,14 @@ import type { Program } from "@coral-xyz/anchor";
 import * as anchor from "@coral-xyz/anchor";
 import { expect } from "chai";
 import type { SwapExample } from "../target/types/swap_example";
-import { createValues, mintingTokens, type TestValues } from "./utils";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from "bn.js";
+import {
+  createValues,
+  expectRevert,
+  mintingTokens,
+  type TestValues,
+} from "./utils";
 
 describe("Withdraw liquidity", () => {
   const provider = anchor.AnchorProvider.env();
@@ -17,7 +24,7 @@ describe("Withdraw liquidity", () => {
     values = createValues();
 
     await program.methods
-      .createAmm(values.id, values.fee)
+      .createAmm(values.id, values.fee, values.protocolFee)
       .accounts({ amm: values.ammKey, admin: values.admin.publicKey })
       .rpc();
 
@@ -29,26 +36,32 @@ describe("Withdraw liquidity", () => {
     });
 
     await program.methods
-      .createPool()
+      .createPool({ constantProduct: {} }, new BN(0))
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
         mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
         poolAccountA: values.poolAccountA,
         poolAccountB: values.poolAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
 
     await program.methods
-      .depositLiquidity(values.depositAmountA, values.depositAmountA)
+      .depositLiquidity(values.depositAmountA, values.depositAmountA, new BN(0))
       .accounts({
+        amm: values.ammKey,
         pool: values.poolKey,
         poolAuthority: values.poolAuthority,
         depositor: values.admin.publicKey,
         mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
         poolAccountA: values.poolAccountA,
@@ -56,6 +69,9 @@ describe("Withdraw liquidity", () => {
         depositorAccountLiquidity: values.liquidityAccount,
         depositorAccountA: values.holderAccountA,
         depositorAccountB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([values.admin])
       .rpc({ skipPreflight: true });
@@ -63,7 +79,11 @@ describe("Withdraw liquidity", () => {
 
   it("Withdraw everything", async () => {
     await program.methods
-      .withdrawLiquidity(values.depositAmountA.sub(values.minimumLiquidity))
+      .withdrawLiquidity(
+        values.depositAmountA.sub(values.minimumLiquidity),
+        new BN(0),
+        new BN(0)
+      )
       .accounts({
         amm: values.ammKey,
         pool: values.poolKey,
@@ -71,6 +91,7 @@ describe("Withdraw liquidity", () => {
         depositer: values.admin.publicKey,
         payer: values.admin.publicKey,
         mintLiquidity: values.mintLiquidity,
+        lockedLiquidity: values.lockedLiquidity,
         mintA: values.mintAKeypair.publicKey,
         mintB: values.mintBKeypair.publicKey,
         poolTokenAccountA: values.poolAccountA,
@@ -78,6 +99,9 @@ describe("Withdraw liquidity", () => {
         depositerAccountTokenLp: values.liquidityAccount,
         depositerAccountTokenA: values.holderAccountA,
         depositerAccountTokenB: values.holderAccountB,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
       } as any)
       .signers([values.admin])
       .rpc();
@@ -105,4 +129,38 @@ describe("Withdraw liquidity", () => {
       values.defaultSupply.sub(values.depositAmountA).toNumber()
     );
   });
+
+  it("Withdraw below the minimum amounts", async () => {
+    const deadline = new BN(Math.floor(Date.now() / 1000) + 60);
+    await expectRevert(
+      program.methods
+        .withdrawLiquidityWithDeadline(
+          values.depositAmountA.sub(values.minimumLiquidity),
+          values.depositAmountA,
+          new BN(0),
+          deadline
+        )
+        .accounts({
+          amm: values.ammKey,
+          pool: values.poolKey,
+          poolAuthority: values.poolAuthority,
+          depositer: values.admin.publicKey,
+          payer: values.admin.publicKey,
+          mintLiquidity: values.mintLiquidity,
+          lockedLiquidity: values.lockedLiquidity,
+          mintA: values.mintAKeypair.publicKey,
+          mintB: values.mintBKeypair.publicKey,
+          poolTokenAccountA: values.poolAccountA,
+          poolTokenAccountB: values.poolAccountB,
+          depositerAccountTokenLp: values.liquidityAccount,
+          depositerAccountTokenA: values.holderAccountA,
+          depositerAccountTokenB: values.holderAccountB,
+          tokenProgramA: TOKEN_PROGRAM_ID,
+          tokenProgramB: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([values.admin])
+        .rpc()
+    );
+  });
 });
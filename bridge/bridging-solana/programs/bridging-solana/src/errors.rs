@@ -24,4 +24,55 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("Message already processed")]
     AlreadyProcessed,
+
+    #[msg("Guardian set must be non-empty, distinct and within the size limit")]
+    InvalidGuardianSet,
+
+    #[msg("Threshold must be between one and the size of the set")]
+    InvalidThreshold,
+
+    #[msg("Guardian already attested this message")]
+    AlreadyAttested,
+
+    #[msg("Message id does not match the message contents")]
+    MessageMismatch,
+
+    #[msg("Message does not come from the bridged chain")]
+    InvalidSourceChain,
+
+    #[msg("Validator set must be non-empty, distinct and within the size limit")]
+    InvalidValidatorSet,
+
+    #[msg("EVM signature mode needs the validator set")]
+    MissingValidatorSet,
+
+    #[msg("Malformed secp256k1 instruction")]
+    InvalidSignatureInstruction,
+
+    #[msg("Not enough EVM validators signed the message")]
+    InsufficientSignatures,
+
+    #[msg("Emergency withdraw timelock has not passed")]
+    TimelockActive,
+
+    #[msg("Delay must not be negative")]
+    InvalidDelay,
+
+    #[msg("Destination chain id and bridge address must be non-zero")]
+    InvalidDestination,
+
+    #[msg("Relayer must not be the default pubkey or already a guardian")]
+    InvalidRelayer,
+
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+
+    #[msg("Message is not for this chain")]
+    InvalidDestinationChain,
+
+    #[msg("Current relayer holds no guardian seat, use set_guardians")]
+    RelayerNotGuardian,
+
+    #[msg("Config is already at the current layout")]
+    AlreadyMigrated,
 }
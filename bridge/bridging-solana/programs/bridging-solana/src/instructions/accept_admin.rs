@@ -0,0 +1,39 @@
+use crate::{errors::ErrorCode, state::BridgeConfig};
+use anchor_lang::prelude::*;
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let previous_admin = config.admin;
+    config.admin = config.pending_admin;
+    config.pending_admin = Pubkey::default();
+
+    emit!(AdminTransferredEvent {
+        config: config.key(),
+        previous_admin,
+        admin: config.admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"bridge", config.token_mint.as_ref()],
+        bump,
+        constraint = config.pending_admin != Pubkey::default()
+            && config.pending_admin == pending_admin.key() @ErrorCode::NotPendingAdmin
+    )]
+    pub config: Account<'info, BridgeConfig>,
+}
+
+#[event]
+pub struct AdminTransferredEvent {
+    config: Pubkey,
+    previous_admin: Pubkey,
+    admin: Pubkey,
+}
@@ -3,7 +3,7 @@ use crate::state::BridgeConfig;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
 
-pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
     let config = &ctx.accounts.config;
     let config_key = config.key();
     let bump = ctx.accounts.config.vault_authority_bump;
@@ -13,6 +13,14 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
         config.admin == ctx.accounts.admin.key(),
         ErrorCode::UnauthorizedAdmin
     );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    // users get emergency_withdraw_delay seconds after the pause to see it coming
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= config.emergency_withdraw_at(),
+        ErrorCode::TimelockActive
+    );
 
     let vault_seeds: &[&[u8]] = &[b"vault", config_key.as_ref(), &[bump]];
     let signer_seeds = &[vault_seeds];
@@ -22,14 +30,19 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
         Transfer {
             authority: ctx.accounts.vault_authority.to_account_info(),
             from: ctx.accounts.token_vault.to_account_info(),
-            to: ctx.accounts.admin_token_account.to_account_info(),
+            to: ctx.accounts.destination_token_account.to_account_info(),
         },
     );
 
-    transfer(
-        cpi_ctx.with_signer(signer_seeds),
-        ctx.accounts.token_vault.amount,
-    )?;
+    transfer(cpi_ctx.with_signer(signer_seeds), amount)?;
+
+    emit!(EmergencyWithdrawEvent {
+        config: config_key,
+        admin: ctx.accounts.admin.key(),
+        destination: ctx.accounts.destination_token_account.key(),
+        amount,
+        vault_balance: ctx.accounts.token_vault.amount - amount,
+    });
 
     Ok(())
 }
@@ -68,10 +81,19 @@ pub struct EmergencyWithdraw<'info> {
     // thats why no init
     #[account(
         mut,
-        constraint = admin_token_account.mint == config.token_mint,
-        constraint = admin_token_account.owner == admin.key()
+        constraint = destination_token_account.mint == config.token_mint
     )]
-    pub admin_token_account: Account<'info, TokenAccount>,
+    pub destination_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
 }
+
+#[event]
+pub struct EmergencyWithdrawEvent {
+    config: Pubkey,
+    admin: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+    // left in the vault after the withdrawal
+    vault_balance: u64,
+}
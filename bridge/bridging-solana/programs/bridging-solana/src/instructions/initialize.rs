@@ -1,4 +1,4 @@
-use crate::state::BridgeConfig;
+use crate::state::{BridgeConfig, GuardianSet, TrustMode};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -7,6 +7,7 @@ use anchor_spl::{
 
 pub fn initialize_bridge(
     ctx: Context<InitializeBridge>,
+    chain_id: u64,
     destination_chain_id: u64,
     destination_bridge: [u8; 20],
     relayer: Pubkey,
@@ -18,15 +19,28 @@ pub fn initialize_bridge(
     config.token_mint = ctx.accounts.token_mint.key();
     config.vault_authority_bump = ctx.bumps.vault_authority;
     config.nonce = 0;
+    config.chain_id = chain_id;
     config.destination_chain_id = destination_chain_id;
     config.destination_bridge = destination_bridge;
     config.relayer_pubkey = relayer;
+    config.emergency_withdraw_delay = 0;
+    config.pending_emergency_withdraw_delay = 0;
+    config.pending_delay_effective_at = 0;
+    config.pending_admin = Pubkey::default();
+
+    // the relayer starts out as the only guardian, set_guardians adds more
+    let guardian_set = &mut ctx.accounts.guardian_set;
+    guardian_set.config = config.key();
+    guardian_set.index = 0;
+    guardian_set.threshold = 1;
+    guardian_set.guardians = vec![relayer];
+    guardian_set.trust_mode = TrustMode::Guardians;
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(destination_chain_id: u64, destination_bridge: [u8; 20])]
+#[instruction(chain_id: u64, destination_chain_id: u64, destination_bridge: [u8; 20])]
 pub struct InitializeBridge<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
@@ -42,6 +56,15 @@ pub struct InitializeBridge<'info> {
     )]
     pub config: Account<'info, BridgeConfig>,
 
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GuardianSet::INIT_SPACE,
+        seeds = [b"guardians", config.key().as_ref()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
     /// CHECK: Vault authority doesn't need to be checked
     #[account(
         seeds=[b"vault", config.key().as_ref()],
@@ -0,0 +1,85 @@
+use crate::{errors::ErrorCode, state::BridgeConfig};
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+};
+use anchor_spl::token::Mint;
+
+// grows a config from before paused_at, the emergency withdraw delay, the
+// admin handover and chain_id to the current size, the admin covering the
+// rent. the config can't be initialized again, its address only depends on
+// the mint. the new fields start out empty, no delay and nothing pending,
+// except chain_id which the admin has to supply
+pub fn migrate_config(ctx: Context<MigrateConfig>, chain_id: u64) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+    require!(
+        config_info.data_len() == BridgeConfig::LEGACY_LEN,
+        ErrorCode::AlreadyMigrated
+    );
+
+    let len = 8 + BridgeConfig::INIT_SPACE;
+    let rent = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(config_info.lamports());
+    if rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: config_info.clone(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    // the old fields are a prefix of the new layout, the rest is zeroed
+    config_info.resize(len)?;
+
+    let mut config = BridgeConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+    require_keys_eq!(
+        config.admin,
+        ctx.accounts.admin.key(),
+        ErrorCode::UnauthorizedAdmin
+    );
+    config.paused_at = 0;
+    config.emergency_withdraw_delay = 0;
+    config.pending_admin = Pubkey::default();
+    config.chain_id = chain_id;
+    config.pending_emergency_withdraw_delay = 0;
+    config.pending_delay_effective_at = 0;
+    config.try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
+
+    emit!(ConfigMigratedEvent {
+        config: config_info.key(),
+        chain_id,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: PDA, safe because seeds are verified. read in the handler,
+    /// which only takes configs still at BridgeConfig::LEGACY_LEN
+    #[account(
+        mut,
+        seeds=[b"bridge", token_mint.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct ConfigMigratedEvent {
+    config: Pubkey,
+    chain_id: u64,
+}
@@ -1,13 +1,31 @@
+pub mod accept_admin;
 pub mod emergency_withdraw;
 pub mod initialize;
 pub mod lock_tokens;
+pub mod migrate_config;
 pub mod pause_bride;
 pub mod resume_bridge;
+pub mod set_destination;
+pub mod set_emergency_withdraw_delay;
+pub mod set_evm_validators;
+pub mod set_guardians;
+pub mod set_relayer;
+pub mod set_trust_mode;
+pub mod transfer_admin;
 pub mod unlock_from_evm;
 
+pub use accept_admin::*;
 pub use emergency_withdraw::*;
 pub use initialize::*;
 pub use lock_tokens::*;
+pub use migrate_config::*;
 pub use pause_bride::*;
 pub use resume_bridge::*;
+pub use set_destination::*;
+pub use set_emergency_withdraw_delay::*;
+pub use set_evm_validators::*;
+pub use set_guardians::*;
+pub use set_relayer::*;
+pub use set_trust_mode::*;
+pub use transfer_admin::*;
 pub use unlock_from_evm::*;
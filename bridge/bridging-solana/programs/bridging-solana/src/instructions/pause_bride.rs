@@ -7,6 +7,13 @@ pub fn pause_bridge(ctx: Context<PauseBridge>) -> Result<()> {
     require!(!config.paused, ErrorCode::AlreadyPaused);
 
     config.paused = true;
+    config.paused_at = Clock::get()?.unix_timestamp;
+
+    emit!(BridgePausedEvent {
+        config: config.key(),
+        paused_at: config.paused_at,
+        emergency_withdraw_at: config.emergency_withdraw_at(),
+    });
 
     Ok(())
 }
@@ -23,3 +30,11 @@ pub struct PauseBridge<'info> {
     )]
     pub config: Account<'info, BridgeConfig>,
 }
+
+#[event]
+pub struct BridgePausedEvent {
+    config: Pubkey,
+    paused_at: i64,
+    // earliest time emergency_withdraw can move funds
+    emergency_withdraw_at: i64,
+}
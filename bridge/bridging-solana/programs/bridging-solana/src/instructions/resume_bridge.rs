@@ -9,6 +9,11 @@ pub fn resume_bridge(ctx: Context<ResumeBridge>) -> Result<()> {
 
     config.paused = false;
 
+    emit!(BridgeResumedEvent {
+        config: config.key(),
+        resumed_at: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
 
@@ -25,3 +30,9 @@ pub struct ResumeBridge<'info> {
     )]
     pub config: Account<'info, BridgeConfig>,
 }
+
+#[event]
+pub struct BridgeResumedEvent {
+    config: Pubkey,
+    resumed_at: i64,
+}
@@ -0,0 +1,45 @@
+use crate::{errors::ErrorCode, state::BridgeConfig};
+use anchor_lang::prelude::*;
+
+pub fn set_destination(
+    ctx: Context<SetDestination>,
+    destination_chain_id: u64,
+    destination_bridge: [u8; 20],
+) -> Result<()> {
+    require!(
+        destination_chain_id != 0 && destination_bridge != [0u8; 20],
+        ErrorCode::InvalidDestination
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.destination_chain_id = destination_chain_id;
+    config.destination_bridge = destination_bridge;
+
+    emit!(DestinationUpdatedEvent {
+        config: config.key(),
+        destination_chain_id,
+        destination_bridge,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetDestination<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"bridge", config.token_mint.as_ref()],
+        bump,
+        constraint = config.admin == admin.key() @ErrorCode::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, BridgeConfig>,
+}
+
+#[event]
+pub struct DestinationUpdatedEvent {
+    config: Pubkey,
+    destination_chain_id: u64,
+    destination_bridge: [u8; 20],
+}
@@ -0,0 +1,59 @@
+use crate::{errors::ErrorCode, state::BridgeConfig};
+use anchor_lang::prelude::*;
+
+pub fn set_emergency_withdraw_delay(
+    ctx: Context<SetEmergencyWithdrawDelay>,
+    delay: i64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    // changing it while paused would move the deadline users were shown
+    require!(!config.paused, ErrorCode::BridgePaused);
+    require!(delay >= 0, ErrorCode::InvalidDelay);
+
+    let now = Clock::get()?.unix_timestamp;
+    let current = config.current_emergency_withdraw_delay(now);
+
+    // a longer delay only gives users more notice, a shorter one waits until
+    // the current delay has passed. either replaces what was pending
+    let effective_at = if delay >= current {
+        config.emergency_withdraw_delay = delay;
+        config.pending_emergency_withdraw_delay = 0;
+        config.pending_delay_effective_at = 0;
+        now
+    } else {
+        config.emergency_withdraw_delay = current;
+        config.pending_emergency_withdraw_delay = delay;
+        config.pending_delay_effective_at = now.saturating_add(current);
+        config.pending_delay_effective_at
+    };
+
+    emit!(EmergencyWithdrawDelayUpdatedEvent {
+        config: config.key(),
+        delay,
+        effective_at,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetEmergencyWithdrawDelay<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"bridge", config.token_mint.as_ref()],
+        bump,
+        constraint = config.admin == admin.key() @ErrorCode::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, BridgeConfig>,
+}
+
+#[event]
+pub struct EmergencyWithdrawDelayUpdatedEvent {
+    config: Pubkey,
+    delay: i64,
+    // when it replaces the current delay
+    effective_at: i64,
+}
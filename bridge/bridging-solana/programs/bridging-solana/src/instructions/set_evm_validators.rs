@@ -0,0 +1,72 @@
+use crate::{
+    errors::ErrorCode,
+    state::{BridgeConfig, EvmValidatorSet, MAX_EVM_VALIDATORS},
+};
+use anchor_lang::prelude::*;
+
+// sets the EVM addresses whose signatures unlock_from_evm accepts in EVM
+// signature mode, creating the set on first use
+pub fn set_evm_validators(
+    ctx: Context<SetEvmValidators>,
+    validators: Vec<[u8; 20]>,
+    threshold: u8,
+) -> Result<()> {
+    require!(
+        !validators.is_empty() && validators.len() <= MAX_EVM_VALIDATORS,
+        ErrorCode::InvalidValidatorSet
+    );
+    for (i, validator) in validators.iter().enumerate() {
+        require!(
+            *validator != [0u8; 20] && !validators[..i].contains(validator),
+            ErrorCode::InvalidValidatorSet
+        );
+    }
+    require!(
+        threshold > 0 && threshold as usize <= validators.len(),
+        ErrorCode::InvalidThreshold
+    );
+
+    let validator_set = &mut ctx.accounts.validator_set;
+    validator_set.config = ctx.accounts.config.key();
+    validator_set.threshold = threshold;
+    validator_set.validators = validators.clone();
+
+    emit!(EvmValidatorSetUpdatedEvent {
+        config: validator_set.config,
+        threshold,
+        validators,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetEvmValidators<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds=[b"bridge", config.token_mint.as_ref()],
+        bump,
+        constraint = config.admin == admin.key() @ErrorCode::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + EvmValidatorSet::INIT_SPACE,
+        seeds = [b"validators", config.key().as_ref()],
+        bump
+    )]
+    pub validator_set: Account<'info, EvmValidatorSet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EvmValidatorSetUpdatedEvent {
+    config: Pubkey,
+    threshold: u8,
+    validators: Vec<[u8; 20]>,
+}
@@ -0,0 +1,75 @@
+use crate::{
+    errors::ErrorCode,
+    state::{BridgeConfig, GuardianSet, MAX_GUARDIANS},
+};
+use anchor_lang::prelude::*;
+
+// rotates the guardian set, also creates it for bridges initialized before
+// guardian sets existed once migrate_config brought their config up to date
+pub fn set_guardians(
+    ctx: Context<SetGuardians>,
+    guardians: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    require!(
+        !guardians.is_empty() && guardians.len() <= MAX_GUARDIANS,
+        ErrorCode::InvalidGuardianSet
+    );
+    for (i, guardian) in guardians.iter().enumerate() {
+        require!(
+            !guardians[..i].contains(guardian),
+            ErrorCode::InvalidGuardianSet
+        );
+    }
+    require!(
+        threshold > 0 && threshold as usize <= guardians.len(),
+        ErrorCode::InvalidThreshold
+    );
+
+    let guardian_set = &mut ctx.accounts.guardian_set;
+    guardian_set.config = ctx.accounts.config.key();
+    guardian_set.index += 1;
+    guardian_set.threshold = threshold;
+    guardian_set.guardians = guardians.clone();
+
+    emit!(GuardianSetUpdatedEvent {
+        config: guardian_set.config,
+        index: guardian_set.index,
+        threshold,
+        guardians,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetGuardians<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds=[b"bridge", config.token_mint.as_ref()],
+        bump,
+        constraint = config.admin == admin.key() @ErrorCode::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + GuardianSet::INIT_SPACE,
+        seeds = [b"guardians", config.key().as_ref()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct GuardianSetUpdatedEvent {
+    config: Pubkey,
+    index: u32,
+    threshold: u8,
+    guardians: Vec<Pubkey>,
+}
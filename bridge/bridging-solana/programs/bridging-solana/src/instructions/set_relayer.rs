@@ -0,0 +1,68 @@
+use crate::{
+    errors::ErrorCode,
+    state::{BridgeConfig, GuardianSet},
+};
+use anchor_lang::prelude::*;
+
+// rotates the relayer key, the old key loses its guardian seat so a leaked
+// key can't keep attesting unlocks
+pub fn set_relayer(ctx: Context<SetRelayer>, relayer: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let guardian_set = &mut ctx.accounts.guardian_set;
+
+    require!(
+        relayer != Pubkey::default() && !guardian_set.guardians.contains(&relayer),
+        ErrorCode::InvalidRelayer
+    );
+
+    // the relayer only ever rotates its own seat, a relayer that set_guardians
+    // already dropped has none to hand over
+    let previous_relayer = config.relayer_pubkey;
+    let seat = guardian_set
+        .guardians
+        .iter()
+        .position(|guardian| *guardian == previous_relayer)
+        .ok_or(ErrorCode::RelayerNotGuardian)?;
+    guardian_set.guardians[seat] = relayer;
+    // the set changed, attestations the old key already made stop counting
+    guardian_set.index += 1;
+
+    config.relayer_pubkey = relayer;
+
+    emit!(RelayerUpdatedEvent {
+        config: config.key(),
+        previous_relayer,
+        relayer,
+        guardian_set_index: guardian_set.index,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRelayer<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"bridge", config.token_mint.as_ref()],
+        bump,
+        constraint = config.admin == admin.key() @ErrorCode::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"guardians", config.key().as_ref()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+}
+
+#[event]
+pub struct RelayerUpdatedEvent {
+    config: Pubkey,
+    previous_relayer: Pubkey,
+    relayer: Pubkey,
+    guardian_set_index: u32,
+}
@@ -0,0 +1,56 @@
+use crate::{
+    errors::ErrorCode,
+    state::{BridgeConfig, EvmValidatorSet, GuardianSet, TrustMode},
+};
+use anchor_lang::prelude::*;
+
+pub fn set_trust_mode(ctx: Context<SetTrustMode>, trust_mode: TrustMode) -> Result<()> {
+    // switching to signatures before there is anyone to sign would lock the vault
+    if trust_mode == TrustMode::EvmSignatures {
+        require!(
+            ctx.accounts.validator_set.is_some(),
+            ErrorCode::MissingValidatorSet
+        );
+    }
+
+    let guardian_set = &mut ctx.accounts.guardian_set;
+    guardian_set.trust_mode = trust_mode;
+
+    emit!(TrustModeUpdatedEvent {
+        config: guardian_set.config,
+        trust_mode,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTrustMode<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds=[b"bridge", config.token_mint.as_ref()],
+        bump,
+        constraint = config.admin == admin.key() @ErrorCode::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, BridgeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"guardians", config.key().as_ref()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        seeds = [b"validators", config.key().as_ref()],
+        bump
+    )]
+    pub validator_set: Option<Account<'info, EvmValidatorSet>>,
+}
+
+#[event]
+pub struct TrustModeUpdatedEvent {
+    config: Pubkey,
+    trust_mode: TrustMode,
+}
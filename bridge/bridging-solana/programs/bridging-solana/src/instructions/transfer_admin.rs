@@ -0,0 +1,37 @@
+use crate::{errors::ErrorCode, state::BridgeConfig};
+use anchor_lang::prelude::*;
+
+// first step of an admin handover, the new admin has to call accept_admin.
+// the default pubkey cancels a pending transfer
+pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.pending_admin = new_admin;
+
+    emit!(AdminTransferStartedEvent {
+        config: config.key(),
+        admin: config.admin,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds=[b"bridge", config.token_mint.as_ref()],
+        bump,
+        constraint = config.admin == admin.key() @ErrorCode::UnauthorizedAdmin
+    )]
+    pub config: Account<'info, BridgeConfig>,
+}
+
+#[event]
+pub struct AdminTransferStartedEvent {
+    config: Pubkey,
+    admin: Pubkey,
+    pending_admin: Pubkey,
+}
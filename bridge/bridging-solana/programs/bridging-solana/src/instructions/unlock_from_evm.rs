@@ -1,31 +1,210 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+use solana_instructions_sysvar::load_instruction_at_checked;
+use solana_keccak_hasher::hashv;
+use solana_sdk_ids::secp256k1_program;
 
 use crate::{
     errors::ErrorCode,
-    state::{BridgeConfig, ProcessedMessage},
+    state::{BridgeConfig, EvmValidatorSet, GuardianSet, ProcessedMessage, TrustMode},
 };
 
+// abi.encode(chainid, dstChainId, config, nonce, amount, solanaRecipient), the
+// BurnedToSolana payload EvmBridge.burnWrapped hashes into its messageId
+pub fn evm_payload(
+    src_chain_id: u64,
+    dst_chain_id: u64,
+    config: &Pubkey,
+    nonce: u64,
+    amount: u64,
+    recipient: &Pubkey,
+) -> [u8; 192] {
+    // abi.encode left pads every integer to a 32 byte big endian word
+    let word = |value: u64| {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    };
+
+    let mut payload = [0u8; 192];
+    payload[..32].copy_from_slice(&word(src_chain_id));
+    payload[32..64].copy_from_slice(&word(dst_chain_id));
+    payload[64..96].copy_from_slice(config.as_ref());
+    payload[96..128].copy_from_slice(&word(nonce));
+    payload[128..160].copy_from_slice(&word(amount));
+    payload[160..].copy_from_slice(recipient.as_ref());
+    payload
+}
+
+pub fn evm_message_id(payload: &[u8; 192]) -> [u8; 32] {
+    hashv(&[payload]).to_bytes()
+}
+
+// the secp256k1 program fails the whole transaction on a bad signature, so
+// every signature over `payload` in its instructions is from the address it
+// names. counts the distinct validators among them
+fn evm_signers(
+    instructions_sysvar: &AccountInfo,
+    payload: &[u8],
+    validators: &[[u8; 20]],
+) -> Result<u8> {
+    // num_signatures, then per signature signature_offset: u16,
+    // signature_instruction_index: u8, eth_address_offset: u16,
+    // eth_address_instruction_index: u8, message_data_offset: u16,
+    // message_data_size: u16, message_instruction_index: u8
+    const OFFSETS_SIZE: usize = 11;
+    let read_u16 =
+        |bytes: &[u8], at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]) as usize;
+
+    let mut signers: Vec<[u8; 20]> = Vec::new();
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        if instruction.program_id == secp256k1_program::ID {
+            let data = &instruction.data;
+            let count = *data.first().ok_or(ErrorCode::InvalidSignatureInstruction)? as usize;
+
+            for i in 0..count {
+                let start = 1 + i * OFFSETS_SIZE;
+                let offsets = data
+                    .get(start..start + OFFSETS_SIZE)
+                    .ok_or(ErrorCode::InvalidSignatureInstruction)?;
+
+                // the address and message have to live in this instruction,
+                // anything else could point at bytes we never look at
+                require!(
+                    [offsets[2], offsets[5], offsets[10]]
+                        .iter()
+                        .all(|&ix| ix as usize == index),
+                    ErrorCode::InvalidSignatureInstruction
+                );
+
+                let address_offset = read_u16(offsets, 3);
+                let message_offset = read_u16(offsets, 6);
+                let message_size = read_u16(offsets, 8);
+                let address: [u8; 20] = data
+                    .get(address_offset..address_offset + 20)
+                    .ok_or(ErrorCode::InvalidSignatureInstruction)?
+                    .try_into()
+                    .unwrap();
+                let message = data
+                    .get(message_offset..message_offset + message_size)
+                    .ok_or(ErrorCode::InvalidSignatureInstruction)?;
+
+                if message == payload
+                    && validators.contains(&address)
+                    && !signers.contains(&address)
+                {
+                    signers.push(address);
+                }
+            }
+        }
+        index += 1;
+    }
+
+    Ok(signers.len() as u8)
+}
+
+// in guardian mode records the calling guardian's attestation and only pays out
+// once `threshold` distinct guardians attested the same message. in EVM
+// signature mode pays out once enough validators signed it
 pub fn unlock_from_evm(
     ctx: Context<UnlockFromEvm>,
-    _src_chain_id: u64,
-    _nonce: u64,
+    src_chain_id: u64,
+    dst_chain_id: u64,
+    nonce: u64,
     amount: u64,
+    message_id: [u8; 32],
 ) -> Result<()> {
     let config = &ctx.accounts.config;
     let config_key = config.key();
+    let guardian_set = &ctx.accounts.guardian_set;
+    let relayer = ctx.accounts.relayer.key();
+    let recipient = ctx.accounts.recipient.key();
     require!(!config.paused, ErrorCode::BridgePaused);
     require!(
-        ctx.accounts.relayer.key() == config.relayer_pubkey,
+        guardian_set.guardians.contains(&relayer),
         ErrorCode::Unauthorized
     );
-
+    // burns come from the chain locks are bridged to
+    require!(
+        src_chain_id == config.destination_chain_id,
+        ErrorCode::InvalidSourceChain
+    );
+    // and are meant for this chain, not another one the EVM bridge serves
+    require!(
+        dst_chain_id == config.chain_id,
+        ErrorCode::InvalidDestinationChain
+    );
+    let payload = evm_payload(
+        src_chain_id,
+        dst_chain_id,
+        &config_key,
+        nonce,
+        amount,
+        &recipient,
+    );
     require!(
-        !ctx.accounts.processed_message.executed,
-        ErrorCode::AlreadyProcessed
+        message_id == evm_message_id(&payload),
+        ErrorCode::MessageMismatch
     );
 
-    ctx.accounts.processed_message.executed = true;
+    let message = &mut ctx.accounts.processed_message;
+    require!(!message.executed, ErrorCode::AlreadyProcessed);
+
+    // the pda is derived from the message id, so every attestation on it is
+    // for the same amount and recipient
+    message.message_id = message_id;
+    message.amount = amount;
+    message.recipient = recipient;
+
+    match guardian_set.trust_mode {
+        TrustMode::Guardians => {
+            // a rotation drops whatever the previous set attested
+            if message.guardian_set_index != guardian_set.index {
+                message.guardian_set_index = guardian_set.index;
+                message.attestations.clear();
+            }
+            require!(
+                !message.attestations.contains(&relayer),
+                ErrorCode::AlreadyAttested
+            );
+            message.attestations.push(relayer);
+
+            let attestations = message.attestations.len() as u8;
+            emit!(UnlockAttestedEvent {
+                config: config_key,
+                message_id,
+                src_chain_id,
+                nonce,
+                guardian: relayer,
+                attestations,
+                threshold: guardian_set.threshold,
+            });
+
+            if attestations < guardian_set.threshold {
+                return Ok(());
+            }
+        }
+        TrustMode::EvmSignatures => {
+            let validator_set = ctx
+                .accounts
+                .validator_set
+                .as_ref()
+                .ok_or(ErrorCode::MissingValidatorSet)?;
+            let signers = evm_signers(
+                &ctx.accounts.instructions_sysvar,
+                &payload,
+                &validator_set.validators,
+            )?;
+            require!(
+                signers >= validator_set.threshold,
+                ErrorCode::InsufficientSignatures
+            );
+        }
+    }
+
+    message.executed = true;
+    message.executed_at_slot = Clock::get()?.slot;
 
     // transfer
     let vault_seeds: &[&[u8]] = &[
@@ -49,7 +228,7 @@ pub fn unlock_from_evm(
 }
 
 #[derive(Accounts)]
-#[instruction(src_chain_id: u64, nonce: u64)]
+#[instruction(src_chain_id: u64, dst_chain_id: u64, nonce: u64, amount: u64, message_id: [u8; 32])]
 pub struct UnlockFromEvm<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
@@ -60,15 +239,24 @@ pub struct UnlockFromEvm<'info> {
     )]
     pub config: Account<'info, BridgeConfig>,
 
+    #[account(
+        seeds = [b"guardians", config.key().as_ref()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    // only needed in EVM signature mode
+    #[account(
+        seeds = [b"validators", config.key().as_ref()],
+        bump
+    )]
+    pub validator_set: Option<Account<'info, EvmValidatorSet>>,
+
     #[account(
         init_if_needed,
         payer = relayer,
         space = 8 + ProcessedMessage::INIT_SPACE,
-        seeds = [
-            b"processed",
-            &src_chain_id.to_le_bytes()[..],
-            &nonce.to_le_bytes()
-        ],
+        seeds = [b"processed", config.key().as_ref(), &message_id],
         bump
     )]
     pub processed_message: Account<'info, ProcessedMessage>,
@@ -97,6 +285,21 @@ pub struct UnlockFromEvm<'info> {
     /// CHECK: used only for ATA constraint
     pub recipient: UncheckedAccount<'info>,
 
+    /// CHECK: instructions sysvar, read for the secp256k1 instructions
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
+
+#[event]
+pub struct UnlockAttestedEvent {
+    config: Pubkey,
+    message_id: [u8; 32],
+    src_chain_id: u64,
+    nonce: u64,
+    guardian: Pubkey,
+    attestations: u8,
+    threshold: u8,
+}
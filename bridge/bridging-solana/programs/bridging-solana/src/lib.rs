@@ -7,6 +7,7 @@ pub mod instructions;
 pub mod state;
 
 use instructions::*;
+use state::TrustMode;
 
 #[program]
 pub mod bridging_solana {
@@ -14,11 +15,22 @@ pub mod bridging_solana {
 
     pub fn initialize(
         ctx: Context<InitializeBridge>,
+        chain_id: u64,
         destination_chain_id: u64,
         destination_bridge: [u8; 20],
         relayer: Pubkey,
     ) -> Result<()> {
-        instructions::initialize_bridge(ctx, destination_chain_id, destination_bridge, relayer)
+        instructions::initialize_bridge(
+            ctx,
+            chain_id,
+            destination_chain_id,
+            destination_bridge,
+            relayer,
+        )
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>, chain_id: u64) -> Result<()> {
+        instructions::migrate_config(ctx, chain_id)
     }
 
     pub fn lock_tokens(
@@ -32,10 +44,19 @@ pub mod bridging_solana {
     pub fn unlock_from_evm(
         ctx: Context<UnlockFromEvm>,
         src_chain_id: u64,
+        dst_chain_id: u64,
         nonce: u64,
         amount: u64,
+        message_id: [u8; 32],
     ) -> Result<()> {
-        instructions::unlock_from_evm(ctx, src_chain_id, nonce, amount)
+        instructions::unlock_from_evm(
+            ctx,
+            src_chain_id,
+            dst_chain_id,
+            nonce,
+            amount,
+            message_id,
+        )
     }
 
     pub fn pause_bridge(
@@ -49,4 +70,55 @@ pub mod bridging_solana {
     ) -> Result<()>{
         instructions::resume_bridge(ctx)
     }
+
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, amount: u64) -> Result<()> {
+        instructions::emergency_withdraw(ctx, amount)
+    }
+
+    pub fn set_emergency_withdraw_delay(
+        ctx: Context<SetEmergencyWithdrawDelay>,
+        delay: i64,
+    ) -> Result<()> {
+        instructions::set_emergency_withdraw_delay(ctx, delay)
+    }
+
+    pub fn set_relayer(ctx: Context<SetRelayer>, relayer: Pubkey) -> Result<()> {
+        instructions::set_relayer(ctx, relayer)
+    }
+
+    pub fn set_destination(
+        ctx: Context<SetDestination>,
+        destination_chain_id: u64,
+        destination_bridge: [u8; 20],
+    ) -> Result<()> {
+        instructions::set_destination(ctx, destination_chain_id, destination_bridge)
+    }
+
+    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::transfer_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    pub fn set_guardians(
+        ctx: Context<SetGuardians>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::set_guardians(ctx, guardians, threshold)
+    }
+
+    pub fn set_evm_validators(
+        ctx: Context<SetEvmValidators>,
+        validators: Vec<[u8; 20]>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::set_evm_validators(ctx, validators, threshold)
+    }
+
+    pub fn set_trust_mode(ctx: Context<SetTrustMode>, trust_mode: TrustMode) -> Result<()> {
+        instructions::set_trust_mode(ctx, trust_mode)
+    }
 }
@@ -1,4 +1,10 @@
 use anchor_lang::prelude::*;
+// the borsh dev-dependency would make the derives' `borsh` path ambiguous in tests
+use anchor_lang::prelude::borsh;
+
+// upper bound on the guardian set, keeps GuardianSet and ProcessedMessage fixed size
+pub const MAX_GUARDIANS: usize = 10;
+pub const MAX_EVM_VALIDATORS: usize = 10;
 
 #[account]
 #[derive(InitSpace)]
@@ -11,6 +17,47 @@ pub struct BridgeConfig {
     pub destination_bridge: [u8; 20],
     pub relayer_pubkey: Pubkey,
     pub paused: bool,
+    pub paused_at: i64,
+    // seconds emergency_withdraw has to wait after pause_bridge, 0 for none
+    pub emergency_withdraw_delay: i64,
+    // set by transfer_admin, becomes admin once it calls accept_admin
+    pub pending_admin: Pubkey,
+    // this chain's id on the EVM side, burns have to name it as their dstChainId
+    pub chain_id: u64,
+    // a lower delay waits out the current one before it replaces it, so a
+    // pause right after the change still gives users the old notice.
+    // pending_delay_effective_at is 0 when nothing is pending
+    pub pending_emergency_withdraw_delay: i64,
+    pub pending_delay_effective_at: i64,
+}
+
+impl BridgeConfig {
+    // the discriminator and the fields up to `paused`, all configs held before
+    // the account grew. migrate_config brings them to the current size
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 1 + 8 + 8 + 20 + 32 + 1;
+
+    // the delay in force at `now`
+    pub fn current_emergency_withdraw_delay(&self, now: i64) -> i64 {
+        if self.pending_delay_effective_at != 0 && now >= self.pending_delay_effective_at {
+            self.pending_emergency_withdraw_delay
+        } else {
+            self.emergency_withdraw_delay
+        }
+    }
+
+    // earliest time emergency_withdraw can move funds after the last pause,
+    // a pending delay only counts from when it takes effect
+    pub fn emergency_withdraw_at(&self) -> i64 {
+        let at = self.paused_at.saturating_add(self.emergency_withdraw_delay);
+        if self.pending_delay_effective_at == 0 {
+            return at;
+        }
+        let pending_at = self
+            .paused_at
+            .saturating_add(self.pending_emergency_withdraw_delay)
+            .max(self.pending_delay_effective_at);
+        at.min(pending_at)
+    }
 }
 
 #[account]
@@ -24,8 +71,50 @@ pub struct LockRecord {
     pub created_at_slot: u64,
 }
 
+// relayers allowed to attest EVM burns, `threshold` of them have to agree
+// before the vault pays out
+#[account]
+#[derive(InitSpace)]
+pub struct GuardianSet {
+    pub config: Pubkey,
+    // bumped on every rotation so attestations from an old set stop counting
+    pub index: u32,
+    pub threshold: u8,
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,
+    pub trust_mode: TrustMode,
+}
+
+// what unlock_from_evm needs before the vault pays out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TrustMode {
+    // `threshold` guardians attested the message
+    Guardians,
+    // `threshold` EVM validators signed the BurnedToSolana payload, checked
+    // through secp256k1 instructions in the same transaction
+    EvmSignatures,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct EvmValidatorSet {
+    pub config: Pubkey,
+    pub threshold: u8,
+    #[max_len(MAX_EVM_VALIDATORS)]
+    pub validators: Vec<[u8; 20]>,
+}
+
+// pending until `threshold` guardians attested the same message, then executed
 #[account]
 #[derive(InitSpace)]
 pub struct ProcessedMessage {
     pub executed: bool,
+    // messageId of the BurnedToSolana event, checked against the fields below
+    pub message_id: [u8; 32],
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub guardian_set_index: u32,
+    #[max_len(MAX_GUARDIANS)]
+    pub attestations: Vec<Pubkey>,
+    pub executed_at_slot: u64,
 }
@@ -0,0 +1,273 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { BridgingSolana } from "../target/types/bridging_solana";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createInitializeMintInstruction,
+  getMinimumBalanceForRentExemptMint,
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("admin config", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider();
+  const program = anchor.workspace.BridgingSolana as Program<BridgingSolana>;
+
+  let admin: Keypair;
+  let newAdmin: Keypair;
+  let unauthorizedAdmin: Keypair;
+  let tokenMint: Keypair;
+  let relayer: Keypair;
+  let newRelayer: Keypair;
+  let configPda: PublicKey;
+  let guardianSetPda: PublicKey;
+
+  // this chain, as the EVM side names it
+  const chainId = new anchor.BN(900);
+  const destinationChainId = new anchor.BN(1);
+  const destinationBridge = Buffer.from(
+    "0x1234567890123456789012345678901234567890".slice(2),
+    "hex"
+  );
+
+  const setRelayer = (signer: Keypair, key: PublicKey) =>
+    program.methods
+      .setRelayer(key)
+      .accounts({
+        admin: signer.publicKey,
+        config: configPda,
+        guardianSet: guardianSetPda,
+      } as any)
+      .signers([signer])
+      .rpc();
+
+  const setDestination = (chainId: anchor.BN, bridge: Buffer) =>
+    program.methods
+      .setDestination(chainId, Array.from(bridge))
+      .accounts({ admin: admin.publicKey, config: configPda } as any)
+      .signers([admin])
+      .rpc();
+
+  const acceptAdmin = (signer: Keypair) =>
+    program.methods
+      .acceptAdmin()
+      .accounts({ pendingAdmin: signer.publicKey, config: configPda } as any)
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    admin = Keypair.generate();
+    newAdmin = Keypair.generate();
+    unauthorizedAdmin = Keypair.generate();
+    tokenMint = Keypair.generate();
+    relayer = Keypair.generate();
+    newRelayer = Keypair.generate();
+
+    for (const wallet of [admin, newAdmin, unauthorizedAdmin]) {
+      const airdrop = await provider.connection.requestAirdrop(
+        wallet.publicKey,
+        2 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    }
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bridge"), tokenMint.publicKey.toBuffer()],
+      program.programId
+    );
+
+    [guardianSetPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("guardians"), configPda.toBuffer()],
+      program.programId
+    );
+
+    const mintRent = await getMinimumBalanceForRentExemptMint(
+      provider.connection
+    );
+
+    const createMintTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: admin.publicKey,
+        lamports: mintRent,
+        newAccountPubkey: tokenMint.publicKey,
+        space: MINT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMintInstruction(
+        tokenMint.publicKey,
+        9,
+        admin.publicKey,
+        null
+      )
+    );
+
+    await provider.sendAndConfirm(createMintTx, [admin, tokenMint]);
+
+    await program.methods
+      .initialize(
+        chainId,
+        destinationChainId,
+        Array.from(destinationBridge),
+        relayer.publicKey
+      )
+      .accountsPartial({
+        admin: admin.publicKey,
+        tokenMint: tokenMint.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+  });
+
+  it("Fails when a non admin sets the relayer", async () => {
+    try {
+      await setRelayer(unauthorizedAdmin, newRelayer.publicKey);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("UnauthorizedAdmin");
+    }
+  });
+
+  it("Rotates the relayer and its guardian seat", async () => {
+    await setRelayer(admin, newRelayer.publicKey);
+
+    const config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.relayerPubkey.toString()).to.equal(
+      newRelayer.publicKey.toString()
+    );
+
+    const guardianSet = await program.account.guardianSet.fetch(
+      guardianSetPda
+    );
+    expect(guardianSet.index).to.equal(1);
+    expect(guardianSet.guardians.map((g) => g.toString())).to.deep.equal([
+      newRelayer.publicKey.toString(),
+    ]);
+  });
+
+  it("Fails to set a relayer that already is a guardian", async () => {
+    try {
+      await setRelayer(admin, newRelayer.publicKey);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("InvalidRelayer");
+    }
+  });
+
+  it("Fails to rotate a relayer that holds no guardian seat", async () => {
+    // the admin hands the only seat to someone else
+    await program.methods
+      .setGuardians([relayer.publicKey], 1)
+      .accounts({
+        admin: admin.publicKey,
+        config: configPda,
+        guardianSet: guardianSetPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      } as any)
+      .signers([admin])
+      .rpc();
+
+    try {
+      await setRelayer(admin, Keypair.generate().publicKey);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("RelayerNotGuardian");
+    }
+
+    const config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.relayerPubkey.toString()).to.equal(
+      newRelayer.publicKey.toString()
+    );
+    const guardianSet = await program.account.guardianSet.fetch(
+      guardianSetPda
+    );
+    expect(guardianSet.index).to.equal(2);
+    expect(guardianSet.guardians.map((g) => g.toString())).to.deep.equal([
+      relayer.publicKey.toString(),
+    ]);
+  });
+
+  it("Fails to set a zero destination", async () => {
+    try {
+      await setDestination(new anchor.BN(5), Buffer.alloc(20));
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("InvalidDestination");
+    }
+  });
+
+  it("Updates the destination", async () => {
+    const newBridge = Buffer.from(
+      "abcdefabcdefabcdefabcdefabcdefabcdefabcd",
+      "hex"
+    );
+    await setDestination(new anchor.BN(5), newBridge);
+
+    const config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.destinationChainId.toNumber()).to.equal(5);
+    expect(Buffer.from(config.destinationBridge)).to.deep.equal(newBridge);
+  });
+
+  it("Fails to migrate a config that is already up to date", async () => {
+    try {
+      await program.methods
+        .migrateConfig(new anchor.BN(5))
+        .accounts({
+          admin: admin.publicKey,
+          tokenMint: tokenMint.publicKey,
+          config: configPda,
+        } as any)
+        .signers([admin])
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("AlreadyMigrated");
+    }
+
+    const config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.chainId.toNumber()).to.equal(chainId.toNumber());
+  });
+
+  it("Hands over admin in two steps", async () => {
+    await program.methods
+      .transferAdmin(newAdmin.publicKey)
+      .accounts({ admin: admin.publicKey, config: configPda } as any)
+      .signers([admin])
+      .rpc();
+
+    let config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.admin.toString()).to.equal(admin.publicKey.toString());
+    expect(config.pendingAdmin.toString()).to.equal(
+      newAdmin.publicKey.toString()
+    );
+
+    try {
+      await acceptAdmin(unauthorizedAdmin);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("NotPendingAdmin");
+    }
+
+    await acceptAdmin(newAdmin);
+
+    config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.admin.toString()).to.equal(newAdmin.publicKey.toString());
+    expect(config.pendingAdmin.toString()).to.equal(
+      PublicKey.default.toString()
+    );
+  });
+
+  it("Fails when the previous admin pauses", async () => {
+    try {
+      await program.methods
+        .pauseBridge()
+        .accounts({ admin: admin.publicKey, config: configPda } as any)
+        .signers([admin])
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("UnauthorizedAdmin");
+    }
+  });
+});
@@ -0,0 +1,300 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { BridgingSolana } from "../target/types/bridging_solana";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createAssociatedTokenAccountInstruction,
+  createInitializeMintInstruction,
+  createMintToInstruction,
+  getAccount,
+  getAssociatedTokenAddress,
+  getMinimumBalanceForRentExemptMint,
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("emergency withdraw", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider();
+  const program = anchor.workspace.BridgingSolana as Program<BridgingSolana>;
+
+  let admin: Keypair;
+  let unauthorizedAdmin: Keypair;
+  let tokenMint: Keypair;
+  let relayer: Keypair;
+  let treasury: Keypair;
+  let configPda: PublicKey;
+  let vaultAuthorityPda: PublicKey;
+  let tokenVaultPda: PublicKey;
+  let treasuryTokenAccount: PublicKey;
+
+  // this chain, as the EVM side names it
+  const chainId = new anchor.BN(900);
+  const destinationChainId = new anchor.BN(1);
+  const destinationBridge = Buffer.from(
+    "0x1234567890123456789012345678901234567890".slice(2),
+    "hex"
+  );
+  const mintDecimals = 9;
+  const vaultAmount = new anchor.BN(1000 * 10 ** mintDecimals);
+  const withdrawAmount = new anchor.BN(400 * 10 ** mintDecimals);
+
+  const withdraw = (signer: Keypair, amount: anchor.BN) =>
+    program.methods
+      .emergencyWithdraw(amount)
+      .accounts({
+        admin: signer.publicKey,
+        config: configPda,
+        vaultAuthority: vaultAuthorityPda,
+        tokenVault: tokenVaultPda,
+        destinationTokenAccount: treasuryTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([signer])
+      .rpc();
+
+  const pause = () =>
+    program.methods
+      .pauseBridge()
+      .accounts({ admin: admin.publicKey, config: configPda } as any)
+      .signers([admin])
+      .rpc();
+
+  const resume = () =>
+    program.methods
+      .resumeBridge()
+      .accounts({ admin: admin.publicKey, config: configPda } as any)
+      .signers([admin])
+      .rpc();
+
+  const setDelay = (delay: number) =>
+    program.methods
+      .setEmergencyWithdrawDelay(new anchor.BN(delay))
+      .accounts({ admin: admin.publicKey, config: configPda } as any)
+      .signers([admin])
+      .rpc();
+
+  before(async () => {
+    admin = Keypair.generate();
+    unauthorizedAdmin = Keypair.generate();
+    tokenMint = Keypair.generate();
+    relayer = Keypair.generate();
+    // not the admin, withdrawals can go to any account of the mint
+    treasury = Keypair.generate();
+
+    for (const wallet of [admin, unauthorizedAdmin]) {
+      const airdrop = await provider.connection.requestAirdrop(
+        wallet.publicKey,
+        2 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    }
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bridge"), tokenMint.publicKey.toBuffer()],
+      program.programId
+    );
+
+    [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), configPda.toBuffer()],
+      program.programId
+    );
+
+    tokenVaultPda = await getAssociatedTokenAddress(
+      tokenMint.publicKey,
+      vaultAuthorityPda,
+      true
+    );
+
+    treasuryTokenAccount = await getAssociatedTokenAddress(
+      tokenMint.publicKey,
+      treasury.publicKey
+    );
+
+    const mintRent = await getMinimumBalanceForRentExemptMint(
+      provider.connection
+    );
+
+    const createMintTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: admin.publicKey,
+        lamports: mintRent,
+        newAccountPubkey: tokenMint.publicKey,
+        space: MINT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMintInstruction(
+        tokenMint.publicKey,
+        mintDecimals,
+        admin.publicKey,
+        null
+      )
+    );
+
+    await provider.sendAndConfirm(createMintTx, [admin, tokenMint]);
+
+    await program.methods
+      .initialize(
+        chainId,
+        destinationChainId,
+        Array.from(destinationBridge),
+        relayer.publicKey
+      )
+      .accountsPartial({
+        admin: admin.publicKey,
+        tokenMint: tokenMint.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+
+    const fundTx = new anchor.web3.Transaction().add(
+      createMintToInstruction(
+        tokenMint.publicKey,
+        tokenVaultPda,
+        admin.publicKey,
+        vaultAmount.toNumber(),
+        [],
+        TOKEN_PROGRAM_ID
+      ),
+      createAssociatedTokenAccountInstruction(
+        admin.publicKey,
+        treasuryTokenAccount,
+        treasury.publicKey,
+        tokenMint.publicKey
+      )
+    );
+
+    await provider.sendAndConfirm(fundTx, [admin]);
+  });
+
+  it("Fails when the bridge is not paused", async () => {
+    try {
+      await withdraw(admin, withdrawAmount);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("NotPaused");
+    }
+  });
+
+  it("Fails when a non admin withdraws", async () => {
+    await pause();
+
+    try {
+      await withdraw(unauthorizedAdmin, withdrawAmount);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("UnauthorizedAdmin");
+    }
+  });
+
+  it("Withdraws part of the vault to another account", async () => {
+    await withdraw(admin, withdrawAmount);
+
+    const vault = await getAccount(provider.connection, tokenVaultPda);
+    const treasuryAccount = await getAccount(
+      provider.connection,
+      treasuryTokenAccount
+    );
+    expect(Number(vault.amount)).to.equal(
+      vaultAmount.toNumber() - withdrawAmount.toNumber()
+    );
+    expect(Number(treasuryAccount.amount)).to.equal(withdrawAmount.toNumber());
+  });
+
+  it("Fails to withdraw nothing", async () => {
+    try {
+      await withdraw(admin, new anchor.BN(0));
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("InvalidAmount");
+    }
+  });
+
+  it("Fails to change the delay while paused", async () => {
+    try {
+      await setDelay(3600);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("BridgePaused");
+    }
+  });
+
+  it("Waits for the timelock after a pause", async () => {
+    await resume();
+    await setDelay(3600);
+    await pause();
+
+    const config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.emergencyWithdrawDelay.toNumber()).to.equal(3600);
+    expect(config.pausedAt.toNumber()).to.be.greaterThan(0);
+
+    try {
+      await withdraw(admin, withdrawAmount);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("TimelockActive");
+    }
+  });
+
+  it("Waits out the current delay before a lower one applies", async () => {
+    await resume();
+    const before = Math.floor(Date.now() / 1000);
+    await setDelay(0);
+
+    const config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.emergencyWithdrawDelay.toNumber()).to.equal(3600);
+    expect(config.pendingEmergencyWithdrawDelay.toNumber()).to.equal(0);
+    expect(config.pendingDelayEffectiveAt.toNumber()).to.be.greaterThan(
+      before + 3000
+    );
+
+    await pause();
+    try {
+      await withdraw(admin, withdrawAmount);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("TimelockActive");
+    }
+  });
+
+  it("Can't lower the delay and withdraw in one transaction", async () => {
+    const adminAccounts = { admin: admin.publicKey, config: configPda } as any;
+    const tx = new anchor.web3.Transaction().add(
+      await program.methods.resumeBridge().accounts(adminAccounts).instruction(),
+      await program.methods
+        .setEmergencyWithdrawDelay(new anchor.BN(0))
+        .accounts(adminAccounts)
+        .instruction(),
+      await program.methods.pauseBridge().accounts(adminAccounts).instruction(),
+      await program.methods
+        .emergencyWithdraw(withdrawAmount)
+        .accounts({
+          admin: admin.publicKey,
+          config: configPda,
+          vaultAuthority: vaultAuthorityPda,
+          tokenVault: tokenVaultPda,
+          destinationTokenAccount: treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .instruction()
+    );
+
+    try {
+      await provider.sendAndConfirm(tx, [admin]);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      // raw transactions aren't translated, the error code is in the logs
+      expect(String(err.logs ?? err)).to.include("TimelockActive");
+    }
+  });
+
+  it("Raises the delay right away", async () => {
+    await resume();
+    await setDelay(7200);
+
+    const config = await program.account.bridgeConfig.fetch(configPda);
+    expect(config.emergencyWithdrawDelay.toNumber()).to.equal(7200);
+    expect(config.pendingDelayEffectiveAt.toNumber()).to.equal(0);
+  });
+});
@@ -0,0 +1,320 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { BridgingSolana } from "../target/types/bridging_solana";
+import {
+  Keypair,
+  PublicKey,
+  Secp256k1Program,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+} from "@solana/web3.js";
+import {
+  createAssociatedTokenAccountInstruction,
+  createInitializeMintInstruction,
+  createMintToInstruction,
+  getAccount,
+  getAssociatedTokenAddress,
+  getMinimumBalanceForRentExemptMint,
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { secp256k1 } from "@noble/curves/secp256k1";
+import { expect } from "chai";
+
+describe("evm signatures", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider();
+  const program = anchor.workspace.BridgingSolana as Program<BridgingSolana>;
+
+  let admin: Keypair;
+  let tokenMint: Keypair;
+  let relayer: Keypair;
+  let recipient: Keypair;
+  let configPda: PublicKey;
+  let guardianSetPda: PublicKey;
+  let validatorSetPda: PublicKey;
+  let vaultAuthorityPda: PublicKey;
+  let tokenVaultPda: PublicKey;
+  let recipientTokenAccount: PublicKey;
+
+  const destinationChainId = new anchor.BN(1);
+  const destinationBridge = Buffer.from(
+    "0x1234567890123456789012345678901234567890".slice(2),
+    "hex"
+  );
+  const mintDecimals = 9;
+  const unlockAmount = new anchor.BN(500 * 10 ** mintDecimals);
+  const srcChainId = new anchor.BN(1);
+  const dstChainId = new anchor.BN(900);
+
+  // EVM validator keys, two of the three have to sign
+  const validatorKeys = [0, 1, 2].map(() => secp256k1.utils.randomPrivateKey());
+  const outsiderKey = secp256k1.utils.randomPrivateKey();
+  const ethAddressOf = (privateKey: Uint8Array) =>
+    Secp256k1Program.publicKeyToEthAddress(
+      secp256k1.getPublicKey(privateKey, false).slice(1)
+    );
+
+  // the BurnedToSolana payload, its keccak256 is the EVM messageId
+  const payloadFor = (n: anchor.BN) =>
+    Buffer.concat([
+      srcChainId.toArrayLike(Buffer, "be", 32),
+      dstChainId.toArrayLike(Buffer, "be", 32),
+      configPda.toBuffer(),
+      n.toArrayLike(Buffer, "be", 32),
+      unlockAmount.toArrayLike(Buffer, "be", 32),
+      recipient.publicKey.toBuffer(),
+    ]);
+
+  const unlock = (n: anchor.BN, signers: Uint8Array[]) => {
+    const payload = payloadFor(n);
+    const messageId = Buffer.from(keccak_256(payload));
+
+    return program.methods
+      .unlockFromEvm(
+        srcChainId,
+        dstChainId,
+        n,
+        unlockAmount,
+        Array.from(messageId)
+      )
+      .accounts({
+        relayer: relayer.publicKey,
+        config: configPda,
+        guardianSet: guardianSetPda,
+        validatorSet: validatorSetPda,
+        processedMessage: PublicKey.findProgramAddressSync(
+          [Buffer.from("processed"), configPda.toBuffer(), messageId],
+          program.programId
+        )[0],
+        vaultAuthority: vaultAuthorityPda,
+        tokenVault: tokenVaultPda,
+        recipientTokenAccount: recipientTokenAccount,
+        recipient: recipient.publicKey,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .preInstructions(
+        signers.map((privateKey) =>
+          Secp256k1Program.createInstructionWithPrivateKey({
+            privateKey,
+            message: payload,
+          })
+        )
+      )
+      .signers([relayer])
+      .rpc();
+  };
+
+  before(async () => {
+    admin = Keypair.generate();
+    tokenMint = Keypair.generate();
+    relayer = Keypair.generate();
+    recipient = Keypair.generate();
+
+    for (const wallet of [admin, relayer]) {
+      const airdrop = await provider.connection.requestAirdrop(
+        wallet.publicKey,
+        2 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    }
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bridge"), tokenMint.publicKey.toBuffer()],
+      program.programId
+    );
+
+    [guardianSetPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("guardians"), configPda.toBuffer()],
+      program.programId
+    );
+
+    [validatorSetPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("validators"), configPda.toBuffer()],
+      program.programId
+    );
+
+    [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), configPda.toBuffer()],
+      program.programId
+    );
+
+    tokenVaultPda = await getAssociatedTokenAddress(
+      tokenMint.publicKey,
+      vaultAuthorityPda,
+      true
+    );
+
+    recipientTokenAccount = await getAssociatedTokenAddress(
+      tokenMint.publicKey,
+      recipient.publicKey
+    );
+
+    const mintRent = await getMinimumBalanceForRentExemptMint(
+      provider.connection
+    );
+
+    const createMintTx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: admin.publicKey,
+        lamports: mintRent,
+        newAccountPubkey: tokenMint.publicKey,
+        space: MINT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMintInstruction(
+        tokenMint.publicKey,
+        mintDecimals,
+        admin.publicKey,
+        null
+      )
+    );
+
+    await provider.sendAndConfirm(createMintTx, [admin, tokenMint]);
+
+    await program.methods
+      .initialize(
+        dstChainId,
+        destinationChainId,
+        Array.from(destinationBridge),
+        relayer.publicKey
+      )
+      .accountsPartial({
+        admin: admin.publicKey,
+        tokenMint: tokenMint.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+
+    const fundTx = new anchor.web3.Transaction().add(
+      createMintToInstruction(
+        tokenMint.publicKey,
+        tokenVaultPda,
+        admin.publicKey,
+        unlockAmount.toNumber() * 2,
+        [],
+        TOKEN_PROGRAM_ID
+      ),
+      createAssociatedTokenAccountInstruction(
+        admin.publicKey,
+        recipientTokenAccount,
+        recipient.publicKey,
+        tokenMint.publicKey
+      )
+    );
+
+    await provider.sendAndConfirm(fundTx, [admin]);
+  });
+
+  it("Fails to switch to evm signatures without a validator set", async () => {
+    try {
+      await program.methods
+        .setTrustMode({ evmSignatures: {} })
+        .accounts({
+          admin: admin.publicKey,
+          config: configPda,
+          guardianSet: guardianSetPda,
+          validatorSet: null,
+        } as any)
+        .signers([admin])
+        .rpc();
+
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("MissingValidatorSet");
+    }
+  });
+
+  it("Fails when a non admin sets the validators", async () => {
+    try {
+      await program.methods
+        .setEvmValidators([Array.from(ethAddressOf(outsiderKey))], 1)
+        .accounts({
+          admin: relayer.publicKey,
+          config: configPda,
+          validatorSet: validatorSetPda,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([relayer])
+        .rpc();
+
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("UnauthorizedAdmin");
+    }
+  });
+
+  it("Sets the validators and switches to evm signatures", async () => {
+    await program.methods
+      .setEvmValidators(
+        validatorKeys.map((key) => Array.from(ethAddressOf(key))),
+        2
+      )
+      .accounts({
+        admin: admin.publicKey,
+        config: configPda,
+        validatorSet: validatorSetPda,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([admin])
+      .rpc();
+
+    await program.methods
+      .setTrustMode({ evmSignatures: {} })
+      .accounts({
+        admin: admin.publicKey,
+        config: configPda,
+        guardianSet: guardianSetPda,
+        validatorSet: validatorSetPda,
+      } as any)
+      .signers([admin])
+      .rpc();
+
+    const validatorSet = await program.account.evmValidatorSet.fetch(
+      validatorSetPda
+    );
+    expect(validatorSet.threshold).to.equal(2);
+    expect(validatorSet.validators.length).to.equal(3);
+
+    const guardianSet = await program.account.guardianSet.fetch(
+      guardianSetPda
+    );
+    expect(guardianSet.trustMode).to.deep.equal({ evmSignatures: {} });
+  });
+
+  it("Fails when the relayer alone submits the unlock", async () => {
+    try {
+      await unlock(new anchor.BN(1), []);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("InsufficientSignatures");
+    }
+  });
+
+  it("Does not count signatures from outside the validator set", async () => {
+    try {
+      await unlock(new anchor.BN(2), [validatorKeys[0], outsiderKey]);
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err.toString()).to.include("InsufficientSignatures");
+    }
+  });
+
+  it("Unlocks once enough validators signed", async () => {
+    const recipientBefore = Number(
+      (await getAccount(provider.connection, recipientTokenAccount)).amount
+    );
+
+    await unlock(new anchor.BN(3), [validatorKeys[0], validatorKeys[2]]);
+
+    const recipientAfter = Number(
+      (await getAccount(provider.connection, recipientTokenAccount)).amount
+    );
+    expect(recipientAfter).to.equal(
+      recipientBefore + unlockAmount.toNumber()
+    );
+  });
+});
@@ -26,6 +26,8 @@ describe("bridging-solana", () => {
   let tokenVaultPda: PublicKey;
 
   // Test parameters
+  // this chain, as the EVM side names it
+  const chainId = new anchor.BN(900);
   const destinationChainId = new anchor.BN(1);
   const destinationBridge = Buffer.from(
     "0x1234567890123456789012345678901234567890".slice(2),
@@ -90,6 +92,7 @@ describe("bridging-solana", () => {
 
       const tx = await program.methods
         .initialize(
+          chainId,
           destinationChainId,
           Array.from(destinationBridge),
           relayer.publicKey
@@ -112,6 +115,7 @@ describe("bridging-solana", () => {
         tokenMint.publicKey.toString()
       );
       expect(configAccount.nonce.toNumber()).to.equal(0);
+      expect(configAccount.chainId.toNumber()).to.equal(chainId.toNumber());
       expect(configAccount.destinationChainId.toNumber()).to.equal(
         destinationChainId.toNumber()
       );
@@ -125,6 +129,19 @@ describe("bridging-solana", () => {
       expect(configAccount.vaultAuthorityBump).to.be.greaterThan(0);
       expect(configAccount.vaultAuthorityBump).to.be.lessThan(256);
 
+      const [guardianSetPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("guardians"), configPda.toBuffer()],
+        program.programId
+      );
+      const guardianSet = await program.account.guardianSet.fetch(
+        guardianSetPda
+      );
+      expect(guardianSet.index).to.equal(0);
+      expect(guardianSet.threshold).to.equal(1);
+      expect(guardianSet.guardians.map((g) => g.toString())).to.deep.equal([
+        relayer.publicKey.toString(),
+      ]);
+
       console.log(" Initialize test passed!");
       console.log("   Admin:", configAccount.admin.toString());
       console.log("   Token Mint:", configAccount.tokenMint.toString());
@@ -137,6 +154,7 @@ describe("bridging-solana", () => {
       try {
         await program.methods
           .initialize(
+            chainId,
             destinationChainId,
             Array.from(destinationBridge),
             relayer.publicKey
@@ -201,6 +219,7 @@ describe("bridging-solana", () => {
       // Initialize with new mint
       const tx = await program.methods
         .initialize(
+          chainId,
           destinationChainId,
           Array.from(destinationBridge),
           relayer.publicKey
@@ -269,6 +288,7 @@ describe("bridging-solana", () => {
 
       const tx = await program.methods
         .initialize(
+          chainId,
           polygonChainId,
           Array.from(destinationBridge),
           relayer.publicKey
@@ -37,6 +37,8 @@ describe("lock_tokens", () => {
   let userTokenAccount: PublicKey;
 
   // Test parameters
+  // this chain, as the EVM side names it
+  const chainId = new anchor.BN(900);
   const destinationChainId = new anchor.BN(1);
   const destinationBridge = Buffer.from(
     "0x1234567890123456789012345678901234567890".slice(2),
@@ -109,6 +111,7 @@ describe("lock_tokens", () => {
 
     await program.methods
       .initialize(
+        chainId,
         destinationChainId,
         Array.from(destinationBridge),
         relayer.publicKey
@@ -30,6 +30,8 @@ describe("pause and resume", () => {
   let tokenVaultPda: PublicKey;
   let userTokenAccount: PublicKey;
 
+  // this chain, as the EVM side names it
+  const chainId = new anchor.BN(900);
   const destinationChainId = new anchor.BN(1);
   const destinationBridge = Buffer.from(
     "0x1234567890123456789012345678901234567890".slice(2),
@@ -107,7 +109,12 @@ describe("pause and resume", () => {
 
     // Initialize bridge
     await program.methods
-      .initialize(destinationChainId, Array.from(destinationBridge), relayer.publicKey)
+      .initialize(
+        chainId,
+        destinationChainId,
+        Array.from(destinationBridge),
+        relayer.publicKey
+      )
       .accountsPartial({
         admin: admin.publicKey,
         tokenMint: tokenMint.publicKey,
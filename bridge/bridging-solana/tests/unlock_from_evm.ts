@@ -1,7 +1,12 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { BridgingSolana } from "../target/types/bridging_solana";
-import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+} from "@solana/web3.js";
 import {
   createAssociatedTokenAccountInstruction,
   createInitializeMintInstruction,
@@ -13,6 +18,7 @@ import {
   MINT_SIZE,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
+import { keccak_256 } from "@noble/hashes/sha3";
 import { expect } from "chai";
 
 describe("unlock from evm", () => {
@@ -26,6 +32,7 @@ describe("unlock from evm", () => {
   let unauthorizedRelayer: Keypair;
   let recipient: Keypair;
   let configPda: PublicKey;
+  let guardianSetPda: PublicKey;
   let vaultAuthorityPda: PublicKey;
   let tokenVaultPda: PublicKey;
   let recipientTokenAccount: PublicKey;
@@ -38,8 +45,34 @@ describe("unlock from evm", () => {
   const mintDecimals = 9;
   const unlockAmount = new anchor.BN(500 * 10 ** mintDecimals);
   const srcChainId = new anchor.BN(1);
+  const dstChainId = new anchor.BN(900);
   const nonce = new anchor.BN(42);
 
+  // EvmBridge.burnWrapped's messageId, abi.encode pads every field to 32 bytes
+  const messageIdFor = (n: anchor.BN, amount = unlockAmount) =>
+    Buffer.from(
+      keccak_256(
+        Buffer.concat([
+          srcChainId.toArrayLike(Buffer, "be", 32),
+          dstChainId.toArrayLike(Buffer, "be", 32),
+          configPda.toBuffer(),
+          n.toArrayLike(Buffer, "be", 32),
+          amount.toArrayLike(Buffer, "be", 32),
+          recipient.publicKey.toBuffer(),
+        ])
+      )
+    );
+
+  const processedMessageFor = (n: anchor.BN, amount = unlockAmount) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("processed"),
+        configPda.toBuffer(),
+        messageIdFor(n, amount),
+      ],
+      program.programId
+    )[0];
+
   before(async () => {
     admin = Keypair.generate();
     tokenMint = Keypair.generate();
@@ -77,6 +110,11 @@ describe("unlock from evm", () => {
       program.programId
     );
 
+    [guardianSetPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("guardians"), configPda.toBuffer()],
+      program.programId
+    );
+
     [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("vault"), configPda.toBuffer()],
       program.programId
@@ -118,6 +156,7 @@ describe("unlock from evm", () => {
     // init bridge
     await program.methods
       .initialize(
+        dstChainId,
         destinationChainId,
         Array.from(destinationBridge),
         relayer.publicKey
@@ -162,14 +201,7 @@ describe("unlock from evm", () => {
   });
 
   it("Successfull unlock tokens from evm bridge", async () => {
-    const processedMessagePda = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("processed"),
-        srcChainId.toArrayLike(Buffer, "le", 8),
-        nonce.toArrayLike(Buffer, "le", 8),
-      ],
-      program.programId
-    )[0];
+    const processedMessagePda = processedMessageFor(nonce);
 
     const vaultBefore = await getAccount(provider.connection, tokenVaultPda);
     const recipientBefore = await getAccount(
@@ -183,15 +215,24 @@ describe("unlock from evm", () => {
     // console.log(`Recipient balance before: ${recipientBalanceBefore}`);
 
     const tx = await program.methods
-      .unlockFromEvm(srcChainId, nonce, unlockAmount)
+      .unlockFromEvm(
+        srcChainId,
+        dstChainId,
+        nonce,
+        unlockAmount,
+        Array.from(messageIdFor(nonce))
+      )
       .accounts({
         relayer: relayer.publicKey,
         config: configPda,
+        guardianSet: guardianSetPda,
+        validatorSet: null,
         processedMessage: processedMessagePda,
         vaultAuthority: vaultAuthorityPda,
         tokenVault: tokenVaultPda,
         recipientTokenAccount: recipientTokenAccount,
         recipient: recipient.publicKey,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -204,6 +245,16 @@ describe("unlock from evm", () => {
       processedMessagePda
     );
     expect(processedMessage.executed).to.be.true;
+    expect(processedMessage.amount.toNumber()).to.equal(
+      unlockAmount.toNumber()
+    );
+    expect(processedMessage.recipient.toString()).to.equal(
+      recipient.publicKey.toString()
+    );
+    expect(Buffer.from(processedMessage.messageId)).to.deep.equal(
+      messageIdFor(nonce)
+    );
+    expect(processedMessage.executedAtSlot.toNumber()).to.be.greaterThan(0);
 
     const vaultAfter = await getAccount(provider.connection, tokenVaultPda);
     const recipientAfter = await getAccount(
@@ -225,23 +276,26 @@ describe("unlock from evm", () => {
   });
 
   it("Fails to unlock with the same nonce twice", async () => {
-    const processedMessagePda = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("processed"),
-        srcChainId.toArrayLike(Buffer, "le", 8),
-        nonce.toArrayLike(Buffer, "le", 8)
-      ], program.programId
-    )[0];
+    const processedMessagePda = processedMessageFor(nonce);
 
     try {
-      await program.methods.unlockFromEvm(srcChainId, nonce, unlockAmount).accounts({
+      await program.methods.unlockFromEvm(
+        srcChainId,
+        dstChainId,
+        nonce,
+        unlockAmount,
+        Array.from(messageIdFor(nonce))
+      ).accounts({
         relayer: relayer.publicKey,
         config: configPda,
+        guardianSet: guardianSetPda,
+        validatorSet: null,
         processedMessage: processedMessagePda,
         vaultAuthority: vaultAuthorityPda,
         tokenVault: tokenVaultPda,
         recipientTokenAccount: recipientTokenAccount,
         recipient: recipient.publicKey,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       }).signers([relayer]).rpc();
@@ -255,26 +309,28 @@ describe("unlock from evm", () => {
 
   it("Fails when unauthorized relayer tries to unlock", async () => {
     const newNonce = new anchor.BN(100);
-    const processedMessagePda = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("processed"),
-        srcChainId.toArrayLike(Buffer, "le", 8),
-        newNonce.toArrayLike(Buffer, "le", 8),
-      ],
-      program.programId
-    )[0];
+    const processedMessagePda = processedMessageFor(newNonce);
 
     try {
       await program.methods
-        .unlockFromEvm(srcChainId, newNonce, unlockAmount)
+        .unlockFromEvm(
+          srcChainId,
+          dstChainId,
+          newNonce,
+          unlockAmount,
+          Array.from(messageIdFor(newNonce))
+        )
         .accounts({
           relayer: unauthorizedRelayer.publicKey,
           config: configPda,
+          guardianSet: guardianSetPda,
+          validatorSet: null,
           processedMessage: processedMessagePda,
           vaultAuthority: vaultAuthorityPda,
           tokenVault: tokenVaultPda,
           recipientTokenAccount: recipientTokenAccount,
           recipient: recipient.publicKey,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         } as any)
@@ -288,6 +344,59 @@ describe("unlock from evm", () => {
     }
   });
 
+  it("Fails to unlock a burn meant for another chain", async () => {
+    const otherNonce = new anchor.BN(150);
+    const otherChainId = new anchor.BN(901);
+    // a burn the EVM bridge sent to another chain, signed over its real payload
+    const messageId = Buffer.from(
+      keccak_256(
+        Buffer.concat([
+          srcChainId.toArrayLike(Buffer, "be", 32),
+          otherChainId.toArrayLike(Buffer, "be", 32),
+          configPda.toBuffer(),
+          otherNonce.toArrayLike(Buffer, "be", 32),
+          unlockAmount.toArrayLike(Buffer, "be", 32),
+          recipient.publicKey.toBuffer(),
+        ])
+      )
+    );
+
+    try {
+      await program.methods
+        .unlockFromEvm(
+          srcChainId,
+          otherChainId,
+          otherNonce,
+          unlockAmount,
+          Array.from(messageId)
+        )
+        .accounts({
+          relayer: relayer.publicKey,
+          config: configPda,
+          guardianSet: guardianSetPda,
+          validatorSet: null,
+          processedMessage: PublicKey.findProgramAddressSync(
+            [Buffer.from("processed"), configPda.toBuffer(), messageId],
+            program.programId
+          )[0],
+          vaultAuthority: vaultAuthorityPda,
+          tokenVault: tokenVaultPda,
+          recipientTokenAccount: recipientTokenAccount,
+          recipient: recipient.publicKey,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([relayer])
+        .rpc();
+
+      expect.fail("Should have thrown an error");
+    } catch (err) {
+      expect(err).to.be.instanceOf(Error);
+      expect(err.toString()).to.include("InvalidDestinationChain");
+    }
+  });
+
   it("Fails to unlock when bridge is paused", async () => {
     // Pause the bridge first
     await program.methods
@@ -300,26 +409,28 @@ describe("unlock from evm", () => {
       .rpc();
 
     const pausedNonce = new anchor.BN(200);
-    const processedMessagePda = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("processed"),
-        srcChainId.toArrayLike(Buffer, "le", 8),
-        pausedNonce.toArrayLike(Buffer, "le", 8),
-      ],
-      program.programId
-    )[0];
+    const processedMessagePda = processedMessageFor(pausedNonce);
 
     try {
       await program.methods
-        .unlockFromEvm(srcChainId, pausedNonce, unlockAmount)
+        .unlockFromEvm(
+          srcChainId,
+          dstChainId,
+          pausedNonce,
+          unlockAmount,
+          Array.from(messageIdFor(pausedNonce))
+        )
         .accounts({
           relayer: relayer.publicKey,
           config: configPda,
+          guardianSet: guardianSetPda,
+          validatorSet: null,
           processedMessage: processedMessagePda,
           vaultAuthority: vaultAuthorityPda,
           tokenVault: tokenVaultPda,
           recipientTokenAccount: recipientTokenAccount,
           recipient: recipient.publicKey,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         } as any)
@@ -343,5 +454,206 @@ describe("unlock from evm", () => {
       .rpc();
   });
 
-});
+  describe("with a 2 of 3 guardian set", () => {
+    let guardians: Keypair[];
+
+    const attest = (
+      guardian: Keypair,
+      n: anchor.BN,
+      amount = unlockAmount,
+      messageId = messageIdFor(n, amount)
+    ) =>
+      program.methods
+        .unlockFromEvm(srcChainId, dstChainId, n, amount, Array.from(messageId))
+        .accounts({
+          relayer: guardian.publicKey,
+          config: configPda,
+          guardianSet: guardianSetPda,
+          validatorSet: null,
+          processedMessage: PublicKey.findProgramAddressSync(
+            [Buffer.from("processed"), configPda.toBuffer(), messageId],
+            program.programId
+          )[0],
+          vaultAuthority: vaultAuthorityPda,
+          tokenVault: tokenVaultPda,
+          recipientTokenAccount: recipientTokenAccount,
+          recipient: recipient.publicKey,
+          instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([guardian])
+        .rpc();
+
+    before(async () => {
+      guardians = [relayer, Keypair.generate(), Keypair.generate()];
+      for (const guardian of guardians.slice(1)) {
+        const airdrop = await provider.connection.requestAirdrop(
+          guardian.publicKey,
+          2 * anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(airdrop);
+      }
+    });
+
+    it("Fails when a non admin rotates the guardian set", async () => {
+      try {
+        await program.methods
+          .setGuardians([unauthorizedRelayer.publicKey], 1)
+          .accounts({
+            admin: unauthorizedRelayer.publicKey,
+            config: configPda,
+            guardianSet: guardianSetPda,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .signers([unauthorizedRelayer])
+          .rpc();
+
+        expect.fail("Should have thrown an error");
+      } catch (err) {
+        expect(err.toString()).to.include("UnauthorizedAdmin");
+      }
+    });
+
+    it("Fails to rotate to a threshold above the set size", async () => {
+      try {
+        await program.methods
+          .setGuardians(
+            guardians.map((g) => g.publicKey),
+            4
+          )
+          .accounts({
+            admin: admin.publicKey,
+            config: configPda,
+            guardianSet: guardianSetPda,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .signers([admin])
+          .rpc();
+
+        expect.fail("Should have thrown an error");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidThreshold");
+      }
+    });
+
+    it("Fails to rotate to a set with duplicate guardians", async () => {
+      try {
+        await program.methods
+          .setGuardians([relayer.publicKey, relayer.publicKey], 1)
+          .accounts({
+            admin: admin.publicKey,
+            config: configPda,
+            guardianSet: guardianSetPda,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .signers([admin])
+          .rpc();
+
+        expect.fail("Should have thrown an error");
+      } catch (err) {
+        expect(err.toString()).to.include("InvalidGuardianSet");
+      }
+    });
+
+    it("Rotates the guardian set", async () => {
+      await program.methods
+        .setGuardians(
+          guardians.map((g) => g.publicKey),
+          2
+        )
+        .accounts({
+          admin: admin.publicKey,
+          config: configPda,
+          guardianSet: guardianSetPda,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([admin])
+        .rpc();
+
+      const guardianSet = await program.account.guardianSet.fetch(
+        guardianSetPda
+      );
+      expect(guardianSet.index).to.equal(1);
+      expect(guardianSet.threshold).to.equal(2);
+      expect(guardianSet.guardians.length).to.equal(3);
+    });
+
+    it("Only unlocks once the threshold is reached", async () => {
+      const pendingNonce = new anchor.BN(300);
+      const recipientBefore = Number(
+        (await getAccount(provider.connection, recipientTokenAccount)).amount
+      );
 
+      await attest(guardians[0], pendingNonce);
+
+      let message = await program.account.processedMessage.fetch(
+        processedMessageFor(pendingNonce)
+      );
+      expect(message.executed).to.be.false;
+      expect(message.attestations.length).to.equal(1);
+      expect(
+        Number(
+          (await getAccount(provider.connection, recipientTokenAccount)).amount
+        )
+      ).to.equal(recipientBefore);
+
+      await attest(guardians[1], pendingNonce);
+
+      message = await program.account.processedMessage.fetch(
+        processedMessageFor(pendingNonce)
+      );
+      expect(message.executed).to.be.true;
+      expect(
+        Number(
+          (await getAccount(provider.connection, recipientTokenAccount)).amount
+        )
+      ).to.equal(recipientBefore + unlockAmount.toNumber());
+    });
+
+    it("Fails when a guardian attests twice", async () => {
+      const pendingNonce = new anchor.BN(301);
+      await attest(guardians[0], pendingNonce);
+
+      try {
+        await attest(guardians[0], pendingNonce);
+        expect.fail("Should have thrown an error");
+      } catch (err) {
+        expect(err.toString()).to.include("AlreadyAttested");
+      }
+    });
+
+    it("Keeps attestations for different amounts apart", async () => {
+      const pendingNonce = new anchor.BN(302);
+      const doubled = unlockAmount.muln(2);
+      await attest(guardians[0], pendingNonce);
+      await attest(guardians[1], pendingNonce, doubled);
+
+      const message = await program.account.processedMessage.fetch(
+        processedMessageFor(pendingNonce)
+      );
+      const doubledMessage = await program.account.processedMessage.fetch(
+        processedMessageFor(pendingNonce, doubled)
+      );
+      expect(message.executed).to.be.false;
+      expect(doubledMessage.executed).to.be.false;
+      expect(doubledMessage.amount.toString()).to.equal(doubled.toString());
+    });
+
+    it("Fails when the message id does not match the message", async () => {
+      const pendingNonce = new anchor.BN(303);
+
+      try {
+        await attest(
+          guardians[0],
+          pendingNonce,
+          unlockAmount.muln(2),
+          messageIdFor(pendingNonce)
+        );
+        expect.fail("Should have thrown an error");
+      } catch (err) {
+        expect(err.toString()).to.include("MessageMismatch");
+      }
+    });
+  });
+});
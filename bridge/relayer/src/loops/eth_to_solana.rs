@@ -22,8 +22,9 @@ use crate::abis::EVM_BRIDGE_ABI;
 
 declare_program!(bridging_solana);
 
-use bridging_solana::accounts::BridgeConfig;
+use bridging_solana::accounts::{BridgeConfig, GuardianSet};
 use bridging_solana::client::args::UnlockFromEvm as UnlockFromEvmArgs;
+use bridging_solana::types::TrustMode;
 
 pub async fn eth_to_solana_loop() -> Result<()> {
     let evm_rpc_url = std::env::var("EVM_RPC_URL").expect("EVM_RPC_URL missing");
@@ -151,16 +152,22 @@ pub async fn submit_unlock_to_solana(
     let config = BridgeConfig::try_deserialize(&mut config_data)
         .map_err(|e| anyhow!("Failed to deserialize BridgeConfig: {:?}", e))?;
 
-    let expected_dst_chain_id: u64 = std::env::var("SOLANA_CHAIN_ID")
-        .unwrap_or_else(|_| "0".to_string())
-        .parse()
-        .unwrap_or(0);
-
-    if ev.dst_chain_id != expected_dst_chain_id {
+    // unlock_from_evm only accepts burns for the chain id stored in the config
+    if ev.dst_chain_id != config.chain_id {
         return Err(anyhow!(
-            "Destination chain ID mismatch: event={}, expected={}",
+            "Destination chain ID mismatch: event={}, bridge chain={}",
             ev.dst_chain_id,
-            expected_dst_chain_id
+            config.chain_id
+        ));
+    }
+
+    // unlock_from_evm only accepts burns from the configured destination,
+    // which set_destination can change
+    if ev.src_chain_id != config.destination_chain_id {
+        return Err(anyhow!(
+            "Source chain ID mismatch: event={}, bridge destination={}",
+            ev.src_chain_id,
+            config.destination_chain_id
         ));
     }
 
@@ -189,11 +196,44 @@ pub async fn submit_unlock_to_solana(
     let (vault_authority, _vault_bump) =
         SolanaPubkey::find_program_address(&[b"vault", config_pubkey.as_ref()], &program_id);
 
-    let processed_seeds = &[
-        b"processed",
-        &ev.src_chain_id.to_le_bytes()[..],
-        &ev.nonce.to_le_bytes()[..],
-    ];
+    // every guardian runs its own relayer, the unlock goes through once
+    // enough of them attested the same event
+    let (guardian_set, _guardian_bump) =
+        SolanaPubkey::find_program_address(&[b"guardians", config_pubkey.as_ref()], &program_id);
+
+    let guardian_set_account = client.get_account(&guardian_set)?;
+    let mut guardian_set_data: &[u8] = &guardian_set_account.data;
+    let guardian_set_state = GuardianSet::try_deserialize(&mut guardian_set_data)
+        .map_err(|e| anyhow!("Failed to deserialize GuardianSet: {:?}", e))?;
+
+    // set_relayer hands the seat to a new key, a rotated out relayer would
+    // only burn fees on Unauthorized
+    let payer_pubkey = payer.pubkey();
+    if !guardian_set_state
+        .guardians
+        .iter()
+        .any(|guardian| guardian.to_bytes() == payer_pubkey.to_bytes())
+    {
+        return Err(anyhow!(
+            "Relayer key {} is not in the guardian set (current relayer: {})",
+            payer_pubkey,
+            config.relayer_pubkey
+        ));
+    }
+
+    // in EVM signature mode the transaction has to carry secp256k1
+    // instructions signed by the EVM validators, which this relayer doesn't
+    // collect
+    if matches!(guardian_set_state.trust_mode, TrustMode::EvmSignatures) {
+        return Err(anyhow!(
+            "Bridge is in EVM signature mode, unlock needs the EVM validators' signatures"
+        ));
+    }
+
+    // the program recomputes the message id from the event fields and
+    // rejects the unlock if they don't match
+    let message_id: [u8; 32] = ev.message_id.to_fixed_bytes();
+    let processed_seeds = &[b"processed", config_pubkey.as_ref(), &message_id[..]];
     let (processed_message_pda, _bump) =
         SolanaPubkey::find_program_address(processed_seeds, &program_id);
 
@@ -205,11 +245,19 @@ pub async fn submit_unlock_to_solana(
     let accounts = vec![
         AccountMeta::new(payer.pubkey(), true),
         AccountMeta::new_readonly(config_pubkey, false),
+        AccountMeta::new_readonly(guardian_set, false),
+        // the program id stands in for the validator set, only used in EVM signature mode
+        AccountMeta::new_readonly(program_id, false),
         AccountMeta::new(processed_message_pda, false),
         AccountMeta::new_readonly(vault_authority, false),
         AccountMeta::new(token_vault, false),
         AccountMeta::new(recipient_token_account, false),
         AccountMeta::new_readonly(sol_recipient, false),
+        AccountMeta::new_readonly(
+            SolanaPubkey::from_str("Sysvar1nstructions1111111111111111111111111")
+                .map_err(|_| anyhow!("Invalid instructions sysvar address"))?,
+            false,
+        ),
         AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
         AccountMeta::new_readonly(
             SolanaPubkey::from_str("11111111111111111111111111111111")
@@ -220,8 +268,10 @@ pub async fn submit_unlock_to_solana(
 
     let instruction_data = UnlockFromEvmArgs {
         src_chain_id: ev.src_chain_id,
+        dst_chain_id: ev.dst_chain_id,
         nonce: ev.nonce,
         amount: amount_u64,
+        message_id,
     };
     let data = instruction_data.data();
 
@@ -238,7 +288,7 @@ pub async fn submit_unlock_to_solana(
     let signature = client.send_and_confirm_transaction(&transaction)?;
 
     println!(
-        "Successfully unlocked {} tokens to {} on Solana (tx: {})",
+        "Attested unlock of {} tokens to {} on Solana (tx: {})",
         amount_u64, sol_recipient, signature
     );
 
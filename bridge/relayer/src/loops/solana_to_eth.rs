@@ -57,6 +57,10 @@ async fn process_new_locks(rpc: &RpcClient, last_processed_nonce: &mut u64) -> R
     let config = BridgeConfig::try_deserialize(&mut config_account_data)
         .map_err(|e| anyhow!("Failed to deserialize BridgeConfig: {:?}", e))?;
 
+    // set_destination can move the bridge to another EVM contract, don't
+    // relay locks to the old one
+    check_destination(&config)?;
+
     let current_nonce = config.nonce;
 
     if current_nonce == *last_processed_nonce {
@@ -97,6 +101,23 @@ async fn process_new_locks(rpc: &RpcClient, last_processed_nonce: &mut u64) -> R
     Ok(())
 }
 
+fn check_destination(config: &BridgeConfig) -> Result<()> {
+    let bridge_address: EvmAddress = std::env::var("EVM_BRIDGE_ADDRESS")?.parse()?;
+    let chain_id: u64 = std::env::var("EVM_CHAIN_ID")?.parse()?;
+
+    if config.destination_bridge != bridge_address.to_fixed_bytes()
+        || config.destination_chain_id != chain_id
+    {
+        return Err(anyhow!(
+            "Bridge destination changed to chain {} / {}, update EVM_CHAIN_ID and EVM_BRIDGE_ADDRESS",
+            config.destination_chain_id,
+            EvmAddress::from(config.destination_bridge)
+        ));
+    }
+
+    Ok(())
+}
+
 #[derive(Debug)]
 struct BridgeMessage {
     pub src_chain_id: u64,